@@ -0,0 +1,8 @@
+ALTER TABLE timers ADD COLUMN `end_time` DATETIME DEFAULT NULL;
+ALTER TABLE timers ADD COLUMN `work_seconds` INT UNSIGNED DEFAULT NULL;
+ALTER TABLE timers ADD COLUMN `break_seconds` INT UNSIGNED DEFAULT NULL;
+ALTER TABLE timers ADD COLUMN `cycles` INT UNSIGNED NOT NULL DEFAULT 1;
+
+ALTER TABLE reminders ADD COLUMN `timer_id` INT UNSIGNED DEFAULT NULL;
+ALTER TABLE reminders ADD CONSTRAINT `reminders_timer_id_fk`
+    FOREIGN KEY (`timer_id`) REFERENCES timers (`id`) ON DELETE CASCADE;
@@ -0,0 +1,7 @@
+ALTER TABLE timers ADD COLUMN `channel_id` INT UNSIGNED DEFAULT NULL;
+ALTER TABLE timers ADD COLUMN `stopped_at` DATETIME DEFAULT NULL;
+ALTER TABLE timers ADD CONSTRAINT `timers_channel_id_fk`
+    FOREIGN KEY (`channel_id`) REFERENCES channels (`id`) ON DELETE CASCADE;
+
+ALTER TABLE guilds ADD COLUMN `timer_manager_role` BIGINT UNSIGNED DEFAULT NULL;
+ALTER TABLE guilds ADD COLUMN `max_timers` INT UNSIGNED NOT NULL DEFAULT 25;
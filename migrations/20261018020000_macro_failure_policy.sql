@@ -0,0 +1 @@
+ALTER TABLE macro ADD COLUMN abort_on_failure BOOL NOT NULL DEFAULT FALSE;
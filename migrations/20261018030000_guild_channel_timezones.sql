@@ -0,0 +1,6 @@
+-- Guild timezones were never settable, so clear the 'UTC' default so that they don't take
+-- precedence over user timezones
+ALTER TABLE guilds MODIFY COLUMN `timezone` VARCHAR(32) DEFAULT NULL;
+UPDATE guilds SET `timezone` = NULL;
+
+ALTER TABLE channels ADD COLUMN `timezone` VARCHAR(32) DEFAULT NULL;
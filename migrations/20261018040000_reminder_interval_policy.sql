@@ -0,0 +1,8 @@
+-- 'wall_clock' repeats at the same local time across daylight saving changes, 'elapsed' repeats
+-- after exactly the interval has passed
+ALTER TABLE reminders ADD COLUMN `interval_policy` ENUM('wall_clock', 'elapsed') NOT NULL DEFAULT 'wall_clock';
+-- The local time a reminder was due at, when it fell in a skipped hour and was moved forward
+ALTER TABLE reminders ADD COLUMN `shifted_from` DATETIME DEFAULT NULL;
+
+-- Intervals that aren't whole days were previously always measured as elapsed time
+UPDATE reminders SET `interval_policy` = 'elapsed' WHERE `interval_seconds` % 86400 != 0;
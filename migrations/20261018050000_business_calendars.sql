@@ -0,0 +1,20 @@
+-- Days of the week that aren't working days, as a bitmask from Monday (1) to Sunday (64).
+-- Defaults to Saturday and Sunday
+ALTER TABLE guilds ADD COLUMN `weekend_days` TINYINT UNSIGNED NOT NULL DEFAULT 96;
+
+CREATE TABLE holidays (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `guild_id` INT UNSIGNED NOT NULL,
+    `date` DATE NOT NULL,
+    `name` VARCHAR(100) NOT NULL DEFAULT '',
+
+    PRIMARY KEY (id),
+    UNIQUE (`guild_id`, `date`),
+
+    FOREIGN KEY (`guild_id`) REFERENCES guilds (`id`) ON DELETE CASCADE
+);
+
+-- What to do when a repeating reminder falls on a weekend day or holiday: send it anyway, skip
+-- the occurrence, or move it to the next or previous working day
+ALTER TABLE reminders ADD COLUMN `business_day_policy` ENUM('ignore', 'skip', 'next', 'previous') NOT NULL DEFAULT 'ignore';
@@ -0,0 +1,18 @@
+-- Secret links to read-only calendar feeds of upcoming reminders, for either a guild or a user.
+-- Deleting the row revokes the link
+CREATE TABLE calendar_feeds (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `token` CHAR(64) NOT NULL,
+    `guild_id` INT UNSIGNED DEFAULT NULL,
+    `user_id` INT UNSIGNED DEFAULT NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (id),
+    UNIQUE (`token`),
+    UNIQUE (`guild_id`),
+    UNIQUE (`user_id`),
+
+    FOREIGN KEY (`guild_id`) REFERENCES guilds (`id`) ON DELETE CASCADE,
+    FOREIGN KEY (`user_id`) REFERENCES users (`id`) ON DELETE CASCADE
+);
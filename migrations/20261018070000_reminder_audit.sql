@@ -0,0 +1,22 @@
+-- A record of each reminder created, edited or deleted by a user. Rows outlive the reminder they
+-- describe, so the reminder is kept by UID and name rather than by foreign key.
+-- `changes` maps each changed field to its old and new value, e.g.
+-- {"utc_time": {"old": "2023-01-01T09:00:00", "new": "2023-01-01T10:00:00"}}
+CREATE TABLE reminder_audit (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `guild_id` INT UNSIGNED DEFAULT NULL,
+    `reminder_uid` VARCHAR(64) NOT NULL,
+    `reminder_name` VARCHAR(100) NOT NULL DEFAULT '',
+    `actor` BIGINT UNSIGNED NOT NULL,
+    `source` ENUM('command', 'dashboard', 'import', 'macro') NOT NULL,
+    `action` ENUM('create', 'edit', 'delete') NOT NULL,
+    `changes` JSON NOT NULL,
+    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (id),
+    INDEX (`guild_id`, `id`),
+    INDEX (`reminder_uid`),
+
+    FOREIGN KEY (`guild_id`) REFERENCES guilds (`id`) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Channel to post reminder activity to, and which activity to post as a bitmask of: created (1),
+-- edited (2), deleted (4), paused (8), offset (16), nudged (32) and removed when undeliverable (64)
+ALTER TABLE guilds ADD COLUMN `log_channel` BIGINT UNSIGNED DEFAULT NULL;
+ALTER TABLE guilds ADD COLUMN `log_events` SMALLINT UNSIGNED NOT NULL DEFAULT 127;
@@ -0,0 +1,41 @@
+-- Recurring windows in which reminders aren't sent, set for a whole guild or a single channel.
+-- Channel windows replace the guild's windows for that channel. Times are local to the channel's
+-- timezone, and a window whose end is not after its start runs into the following day. `days` is
+-- the days the window starts on, as a bitmask from Monday (1) to Sunday (64).
+-- `policy` is what happens to reminders due inside the window: dropped, delivered when the window
+-- ends, or listed together in one message when the window ends
+CREATE TABLE quiet_hours (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `guild_id` INT UNSIGNED DEFAULT NULL,
+    `channel_id` INT UNSIGNED DEFAULT NULL,
+    `days` TINYINT UNSIGNED NOT NULL DEFAULT 127,
+    `start_time` TIME NOT NULL,
+    `end_time` TIME NOT NULL,
+    `policy` ENUM('drop', 'defer', 'batch') NOT NULL DEFAULT 'defer',
+
+    PRIMARY KEY (id),
+
+    FOREIGN KEY (`guild_id`) REFERENCES guilds (`id`) ON DELETE CASCADE,
+    FOREIGN KEY (`channel_id`) REFERENCES channels (`id`) ON DELETE CASCADE
+);
+
+-- When a reminder held by quiet hours will be delivered. The reminder keeps its `utc_time` so that
+-- repeating reminders stay on their schedule
+ALTER TABLE reminders ADD COLUMN `deferred_until` DATETIME DEFAULT NULL;
+
+-- Reminders held by quiet hours with the batch policy, waiting to be listed together
+CREATE TABLE quiet_hours_batch (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `channel_id` INT UNSIGNED NOT NULL,
+    `name` VARCHAR(100) NOT NULL DEFAULT '',
+    `content` VARCHAR(2048) NOT NULL DEFAULT '',
+    `due_time` DATETIME NOT NULL,
+    `release_time` DATETIME NOT NULL,
+
+    PRIMARY KEY (id),
+    INDEX (`release_time`),
+
+    FOREIGN KEY (`channel_id`) REFERENCES channels (`id`) ON DELETE CASCADE
+);
@@ -0,0 +1,33 @@
+-- Reminder names are unique within a channel so that commands can refer to reminders by name.
+-- Existing duplicates keep the name of the oldest reminder, and the others have their ID appended.
+-- A renamed reminder could take a name that another reminder already has, so renaming repeats
+-- with a further number appended until every name is free, as the index can't be created otherwise
+CREATE PROCEDURE rename_duplicate_reminders()
+BEGIN
+    DECLARE attempt INT DEFAULT 0;
+
+    WHILE EXISTS (SELECT 1 FROM reminders GROUP BY channel_id, name HAVING COUNT(1) > 1) DO
+        UPDATE reminders
+        INNER JOIN (
+            SELECT
+                id,
+                ROW_NUMBER() OVER (PARTITION BY channel_id, name ORDER BY id) AS `position`,
+                CONCAT(' #', id, IF(attempt > 0, CONCAT('.', attempt), '')) AS suffix
+            FROM reminders
+        ) AS numbered ON numbered.id = reminders.id
+        LEFT JOIN (
+            SELECT DISTINCT channel_id, name FROM reminders
+        ) AS existing ON existing.channel_id = reminders.channel_id
+            AND existing.name = CONCAT(LEFT(reminders.name, 24 - LENGTH(numbered.suffix)), numbered.suffix)
+        SET reminders.name = CONCAT(LEFT(reminders.name, 24 - LENGTH(numbered.suffix)), numbered.suffix)
+        WHERE numbered.position > 1 AND existing.name IS NULL;
+
+        SET attempt = attempt + 1;
+    END WHILE;
+END;
+
+CALL rename_duplicate_reminders();
+
+DROP PROCEDURE rename_duplicate_reminders;
+
+ALTER TABLE reminders ADD UNIQUE INDEX `unique_name_in_channel` (`channel_id`, `name`);
@@ -0,0 +1,13 @@
+-- Display names and avatars saved in a guild, for reminders to be sent as with `/remind username:`
+CREATE TABLE persona (
+    `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+
+    `guild_id` INT UNSIGNED NOT NULL,
+    `name` VARCHAR(32) NOT NULL,
+    `avatar` VARCHAR(512) DEFAULT NULL,
+
+    PRIMARY KEY (id),
+    UNIQUE INDEX `unique_name_in_guild` (`guild_id`, `name`),
+
+    FOREIGN KEY (`guild_id`) REFERENCES guilds (`id`) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+-- When the postman last checked that a channel's webhook exists, and why a missing webhook couldn't
+-- be recreated, for the dashboard to show
+ALTER TABLE channels ADD COLUMN `webhook_checked_at` DATETIME DEFAULT NULL;
+ALTER TABLE channels ADD COLUMN `webhook_error` VARCHAR(255) DEFAULT NULL;
+
+-- Missing webhooks that couldn't be recreated (128) are posted to log channels by default. Guilds
+-- that chose their own events are left as they are
+ALTER TABLE guilds ALTER COLUMN `log_events` SET DEFAULT 255;
+UPDATE guilds SET log_events = 255 WHERE log_events = 127;
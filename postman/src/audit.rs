@@ -0,0 +1,293 @@
+//! Recording changes to reminders in `reminder_audit`. The bot and the dashboard both snapshot
+//! reminders from the database, so that their entries have the same shape
+
+use chrono::NaiveDateTime;
+use log::warn;
+use serde_json::{json, Map, Value};
+use sqlx::Executor;
+
+use crate::Database;
+
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Clone, Copy)]
+pub enum AuditSource {
+    Command,
+    Macro,
+    Dashboard,
+    Import,
+}
+
+impl AuditSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSource::Command => "command",
+            AuditSource::Macro => "macro",
+            AuditSource::Dashboard => "dashboard",
+            AuditSource::Import => "import",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum AuditAction {
+    Create,
+    Edit,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Edit => "edit",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// The audited fields of a reminder at a point in time
+pub struct ReminderSnapshot {
+    guild_id: Option<u64>,
+    pub uid: String,
+    pub name: String,
+    fields: Map<String, Value>,
+}
+
+impl ReminderSnapshot {
+    /// Take snapshots of reminders by ID, given as a comma-separated list
+    pub async fn of(db: impl Executor<'_, Database = Database>, ids: &str) -> Vec<Self> {
+        Self::fetch(db, ids, "").await
+    }
+
+    /// Take a snapshot of a reminder by UID
+    pub async fn of_uid(db: impl Executor<'_, Database = Database>, uid: &str) -> Option<Self> {
+        Self::fetch(db, "", uid).await.into_iter().next()
+    }
+
+    async fn fetch(db: impl Executor<'_, Database = Database>, ids: &str, uids: &str) -> Vec<Self> {
+        sqlx::query!(
+            "
+SELECT
+    guilds.guild AS guild_id,
+    channels.channel,
+    reminders.uid,
+    reminders.name,
+    reminders.content,
+    reminders.tts AS \"tts: bool\",
+    reminders.attachment_name,
+    reminders.embed_title,
+    reminders.embed_description,
+    reminders.embed_author,
+    reminders.embed_author_url,
+    reminders.embed_footer,
+    reminders.embed_footer_url,
+    reminders.embed_image_url,
+    reminders.embed_thumbnail_url,
+    reminders.embed_color,
+    CAST(reminders.embed_fields AS CHAR) AS \"embed_fields: String\",
+    reminders.username,
+    reminders.avatar,
+    reminders.utc_time,
+    reminders.timezone,
+    reminders.expires,
+    reminders.interval_seconds,
+    reminders.interval_days,
+    reminders.interval_months,
+    reminders.interval_policy,
+    reminders.business_day_policy,
+    reminders.restartable AS \"restartable: bool\",
+    reminders.enabled AS \"enabled: bool\"
+FROM reminders
+INNER JOIN channels ON channels.id = reminders.channel_id
+LEFT JOIN guilds ON guilds.id = channels.guild_id
+WHERE FIND_IN_SET(reminders.id, ?) OR FIND_IN_SET(reminders.uid, ?)",
+            ids,
+            uids
+        )
+        .fetch_all(db)
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|row| {
+                    let format = |time: NaiveDateTime| time.format(TIME_FORMAT).to_string();
+
+                    let fields = json!({
+                        "channel": row.channel.to_string(),
+                        "name": row.name,
+                        "content": row.content,
+                        "tts": row.tts,
+                        "attachment_name": row.attachment_name,
+                        "embed_title": row.embed_title,
+                        "embed_description": row.embed_description,
+                        "embed_author": row.embed_author,
+                        "embed_author_url": row.embed_author_url,
+                        "embed_footer": row.embed_footer,
+                        "embed_footer_url": row.embed_footer_url,
+                        "embed_image_url": row.embed_image_url,
+                        "embed_thumbnail_url": row.embed_thumbnail_url,
+                        "embed_color": row.embed_color,
+                        "embed_fields": row
+                            .embed_fields
+                            .and_then(|fields| serde_json::from_str::<Value>(&fields).ok()),
+                        "username": row.username,
+                        "avatar": row.avatar,
+                        "utc_time": format(row.utc_time),
+                        "timezone": row.timezone,
+                        "expires": row.expires.map(format),
+                        "interval_seconds": row.interval_seconds,
+                        "interval_days": row.interval_days,
+                        "interval_months": row.interval_months,
+                        "interval_policy": row.interval_policy,
+                        "business_day_policy": row.business_day_policy,
+                        "restartable": row.restartable,
+                        "enabled": row.enabled,
+                    });
+
+                    ReminderSnapshot {
+                        guild_id: row.guild_id,
+                        uid: row.uid,
+                        name: row.name,
+                        fields: fields.as_object().cloned().unwrap_or_default(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_else(|e| {
+            warn!("Could not take snapshot of reminders: {:?}", e);
+
+            vec![]
+        })
+    }
+
+    /// Reminders sent to DMs have no guild of their own, so are kept with the guild they were set
+    /// in
+    pub fn set_in(mut self, guild_id: Option<u64>) -> Self {
+        self.guild_id = self.guild_id.or(guild_id);
+
+        self
+    }
+
+    /// The reminder's name and channel, for showing in messages
+    pub fn describe(&self) -> String {
+        format!("\"{}\" for <#{}>", self.name, self.fields["channel"].as_str().unwrap_or_default())
+    }
+}
+
+/// The fields that differ between two snapshots, as `{field: {"old": ..., "new": ...}}`. `None`
+/// is the old snapshot of a created reminder, or the new snapshot of a deleted one
+pub fn diff(old: Option<&ReminderSnapshot>, new: Option<&ReminderSnapshot>) -> Value {
+    let empty = Map::new();
+    let old_fields = old.map_or(&empty, |s| &s.fields);
+    let new_fields = new.map_or(&empty, |s| &s.fields);
+
+    let mut changes = Map::new();
+
+    for field in old_fields.keys().chain(new_fields.keys()) {
+        if changes.contains_key(field) {
+            continue;
+        }
+
+        let old_value = old_fields.get(field).unwrap_or(&Value::Null);
+        let new_value = new_fields.get(field).unwrap_or(&Value::Null);
+
+        if old_value != new_value {
+            changes.insert(field.clone(), json!({"old": old_value, "new": new_value}));
+        }
+    }
+
+    Value::Object(changes)
+}
+
+/// Record a change to a reminder. Failing to record is logged rather than failing the change.
+/// Edits that didn't change anything aren't recorded
+pub async fn record(
+    db: impl Executor<'_, Database = Database>,
+    actor: u64,
+    source: AuditSource,
+    action: AuditAction,
+    old: Option<&ReminderSnapshot>,
+    new: Option<&ReminderSnapshot>,
+) {
+    let snapshot = match new.or(old) {
+        Some(snapshot) => snapshot,
+
+        None => return,
+    };
+
+    let changes = diff(old, new);
+    if changes.as_object().map_or(true, |c| c.is_empty()) {
+        return;
+    }
+
+    if let Err(e) = sqlx::query!(
+        "
+INSERT INTO reminder_audit (guild_id, reminder_uid, reminder_name, actor, source, action, changes)
+VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?, ?, ?)",
+        snapshot.guild_id,
+        snapshot.uid,
+        snapshot.name,
+        actor,
+        source.as_str(),
+        action.as_str(),
+        changes.to_string()
+    )
+    .execute(db)
+    .await
+    {
+        warn!("Could not record audit entry for {}: {:?}", snapshot.uid, e);
+    }
+}
+
+/// Record changes to many reminders at once, pairing old and new snapshots by UID
+pub async fn record_all(
+    db: impl Executor<'_, Database = Database> + Copy,
+    actor: u64,
+    source: AuditSource,
+    action: AuditAction,
+    old: &[ReminderSnapshot],
+    new: &[ReminderSnapshot],
+) {
+    for snapshot in old {
+        let updated = new.iter().find(|s| s.uid == snapshot.uid);
+
+        record(db, actor, source, action, Some(snapshot), updated).await;
+    }
+}
+
+/// Record reminders on some channels being moved by `seconds`. Must be called before they're moved
+pub async fn record_offset(
+    db: impl Executor<'_, Database = Database>,
+    actor: u64,
+    source: AuditSource,
+    channels: &str,
+    seconds: i64,
+) {
+    if let Err(e) = sqlx::query!(
+        "
+INSERT INTO reminder_audit (guild_id, reminder_uid, reminder_name, actor, source, action, changes)
+SELECT
+    channels.guild_id,
+    reminders.uid,
+    reminders.name,
+    ?,
+    ?,
+    'edit',
+    JSON_OBJECT('utc_time', JSON_OBJECT(
+        'old', DATE_FORMAT(reminders.utc_time, '%Y-%m-%dT%H:%i:%s'),
+        'new', DATE_FORMAT(DATE_ADD(reminders.utc_time, INTERVAL ? SECOND), '%Y-%m-%dT%H:%i:%s')
+    ))
+FROM reminders
+INNER JOIN channels ON channels.id = reminders.channel_id
+WHERE FIND_IN_SET(channels.channel, ?)",
+        actor,
+        source.as_str(),
+        seconds,
+        channels
+    )
+    .execute(db)
+    .await
+    {
+        warn!("Could not record audit entries for offset: {:?}", e);
+    }
+}
@@ -0,0 +1,23 @@
+//! Limits that both the bot and the dashboard check, so that they agree
+
+use std::env;
+
+use lazy_static::lazy_static;
+
+// Embed limits, as set by Discord
+pub const EMBED_TITLE_MAX_LENGTH: usize = 256;
+pub const EMBED_DESCRIPTION_MAX_LENGTH: usize = 4096;
+pub const EMBED_AUTHOR_MAX_LENGTH: usize = 256;
+pub const EMBED_FOOTER_MAX_LENGTH: usize = 2048;
+pub const EMBED_MAX_FIELDS: usize = 25;
+pub const EMBED_FIELD_TITLE_MAX_LENGTH: usize = 256;
+pub const EMBED_FIELD_VALUE_MAX_LENGTH: usize = 1024;
+// Combined length of the title, description, author, footer and fields
+pub const EMBED_MAX_LENGTH: usize = 6000;
+
+lazy_static! {
+    pub static ref MACRO_MAX_COMMANDS: usize = env::var("MACRO_MAX_COMMANDS")
+        .ok()
+        .and_then(|inner| inner.parse::<usize>().ok())
+        .unwrap_or(10);
+}
@@ -0,0 +1,157 @@
+//! Posting reminder activity to a guild's log channel. Guilds choose which events are posted
+
+use log::warn;
+use serenity::{
+    http::Http,
+    model::{id::ChannelId, Timestamp},
+};
+use sqlx::Executor;
+
+use crate::Database;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LogEvent {
+    Created,
+    Edited,
+    Deleted,
+    Paused,
+    Offset,
+    Nudged,
+    /// Deleted by the bot because it could not be delivered
+    Removed,
+    /// A channel's webhook is missing and the bot couldn't recreate it
+    Webhook,
+}
+
+impl LogEvent {
+    pub const ALL: [LogEvent; 8] = [
+        LogEvent::Created,
+        LogEvent::Edited,
+        LogEvent::Deleted,
+        LogEvent::Paused,
+        LogEvent::Offset,
+        LogEvent::Nudged,
+        LogEvent::Removed,
+        LogEvent::Webhook,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogEvent::Created => "created",
+            LogEvent::Edited => "edited",
+            LogEvent::Deleted => "deleted",
+            LogEvent::Paused => "paused",
+            LogEvent::Offset => "offset",
+            LogEvent::Nudged => "nudged",
+            LogEvent::Removed => "removed",
+            LogEvent::Webhook => "webhook",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|event| event.name() == name).copied()
+    }
+
+    /// The bit of the event in `guilds.log_events`
+    pub fn bit(&self) -> u16 {
+        1 << Self::ALL.iter().position(|event| event == self).unwrap()
+    }
+
+    /// The events enabled in a `guilds.log_events` bitmask
+    pub fn enabled(mask: u16) -> Vec<Self> {
+        Self::ALL.iter().filter(|event| mask & event.bit() != 0).copied().collect()
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            LogEvent::Created => "Reminder Created",
+            LogEvent::Edited => "Reminder Edited",
+            LogEvent::Deleted => "Reminder Deleted",
+            LogEvent::Paused => "Reminders Paused",
+            LogEvent::Offset => "Reminders Offset",
+            LogEvent::Nudged => "Reminders Nudged",
+            LogEvent::Removed => "Reminder Removed",
+            LogEvent::Webhook => "Webhook Missing",
+        }
+    }
+
+    fn color(&self) -> u32 {
+        match self {
+            LogEvent::Created => 0x8fb677,
+            LogEvent::Edited => 0x5865f2,
+            LogEvent::Deleted | LogEvent::Removed | LogEvent::Webhook => 0xed4245,
+            LogEvent::Paused | LogEvent::Offset | LogEvent::Nudged => 0xfee75c,
+        }
+    }
+}
+
+/// Post an event to a guild's log channel, if it has one and has the event enabled. Failures are
+/// logged, as the activity being posted has already happened
+pub async fn post(
+    http: impl AsRef<Http>,
+    pool: impl Executor<'_, Database = Database>,
+    guild_id: u64,
+    event: LogEvent,
+    description: impl ToString,
+) {
+    let settings =
+        match sqlx::query!("SELECT log_channel, log_events FROM guilds WHERE guild = ?", guild_id)
+            .fetch_optional(pool)
+            .await
+        {
+            Ok(settings) => settings,
+
+            Err(e) => {
+                warn!("Could not fetch log settings for {}: {:?}", guild_id, e);
+
+                return;
+            }
+        };
+
+    let log_channel = match settings {
+        Some(row) if row.log_events & event.bit() != 0 => row.log_channel,
+
+        _ => None,
+    };
+
+    if let Some(log_channel) = log_channel {
+        let description = description.to_string();
+
+        if let Err(e) = ChannelId(log_channel)
+            .send_message(http.as_ref(), |m| {
+                m.embed(|e| {
+                    e.title(event.title())
+                        .description(description)
+                        .color(event.color())
+                        .timestamp(Timestamp::now())
+                })
+            })
+            .await
+        {
+            warn!("Could not post to log channel of {}: {:?}", guild_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_match_schema() {
+        let bits: Vec<u16> = LogEvent::ALL.iter().map(|event| event.bit()).collect();
+
+        assert_eq!(bits, vec![1, 2, 4, 8, 16, 32, 64, 128]);
+        assert_eq!(LogEvent::enabled(255), LogEvent::ALL.to_vec());
+        assert_eq!(LogEvent::enabled(4 | 64), vec![LogEvent::Deleted, LogEvent::Removed]);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for event in LogEvent::ALL {
+            assert_eq!(LogEvent::from_name(event.name()), Some(event));
+        }
+
+        assert_eq!(LogEvent::from_name("sent"), None);
+    }
+}
@@ -1,8 +1,17 @@
+pub mod audit;
+pub mod consts;
+pub mod guild_log;
+pub mod names;
+pub mod quiet_hours;
+pub mod recurrence;
+pub mod selector;
 mod sender;
+mod webhooks;
 
 use std::env;
 
 use log::{info, warn};
+pub use sender::send_now;
 use serenity::client::Context;
 use sqlx::{Executor, MySql};
 use tokio::{
@@ -27,6 +36,11 @@ pub async fn initialize(
 }
 
 async fn _initialize(ctx: Context, pool: impl Executor<'_, Database = Database> + Copy) {
+    // webhooks are checked alongside sending, so that slow requests to Discord don't delay reminders
+    tokio::join!(send_reminders(&ctx, pool), webhooks::check_webhooks(pool, &ctx));
+}
+
+async fn send_reminders(ctx: &Context, pool: impl Executor<'_, Database = Database> + Copy) {
     let remind_interval = env::var("REMIND_INTERVAL")
         .map(|inner| inner.parse::<u64>().ok())
         .ok()
@@ -45,6 +59,8 @@ async fn _initialize(ctx: Context, pool: impl Executor<'_, Database = Database>
             }
         }
 
+        quiet_hours::send_batches(pool, &ctx.http).await;
+
         sleep_until(sleep_to).await;
     }
 }
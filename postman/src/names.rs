@@ -0,0 +1,107 @@
+//! Reminder names are unique within a channel, so that commands can refer to reminders by name.
+//! Reminders created without a name, or with a name that is already taken, are numbered instead,
+//! e.g. `Reminder 2`
+
+use std::collections::HashSet;
+
+use sqlx::Executor;
+
+use crate::Database;
+
+pub const MAX_NAME_LENGTH: usize = 24;
+pub const DEFAULT_NAME: &str = "Reminder";
+
+/// `name` with a number on the end, shortened to fit
+fn numbered(name: &str, number: usize) -> String {
+    let suffix = format!(" {}", number);
+    let base = name.chars().take(MAX_NAME_LENGTH - suffix.len()).collect::<String>();
+
+    format!("{}{}", base.trim_end(), suffix)
+}
+
+/// The first of `name`, `name 2`, `name 3`... that isn't in `taken`. Names are compared without
+/// case, as the database does
+pub fn first_free(name: &str, taken: &HashSet<String>) -> String {
+    let name = name.trim();
+    let name = if name.is_empty() { DEFAULT_NAME } else { name };
+    let name = name.chars().take(MAX_NAME_LENGTH).collect::<String>();
+
+    if !taken.contains(&name.to_lowercase()) {
+        return name;
+    }
+
+    (2..)
+        .map(|number| numbered(&name, number))
+        .find(|candidate| !taken.contains(&candidate.to_lowercase()))
+        .unwrap()
+}
+
+/// A name for a new reminder in a channel, based on `name`
+pub async fn free_name(
+    pool: impl Executor<'_, Database = Database>,
+    channel_id: u32,
+    name: &str,
+) -> Result<String, sqlx::Error> {
+    let taken = sqlx::query_scalar!("SELECT name FROM reminders WHERE channel_id = ?", channel_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+
+    Ok(first_free(name, &taken))
+}
+
+/// Whether a reminder other than `uid` in a channel already has a name
+pub async fn is_taken(
+    pool: impl Executor<'_, Database = Database>,
+    channel_id: u32,
+    name: &str,
+    uid: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT COUNT(1) FROM reminders WHERE channel_id = ? AND name = ? AND (? IS NULL OR uid != ?)",
+        channel_id,
+        name.trim(),
+        uid,
+        uid
+    )
+    .fetch_one(pool)
+    .await
+    .map(|count| count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn taken(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_lowercase()).collect()
+    }
+
+    #[test]
+    fn free_names_kept() {
+        assert_eq!(first_free("Standup", &taken(&["Reminder"])), "Standup");
+        assert_eq!(first_free("  ", &taken(&[])), "Reminder");
+    }
+
+    #[test]
+    fn taken_names_numbered() {
+        assert_eq!(first_free("Reminder", &taken(&["Reminder"])), "Reminder 2");
+        assert_eq!(
+            first_free("reminder", &taken(&["Reminder", "Reminder 2", "Reminder 4"])),
+            "reminder 3"
+        );
+    }
+
+    #[test]
+    fn long_names_shortened() {
+        let name = "A very long reminder name here";
+
+        assert_eq!(first_free(name, &taken(&[])), "A very long reminder nam");
+        assert_eq!(
+            first_free(name, &taken(&["A very long reminder nam"])),
+            "A very long reminder n 2"
+        );
+    }
+}
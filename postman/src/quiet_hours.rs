@@ -0,0 +1,394 @@
+//! Recurring windows in which a guild or channel doesn't receive reminders
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use chrono_tz::Tz;
+use log::warn;
+use serenity::{
+    http::Http,
+    model::{id::ChannelId, Timestamp},
+};
+use sqlx::Executor;
+
+use crate::{recurrence::resolve_local, Database};
+
+pub const WEEKDAYS: [&str; 7] =
+    ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+// Longest a reminder's content is shown in a batch summary
+const SUMMARY_CONTENT_LENGTH: usize = 100;
+// Longest description Discord allows in an embed
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+/// What happens to a reminder due inside a window
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuietPolicy {
+    /// Don't send the reminder
+    Drop,
+    /// Send the reminder when the window ends
+    Defer,
+    /// List the reminder in a single message sent when the window ends
+    Batch,
+}
+
+impl From<&str> for QuietPolicy {
+    fn from(policy: &str) -> Self {
+        match policy {
+            "drop" => QuietPolicy::Drop,
+            "batch" => QuietPolicy::Batch,
+            _ => QuietPolicy::Defer,
+        }
+    }
+}
+
+impl QuietPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuietPolicy::Drop => "drop",
+            QuietPolicy::Defer => "defer",
+            QuietPolicy::Batch => "batch",
+        }
+    }
+}
+
+pub struct QuietWindow {
+    /// Days of the week the window starts on, as a bitmask from Monday (1) to Sunday (64)
+    pub days: u8,
+    pub start: NaiveTime,
+    /// If not after `start`, the window ends on the following day
+    pub end: NaiveTime,
+    pub policy: QuietPolicy,
+}
+
+impl QuietWindow {
+    /// When the window ends, if `time` falls inside it
+    pub fn end_of(&self, timezone: &Tz, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = time.with_timezone(timezone).naive_local();
+
+        // A window running into the following day may have started the day before
+        [local.date().pred_opt(), Some(local.date())].into_iter().flatten().find_map(|date| {
+            if self.days & (1 << date.weekday().num_days_from_monday()) == 0 {
+                return None;
+            }
+
+            let start = date.and_time(self.start);
+            let end = if self.end > self.start {
+                date.and_time(self.end)
+            } else {
+                date.succ_opt()?.and_time(self.end)
+            };
+
+            if start <= local && local < end {
+                Some(resolve_local(timezone, end).with_timezone(&Utc))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The window `time` falls inside, as its policy and when it ends. If several windows overlap, the
+/// one ending last is used
+pub fn active(
+    windows: &[QuietWindow],
+    timezone: &Tz,
+    time: DateTime<Utc>,
+) -> Option<(QuietPolicy, DateTime<Utc>)> {
+    windows
+        .iter()
+        .filter_map(|window| window.end_of(timezone, time).map(|end| (window.policy, end)))
+        .max_by_key(|(_, end)| *end)
+}
+
+/// Parse a list of days such as "mon, wed, fri", "weekdays", "weekends" or "daily" into a bitmask
+pub fn parse_days(days: &str) -> Option<u8> {
+    match days.trim().to_lowercase().as_str() {
+        "daily" | "every day" | "everyday" | "all" => Some(0b1111111),
+
+        "weekdays" => Some(0b0011111),
+
+        "weekends" => Some(0b1100000),
+
+        days => days
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|day| !day.is_empty())
+            .try_fold(0, |mask, day| {
+                WEEKDAYS
+                    .iter()
+                    .position(|d| day.len() >= 3 && d.starts_with(day))
+                    .map(|index| mask | 1 << index)
+            })
+            .filter(|mask| *mask != 0),
+    }
+}
+
+/// Describe a bitmask of days, the reverse of [`parse_days`]
+pub fn describe_days(days: u8) -> String {
+    match days {
+        0b1111111 => "Every day".to_string(),
+
+        0b0011111 => "Weekdays".to_string(),
+
+        0b1100000 => "Weekends".to_string(),
+
+        days => WEEKDAYS
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| days & (1 << index) != 0)
+            .map(|(_, day)| format!("{}{}", day[..1].to_uppercase(), &day[1..3]))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// The window a channel is inside at `time`. Channels with windows of their own ignore their
+/// guild's windows
+pub async fn active_for_channel(
+    pool: impl Executor<'_, Database = Database>,
+    channel_id: u64,
+    time: DateTime<Utc>,
+) -> Option<(QuietPolicy, DateTime<Utc>)> {
+    let rows = match sqlx::query!(
+        "SELECT
+            quiet_hours.days,
+            quiet_hours.start_time,
+            quiet_hours.end_time,
+            quiet_hours.policy,
+            quiet_hours.channel_id IS NOT NULL AS \"channel_window: bool\",
+            IFNULL(channels.timezone, guilds.timezone) AS timezone
+        FROM channels
+        INNER JOIN quiet_hours
+            ON quiet_hours.channel_id = channels.id OR quiet_hours.guild_id = channels.guild_id
+        LEFT JOIN guilds ON guilds.id = channels.guild_id
+        WHERE channels.channel = ?",
+        channel_id
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows,
+
+        Err(e) => {
+            warn!("Could not fetch quiet hours for {}: {:?}", channel_id, e);
+
+            return None;
+        }
+    };
+
+    let timezone =
+        rows.first().and_then(|row| row.timezone.as_ref()?.parse().ok()).unwrap_or(Tz::UTC);
+    let has_channel_windows = rows.iter().any(|row| row.channel_window);
+
+    let windows = rows
+        .into_iter()
+        .filter(|row| row.channel_window || !has_channel_windows)
+        .map(|row| QuietWindow {
+            days: row.days,
+            start: row.start_time,
+            end: row.end_time,
+            policy: QuietPolicy::from(row.policy.as_str()),
+        })
+        .collect::<Vec<_>>();
+
+    active(&windows, &timezone, time)
+}
+
+/// Send the reminders batched by quiet hours that have ended, as one message per channel
+pub async fn send_batches(pool: impl Executor<'_, Database = Database> + Copy, http: &Http) {
+    let rows = match sqlx::query!(
+        "SELECT quiet_hours_batch.id, channels.channel, quiet_hours_batch.name,
+            quiet_hours_batch.content, quiet_hours_batch.due_time
+        FROM quiet_hours_batch
+        INNER JOIN channels ON channels.id = quiet_hours_batch.channel_id
+        WHERE quiet_hours_batch.release_time <= NOW()
+        ORDER BY quiet_hours_batch.due_time"
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows,
+
+        Err(e) => {
+            warn!("Could not fetch batched reminders: {:?}", e);
+
+            return;
+        }
+    };
+
+    let mut batches: HashMap<u64, Vec<String>> = HashMap::new();
+    let mut ids = vec![];
+
+    for row in rows {
+        let content = if row.content.chars().count() > SUMMARY_CONTENT_LENGTH {
+            format!("{}...", row.content.chars().take(SUMMARY_CONTENT_LENGTH).collect::<String>())
+        } else {
+            row.content
+        };
+
+        batches.entry(row.channel).or_default().push(format!(
+            "<t:{}:t> **{}** {}",
+            row.due_time.timestamp(),
+            row.name,
+            content
+        ));
+        ids.push(row.id.to_string());
+    }
+
+    if ids.is_empty() {
+        return;
+    }
+
+    // Batches are removed before sending, so that a channel that can't be sent to doesn't have its
+    // summary retried forever
+    if let Err(e) =
+        sqlx::query!("DELETE FROM quiet_hours_batch WHERE FIND_IN_SET(id, ?)", ids.join(","))
+            .execute(pool)
+            .await
+    {
+        warn!("Could not clear batched reminders: {:?}", e);
+
+        return;
+    }
+
+    for (channel, lines) in batches {
+        if let Err(e) = ChannelId(channel)
+            .send_message(http, |m| {
+                m.embed(|e| {
+                    e.title("Reminders During Quiet Hours")
+                        .description(summarize(&lines))
+                        .timestamp(Timestamp::now())
+                })
+            })
+            .await
+        {
+            warn!("Could not send batched reminders to {}: {:?}", channel, e);
+        }
+    }
+}
+
+/// Join summary lines, leaving out those that don't fit in an embed
+fn summarize(lines: &[String]) -> String {
+    let mut description = String::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let more = format!("\n...and {} more", lines.len() - index);
+
+        if description.len() + line.len() + 1 + more.len() > MAX_DESCRIPTION_LENGTH {
+            description.push_str(&more);
+
+            break;
+        }
+
+        if !description.is_empty() {
+            description.push('\n');
+        }
+        description.push_str(line);
+    }
+
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+
+    use super::*;
+
+    fn time(tz: &Tz, date: (i32, u32, u32), time: (u32, u32)) -> DateTime<Utc> {
+        tz.from_local_datetime(
+            &NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+                .unwrap()
+                .and_hms_opt(time.0, time.1, 0)
+                .unwrap(),
+        )
+        .unwrap()
+        .with_timezone(&Utc)
+    }
+
+    fn window(days: u8, start: (u32, u32), end: (u32, u32)) -> QuietWindow {
+        QuietWindow {
+            days,
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            policy: QuietPolicy::Defer,
+        }
+    }
+
+    #[test]
+    fn overnight_window() {
+        let tz: Tz = "Europe/London".parse().unwrap();
+        // Weeknights from 22:00 to 07:00, starting Monday to Friday
+        let nights = window(0b0011111, (22, 0), (7, 0));
+
+        // Friday 2023-10-06
+        assert_eq!(nights.end_of(&tz, time(&tz, (2023, 10, 6), (21, 59))), None);
+        assert_eq!(
+            nights.end_of(&tz, time(&tz, (2023, 10, 6), (23, 30))),
+            Some(time(&tz, (2023, 10, 7), (7, 0)))
+        );
+        // Saturday morning is inside Friday night's window
+        assert_eq!(
+            nights.end_of(&tz, time(&tz, (2023, 10, 7), (6, 59))),
+            Some(time(&tz, (2023, 10, 7), (7, 0)))
+        );
+        assert_eq!(nights.end_of(&tz, time(&tz, (2023, 10, 7), (7, 0))), None);
+        // No window starts on Saturday night
+        assert_eq!(nights.end_of(&tz, time(&tz, (2023, 10, 7), (23, 0))), None);
+        // Monday morning isn't inside a window, as none started on Sunday
+        assert_eq!(nights.end_of(&tz, time(&tz, (2023, 10, 9), (3, 0))), None);
+    }
+
+    #[test]
+    fn all_day_window() {
+        let tz = Tz::UTC;
+        let weekends = window(0b1100000, (0, 0), (0, 0));
+
+        assert_eq!(
+            weekends.end_of(&tz, time(&tz, (2023, 10, 7), (12, 0))),
+            Some(time(&tz, (2023, 10, 8), (0, 0)))
+        );
+        assert_eq!(weekends.end_of(&tz, time(&tz, (2023, 10, 9), (0, 0))), None);
+
+        // Overlapping windows end with the last of them
+        let windows = [weekends, window(0b1111111, (22, 0), (7, 0))];
+        assert_eq!(
+            active(&windows, &tz, time(&tz, (2023, 10, 8), (23, 0))),
+            Some((QuietPolicy::Defer, time(&tz, (2023, 10, 9), (7, 0))))
+        );
+    }
+
+    #[test]
+    fn window_ending_in_skipped_hour() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let night = window(0b1111111, (22, 0), (2, 30));
+
+        // 02:30 doesn't exist on 2023-03-12, so the window ends at 03:30
+        assert_eq!(
+            night.end_of(&tz, time(&tz, (2023, 3, 11), (23, 0))),
+            Some(time(&tz, (2023, 3, 12), (3, 30)))
+        );
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(parse_days("weekdays"), Some(0b0011111));
+        assert_eq!(parse_days("Mon, wed friday"), Some(0b0010101));
+        assert_eq!(parse_days("sun"), Some(0b1000000));
+        assert_eq!(parse_days("mo"), None);
+        assert_eq!(parse_days("mon, someday"), None);
+        assert_eq!(parse_days(""), None);
+
+        assert_eq!(describe_days(0b0010101), "Mon, Wed, Fri");
+        assert_eq!(describe_days(0b1100000), "Weekends");
+    }
+
+    #[test]
+    fn summary_fits_embed() {
+        let lines = vec!["x".repeat(1000); 10];
+        let summary = summarize(&lines);
+
+        assert!(summary.len() <= MAX_DESCRIPTION_LENGTH);
+        assert!(summary.ends_with("...and 6 more"));
+    }
+}
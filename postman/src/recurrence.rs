@@ -0,0 +1,607 @@
+use std::collections::HashSet;
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, Offset,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+// Longest run of skipped occurrences before a reminder is considered unable to repeat
+const MAX_SKIPPED: u32 = 1000;
+// Furthest a reminder will be moved looking for a working day
+const MAX_SHIFT_DAYS: i64 = 366;
+
+/// How the interval of a repeating reminder is measured
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntervalPolicy {
+    /// Repeat at the same local time, e.g. 9am every day, regardless of daylight saving changes
+    WallClock,
+    /// Repeat once exactly the interval has passed, so the local time moves with daylight saving
+    Elapsed,
+}
+
+impl From<&str> for IntervalPolicy {
+    fn from(policy: &str) -> Self {
+        match policy {
+            "elapsed" => Self::Elapsed,
+            _ => Self::WallClock,
+        }
+    }
+}
+
+/// What to do when an occurrence of a repeating reminder falls on a non-working day
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BusinessDayPolicy {
+    /// Send the reminder anyway
+    Ignore,
+    /// Don't send this occurrence
+    Skip,
+    /// Send the reminder on the next working day
+    Next,
+    /// Send the reminder on the previous working day
+    Previous,
+}
+
+impl From<&str> for BusinessDayPolicy {
+    fn from(policy: &str) -> Self {
+        match policy {
+            "skip" => Self::Skip,
+            "next" => Self::Next,
+            "previous" => Self::Previous,
+            _ => Self::Ignore,
+        }
+    }
+}
+
+/// The working days of a guild
+pub struct Calendar {
+    /// Days of the week that aren't working days, as a bitmask from Monday (1) to Sunday (64)
+    pub weekend_days: u8,
+    pub holidays: HashSet<NaiveDate>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar { weekend_days: 0b1100000, holidays: HashSet::new() }
+    }
+}
+
+impl Calendar {
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.weekend_days & (1 << date.weekday().num_days_from_monday()) == 0
+            && !self.holidays.contains(&date)
+    }
+}
+
+/// Map a local time to a single instant. Local times skipped by a daylight saving change are
+/// moved forward by the length of the skip (02:30 becomes 03:30), and local times repeated by a
+/// daylight saving change use the earlier occurrence
+pub fn resolve_local(timezone: &Tz, local: NaiveDateTime) -> DateTime<Tz> {
+    match timezone.from_local_datetime(&local) {
+        LocalResult::Single(time) => time,
+
+        LocalResult::Ambiguous(earliest, _) => earliest,
+
+        LocalResult::None => {
+            let offset_before =
+                timezone.offset_from_utc_datetime(&(local - Duration::days(1))).fix();
+
+            timezone.from_utc_datetime(
+                &(local - Duration::seconds(offset_before.local_minus_utc() as i64)),
+            )
+        }
+    }
+}
+
+/// The schedule of a repeating reminder
+pub struct Recurrence {
+    pub timezone: Tz,
+    pub policy: IntervalPolicy,
+    pub seconds: Option<u32>,
+    pub days: Option<u32>,
+    pub months: Option<u32>,
+    pub business_days: BusinessDayPolicy,
+    pub calendar: Calendar,
+}
+
+impl Recurrence {
+    /// The months and days of the interval, counted on the local calendar, and the rest of the
+    /// interval, counted as elapsed time. For wall clock reminders, whole days given in seconds,
+    /// as the dashboard stores them, are counted as days
+    fn parts(&self) -> (Months, Days, Duration) {
+        let seconds = self.seconds.unwrap_or(0) as u64;
+        let (days, seconds) = match self.policy {
+            IntervalPolicy::WallClock => (seconds / 86400, seconds % 86400),
+            IntervalPolicy::Elapsed => (0, seconds),
+        };
+
+        (
+            Months::new(self.months.unwrap_or(0)),
+            Days::new(self.days.unwrap_or(0) as u64 + days),
+            Duration::seconds(seconds as i64),
+        )
+    }
+
+    /// Apply the interval once to the occurrence due at `time`, intended for `local`
+    fn step(
+        &self,
+        time: DateTime<Tz>,
+        local: NaiveDateTime,
+    ) -> Option<(DateTime<Tz>, NaiveDateTime)> {
+        let (months, days, elapsed) = self.parts();
+
+        match self.policy {
+            IntervalPolicy::WallClock => {
+                // Only the calendar parts keep the local time. Hours and minutes are elapsed time,
+                // so that hourly reminders aren't lost or repeated around daylight saving changes
+                let (base, local) = if months == Months::new(0) && days == Days::new(0) {
+                    (time, local)
+                } else {
+                    let local = local.checked_add_months(months)?.checked_add_days(days)?;
+
+                    (resolve_local(&self.timezone, local), local)
+                };
+
+                if elapsed.is_zero() {
+                    Some((base, local))
+                } else {
+                    let next = base + elapsed;
+
+                    Some((next, next.naive_local()))
+                }
+            }
+
+            IntervalPolicy::Elapsed => {
+                // Months and days have no fixed length, so are always counted on the calendar
+                let next = resolve_local(
+                    &self.timezone,
+                    time.naive_local().checked_add_months(months)?.checked_add_days(days)?,
+                ) + elapsed;
+
+                Some((next, next.naive_local()))
+            }
+        }
+    }
+
+    /// The occurrence following the one due at `time`, as an instant and the local time it was
+    /// intended for. `None` if the interval can't be applied
+    fn advance(
+        &self,
+        time: DateTime<Tz>,
+        local: NaiveDateTime,
+    ) -> Option<(DateTime<Tz>, NaiveDateTime)> {
+        let (months, days, elapsed) = self.parts();
+
+        if months == Months::new(0) && days == Days::new(0) && elapsed.is_zero() {
+            return None;
+        }
+
+        // A local time skipped by a daylight saving change can resolve to the same instant as the
+        // occurrence before it, so step again until the time moves on
+        let mut next = self.step(time, local)?;
+        for _ in 0..MAX_SKIPPED {
+            if next.0 > time {
+                return Some(next);
+            }
+
+            next = self.step(next.0, next.1)?;
+        }
+
+        None
+    }
+
+    /// When an occurrence intended for `local` is actually sent, after moving it off a
+    /// non-working day. `None` if the occurrence is skipped
+    fn place(&self, time: DateTime<Tz>, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+        if self.business_days == BusinessDayPolicy::Ignore
+            || self.calendar.is_working_day(local.date())
+        {
+            return Some(time);
+        }
+
+        let step = match self.business_days {
+            BusinessDayPolicy::Next => Duration::days(1),
+            BusinessDayPolicy::Previous => Duration::days(-1),
+            _ => return None,
+        };
+
+        let mut date = local.date();
+        for _ in 0..MAX_SHIFT_DAYS {
+            date += step;
+
+            if self.calendar.is_working_day(date) {
+                return Some(resolve_local(&self.timezone, date.and_time(local.time())));
+            }
+        }
+
+        None
+    }
+
+    /// The times the reminder will be sent, starting from the occurrence due at `time`.
+    /// `shifted_from` is the local time that occurrence was intended for, if it was moved out of
+    /// a skipped hour or off a non-working day, so that later occurrences return to the intended
+    /// local time. Each item is the time to send the reminder, and the local time it was
+    /// intended for
+    pub fn occurrences(
+        &self,
+        time: DateTime<Utc>,
+        shifted_from: Option<NaiveDateTime>,
+    ) -> impl Iterator<Item = (DateTime<Utc>, NaiveDateTime)> + '_ {
+        let time = time.with_timezone(&self.timezone);
+
+        // Ignore `shifted_from` if the reminder has since been moved to a different time
+        let mut intended = shifted_from
+            .map(|local| (resolve_local(&self.timezone, local), local))
+            .filter(|(intended, local)| self.place(*intended, *local) == Some(time));
+        let mut first = true;
+
+        std::iter::from_fn(move || {
+            let (next, local) = if first {
+                first = false;
+
+                let (intended_time, local) = intended.unwrap_or_else(|| (time, time.naive_local()));
+                intended = Some((intended_time, local));
+
+                (time, local)
+            } else {
+                let mut skipped = 0;
+
+                loop {
+                    let (intended_time, local) = intended?;
+                    let advanced = self.advance(intended_time, local);
+                    intended = advanced;
+
+                    let (intended_time, local) = advanced?;
+                    if let Some(next) = self.place(intended_time, local) {
+                        break (next, local);
+                    }
+
+                    skipped += 1;
+                    if skipped > MAX_SKIPPED {
+                        intended = None;
+
+                        return None;
+                    }
+                }
+            };
+
+            Some((next.with_timezone(&Utc), local))
+        })
+    }
+
+    /// The first time after `now` that the reminder will be sent, and the local time it was
+    /// intended for if it has been moved. `None` if the reminder can't repeat
+    pub fn next_after(
+        &self,
+        time: DateTime<Utc>,
+        shifted_from: Option<NaiveDateTime>,
+        now: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, Option<NaiveDateTime>)> {
+        self.occurrences(time, shifted_from).find(|(next, _)| *next > now).map(|(next, local)| {
+            let shifted_from = if next.with_timezone(&self.timezone).naive_local() == local {
+                None
+            } else {
+                Some(local)
+            };
+
+            (next, shifted_from)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(timezone: Tz, date: (i32, u32, u32), time: (u32, u32)) -> DateTime<Utc> {
+        resolve_local(&timezone, naive(date, time)).with_timezone(&Utc)
+    }
+
+    fn naive(date: (i32, u32, u32), time: (u32, u32)) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(time.0, time.1, 0)
+            .unwrap()
+    }
+
+    fn recurrence(timezone: Tz, policy: IntervalPolicy, seconds: u32) -> Recurrence {
+        Recurrence {
+            timezone,
+            policy,
+            seconds: Some(seconds),
+            days: None,
+            months: None,
+            business_days: BusinessDayPolicy::Ignore,
+            calendar: Calendar::default(),
+        }
+    }
+
+    fn next(
+        recurrence: &Recurrence,
+        time: DateTime<Utc>,
+        shifted_from: Option<NaiveDateTime>,
+    ) -> (NaiveDateTime, Option<NaiveDateTime>) {
+        let (next, shifted_from) = recurrence.next_after(time, shifted_from, time).unwrap();
+
+        (next.with_timezone(&recurrence.timezone).naive_local(), shifted_from)
+    }
+
+    #[test]
+    fn wall_clock_keeps_local_time_in_europe() {
+        let tz = Tz::Europe__London;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // Clocks go forward on 26 March 2023, and back on 29 October 2023
+        let before_spring = local(tz, (2023, 3, 25), (9, 0));
+        assert_eq!(next(&daily, before_spring, None), (naive((2023, 3, 26), (9, 0)), None));
+
+        let before_autumn = local(tz, (2023, 10, 28), (9, 0));
+        assert_eq!(next(&daily, before_autumn, None), (naive((2023, 10, 29), (9, 0)), None));
+    }
+
+    #[test]
+    fn wall_clock_keeps_local_time_in_us() {
+        let tz = Tz::America__New_York;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // Clocks go forward on 12 March 2023, and back on 5 November 2023
+        let before_spring = local(tz, (2023, 3, 11), (9, 0));
+        assert_eq!(next(&daily, before_spring, None), (naive((2023, 3, 12), (9, 0)), None));
+
+        let before_autumn = local(tz, (2023, 11, 4), (9, 0));
+        assert_eq!(next(&daily, before_autumn, None), (naive((2023, 11, 5), (9, 0)), None));
+    }
+
+    #[test]
+    fn elapsed_follows_daylight_saving() {
+        let tz = Tz::Europe__London;
+        let daily = recurrence(tz, IntervalPolicy::Elapsed, 86400);
+
+        let before_spring = local(tz, (2023, 3, 25), (9, 0));
+        assert_eq!(next(&daily, before_spring, None), (naive((2023, 3, 26), (10, 0)), None));
+
+        let tz = Tz::America__New_York;
+        let daily = recurrence(tz, IntervalPolicy::Elapsed, 86400);
+
+        let before_autumn = local(tz, (2023, 11, 4), (9, 0));
+        assert_eq!(next(&daily, before_autumn, None), (naive((2023, 11, 5), (8, 0)), None));
+    }
+
+    #[test]
+    fn hourly_wall_clock_across_spring_forward() {
+        let tz = Tz::America__New_York;
+        let hourly = recurrence(tz, IntervalPolicy::WallClock, 3600);
+
+        // 02:00 doesn't exist on 12 March 2023, so 01:00 EST is followed by 03:00 EDT
+        let one = local(tz, (2023, 3, 12), (1, 0));
+        let (three, _) = hourly.next_after(one, None, one).unwrap();
+        assert_eq!(three, one + Duration::hours(1));
+        assert_eq!(three.with_timezone(&tz).naive_local(), naive((2023, 3, 12), (3, 0)));
+
+        let (four, _) = hourly.next_after(three, None, three).unwrap();
+        assert_eq!(four.with_timezone(&tz).naive_local(), naive((2023, 3, 12), (4, 0)));
+    }
+
+    #[test]
+    fn hourly_wall_clock_across_fall_back() {
+        let tz = Tz::America__New_York;
+        let hourly = recurrence(tz, IntervalPolicy::WallClock, 3600);
+
+        // 01:00 happens twice on 5 November 2023, and both are sent
+        let midnight = local(tz, (2023, 11, 5), (0, 0));
+        let times = hourly
+            .occurrences(midnight, None)
+            .take(4)
+            .map(|(time, _)| time.naive_utc())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            times,
+            vec![
+                naive((2023, 11, 5), (4, 0)),
+                naive((2023, 11, 5), (5, 0)),
+                naive((2023, 11, 5), (6, 0)),
+                naive((2023, 11, 5), (7, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn elapsed_days_use_local_calendar() {
+        let tz = Tz::Europe__London;
+        let daily = Recurrence {
+            seconds: None,
+            days: Some(1),
+            ..recurrence(tz, IntervalPolicy::Elapsed, 0)
+        };
+
+        let before_spring = local(tz, (2023, 3, 25), (9, 0));
+        assert_eq!(next(&daily, before_spring, None), (naive((2023, 3, 26), (9, 0)), None));
+    }
+
+    #[test]
+    fn skipped_time_is_moved_forward() {
+        let tz = Tz::America__New_York;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // 02:30 doesn't exist on 12 March 2023, so is moved to 03:30
+        let before_spring = local(tz, (2023, 3, 11), (2, 30));
+        let (moved, shifted_from) = next(&daily, before_spring, None);
+        assert_eq!(moved, naive((2023, 3, 12), (3, 30)));
+        assert_eq!(shifted_from, Some(naive((2023, 3, 12), (2, 30))));
+
+        // The following day returns to 02:30
+        assert_eq!(
+            next(&daily, resolve_local(&tz, moved).with_timezone(&Utc), shifted_from),
+            (naive((2023, 3, 13), (2, 30)), None)
+        );
+    }
+
+    #[test]
+    fn stale_shifted_time_is_ignored() {
+        let tz = Tz::Europe__Berlin;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // The reminder has since been moved to 10:00, so the old shifted time no longer applies
+        let time = local(tz, (2023, 3, 26), (10, 0));
+        assert_eq!(
+            next(&daily, time, Some(naive((2023, 3, 26), (2, 30)))),
+            (naive((2023, 3, 27), (10, 0)), None)
+        );
+    }
+
+    #[test]
+    fn repeated_time_uses_earlier_occurrence() {
+        let tz = Tz::Europe__Berlin;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // 02:30 happens twice on 29 October 2023, first in CEST (UTC+2)
+        let before_autumn = local(tz, (2023, 10, 28), (2, 30));
+        let (next, _) = daily.next_after(before_autumn, None, before_autumn).unwrap();
+        assert_eq!(next.naive_utc(), naive((2023, 10, 29), (0, 30)));
+
+        let tz = Tz::America__New_York;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        // 01:30 happens twice on 5 November 2023, first in EDT (UTC-4)
+        let before_autumn = local(tz, (2023, 11, 4), (1, 30));
+        let (next, _) = daily.next_after(before_autumn, None, before_autumn).unwrap();
+        assert_eq!(next.naive_utc(), naive((2023, 11, 5), (5, 30)));
+    }
+
+    #[test]
+    fn months_and_days_use_local_calendar() {
+        let tz = Tz::Europe__London;
+        let monthly = Recurrence {
+            timezone: tz,
+            policy: IntervalPolicy::Elapsed,
+            seconds: None,
+            days: None,
+            months: Some(1),
+            business_days: BusinessDayPolicy::Ignore,
+            calendar: Calendar::default(),
+        };
+
+        let before_spring = local(tz, (2023, 3, 15), (9, 0));
+        assert_eq!(next(&monthly, before_spring, None), (naive((2023, 4, 15), (9, 0)), None));
+
+        let weekly = Recurrence {
+            timezone: Tz::America__New_York,
+            policy: IntervalPolicy::WallClock,
+            seconds: None,
+            days: Some(7),
+            months: None,
+            business_days: BusinessDayPolicy::Ignore,
+            calendar: Calendar::default(),
+        };
+
+        let before_autumn = local(Tz::America__New_York, (2023, 11, 1), (18, 0));
+        assert_eq!(next(&weekly, before_autumn, None), (naive((2023, 11, 8), (18, 0)), None));
+    }
+
+    #[test]
+    fn catches_up_to_now() {
+        let tz = Tz::Europe__London;
+        let daily = recurrence(tz, IntervalPolicy::WallClock, 86400);
+
+        let time = local(tz, (2023, 3, 20), (9, 0));
+        let now = local(tz, (2023, 3, 28), (12, 0));
+        let (next, _) = daily.next_after(time, None, now).unwrap();
+        assert_eq!(next, local(tz, (2023, 3, 29), (9, 0)));
+    }
+
+    #[test]
+    fn zero_interval_is_rejected() {
+        let daily = recurrence(Tz::UTC, IntervalPolicy::WallClock, 0);
+
+        let time = local(Tz::UTC, (2023, 3, 20), (9, 0));
+        assert!(daily.next_after(time, None, time).is_none());
+    }
+
+    fn business_days(policy: BusinessDayPolicy, holidays: &[(i32, u32, u32)]) -> Recurrence {
+        Recurrence {
+            business_days: policy,
+            calendar: Calendar {
+                holidays: holidays
+                    .iter()
+                    .map(|(y, m, d)| NaiveDate::from_ymd_opt(*y, *m, *d).unwrap())
+                    .collect(),
+                ..Calendar::default()
+            },
+            ..recurrence(Tz::Europe__London, IntervalPolicy::WallClock, 86400)
+        }
+    }
+
+    #[test]
+    fn weekends_are_skipped() {
+        let daily = business_days(BusinessDayPolicy::Skip, &[]);
+
+        // Friday 6 October 2023, so the next working day is Monday
+        let friday = local(Tz::Europe__London, (2023, 10, 6), (9, 0));
+        assert_eq!(next(&daily, friday, None), (naive((2023, 10, 9), (9, 0)), None));
+    }
+
+    #[test]
+    fn holidays_are_skipped() {
+        let daily = business_days(BusinessDayPolicy::Skip, &[(2023, 12, 25), (2023, 12, 26)]);
+
+        let friday = local(Tz::Europe__London, (2023, 12, 22), (9, 0));
+        assert_eq!(next(&daily, friday, None), (naive((2023, 12, 27), (9, 0)), None));
+    }
+
+    #[test]
+    fn shifted_to_next_working_day() {
+        let weekly = Recurrence {
+            seconds: None,
+            days: Some(7),
+            ..business_days(BusinessDayPolicy::Next, &[])
+        };
+
+        // Saturday 7 October 2023 is moved to Monday, then returns to Saturday's schedule
+        let saturday = local(Tz::Europe__London, (2023, 9, 30), (9, 0));
+        let (moved, shifted_from) = next(&weekly, saturday, None);
+        assert_eq!(moved, naive((2023, 10, 9), (9, 0)));
+        assert_eq!(shifted_from, Some(naive((2023, 10, 7), (9, 0))));
+
+        let moved = local(Tz::Europe__London, (2023, 10, 9), (9, 0));
+        assert_eq!(
+            next(&weekly, moved, shifted_from),
+            (naive((2023, 10, 16), (9, 0)), Some(naive((2023, 10, 14), (9, 0))))
+        );
+    }
+
+    #[test]
+    fn shifted_to_previous_working_day() {
+        let monthly = Recurrence {
+            seconds: None,
+            months: Some(1),
+            ..business_days(BusinessDayPolicy::Previous, &[])
+        };
+
+        // 15 October 2023 is a Sunday, so is moved back to Friday 13th
+        let september = local(Tz::Europe__London, (2023, 9, 15), (9, 0));
+        let (moved, shifted_from) = next(&monthly, september, None);
+        assert_eq!(moved, naive((2023, 10, 13), (9, 0)));
+
+        let moved = local(Tz::Europe__London, (2023, 10, 13), (9, 0));
+        assert_eq!(next(&monthly, moved, shifted_from), (naive((2023, 11, 15), (9, 0)), None));
+    }
+
+    #[test]
+    fn shift_into_past_is_dropped() {
+        let daily = business_days(BusinessDayPolicy::Previous, &[]);
+
+        // Saturday and Sunday both move back onto Friday, which has already been sent
+        let friday = local(Tz::Europe__London, (2023, 10, 6), (9, 0));
+        assert_eq!(next(&daily, friday, None), (naive((2023, 10, 9), (9, 0)), None));
+    }
+
+    #[test]
+    fn no_working_days_stops_repeating() {
+        let mut daily = business_days(BusinessDayPolicy::Skip, &[]);
+        daily.calendar.weekend_days = 0b1111111;
+
+        let time = local(Tz::Europe__London, (2023, 10, 6), (9, 0));
+        assert!(daily.next_after(time, None, time).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+//! A small language for choosing reminders to change in bulk, made of space-separated filters:
+//!
+//! ```text
+//! channel:#general name:"daily standup*" by:@someone after:2023-01-01 before:2023-02-01T09:00
+//! repeating:no enabled:yes
+//! ```
+//!
+//! A reminder must match every filter. Filters on channels and creators may be given more than
+//! once to match any of them. An empty selector matches every reminder
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use sqlx::{Executor, QueryBuilder};
+
+use crate::{recurrence::resolve_local, Database};
+
+const FILTERS: &str = "channel, name, by, after, before, repeating or enabled";
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Selector {
+    channels: Vec<u64>,
+    /// A pattern for SQL `LIKE`
+    name: Option<String>,
+    set_by: Vec<u64>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+    repeating: Option<bool>,
+    enabled: Option<bool>,
+}
+
+/// Split a selector into filters on spaces outside of double quotes
+fn split_filters(selector: &str) -> Result<Vec<String>, String> {
+    let mut filters = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in selector.chars() {
+        match c {
+            '"' => quoted = !quoted,
+
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    filters.push(std::mem::take(&mut current));
+                }
+            }
+
+            c => current.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("A quote in the selector isn't closed".to_string());
+    }
+
+    if !current.is_empty() {
+        filters.push(current);
+    }
+
+    Ok(filters)
+}
+
+/// An ID given as a mention, e.g. `<#123>` or `<@!123>`, or as a number
+fn parse_id(value: &str, prefixes: &[&str]) -> Option<u64> {
+    let id = prefixes
+        .iter()
+        .find_map(|prefix| value.strip_prefix(prefix)?.strip_suffix('>'))
+        .unwrap_or(value);
+
+    id.parse().ok()
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "yes" | "true" | "on" => Ok(true),
+
+        "no" | "false" | "off" => Ok(false),
+
+        _ => Err(format!("`{}` should be yes or no", key)),
+    }
+}
+
+fn parse_time(key: &str, value: &str, timezone: &Tz) -> Result<DateTime<Utc>, String> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()))
+        })
+        .map(|local| resolve_local(timezone, local).with_timezone(&Utc))
+        .map_err(|_| format!("`{}` should be a date like 2023-01-31 or 2023-01-31T09:00", key))
+}
+
+/// Convert a name pattern using `*` and `?` wildcards to a pattern for SQL `LIKE`
+fn like_pattern(glob: &str) -> String {
+    let mut pattern = String::new();
+
+    for c in glob.chars() {
+        match c {
+            '%' | '_' | '\\' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+
+            '*' => pattern.push('%'),
+
+            '?' => pattern.push('_'),
+
+            c => pattern.push(c),
+        }
+    }
+
+    pattern
+}
+
+impl Selector {
+    /// Parse a selector. Dates are in `timezone`, falling back to UTC if it isn't recognised
+    pub fn parse(selector: &str, timezone: &str) -> Result<Self, String> {
+        let timezone: Tz = timezone.parse().unwrap_or(Tz::UTC);
+        let mut parsed = Selector::default();
+
+        for filter in split_filters(selector)? {
+            let (key, value) = filter.split_once(':').ok_or_else(|| {
+                format!(
+                    "`{}` should be a filter like `name:value`. Filters are {}",
+                    filter, FILTERS
+                )
+            })?;
+            let key = key.to_lowercase();
+
+            match key.as_str() {
+                "channel" => parsed.channels.push(
+                    parse_id(value, &["<#"])
+                        .ok_or_else(|| "`channel` should be a channel mention".to_string())?,
+                ),
+
+                "name" => parsed.name = Some(like_pattern(value)),
+
+                "by" => parsed.set_by.push(
+                    parse_id(value, &["<@!", "<@"])
+                        .ok_or_else(|| "`by` should be a user mention".to_string())?,
+                ),
+
+                "after" => parsed.after = Some(parse_time(&key, value, &timezone)?),
+
+                "before" => parsed.before = Some(parse_time(&key, value, &timezone)?),
+
+                "repeating" => parsed.repeating = Some(parse_bool(&key, value)?),
+
+                "enabled" => parsed.enabled = Some(parse_bool(&key, value)?),
+
+                _ => return Err(format!("Unknown filter `{}`. Filters are {}", key, FILTERS)),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// The IDs of the reminders in a guild's channels matched by the selector
+    pub async fn matching(
+        &self,
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: u64,
+    ) -> Result<Vec<u32>, sqlx::Error> {
+        let mut query = QueryBuilder::new(
+            "SELECT reminders.id FROM reminders
+            INNER JOIN channels ON channels.id = reminders.channel_id
+            WHERE channels.guild_id = (SELECT id FROM guilds WHERE guild = ",
+        );
+        query.push_bind(guild_id).push(")");
+
+        if !self.channels.is_empty() {
+            query.push(" AND channels.channel IN (");
+            let mut channels = query.separated(", ");
+            for channel in &self.channels {
+                channels.push_bind(channel);
+            }
+            query.push(")");
+        }
+
+        if let Some(name) = &self.name {
+            query.push(" AND reminders.name LIKE ").push_bind(name);
+        }
+
+        if !self.set_by.is_empty() {
+            query.push(" AND reminders.set_by IN (SELECT id FROM users WHERE user IN (");
+            let mut users = query.separated(", ");
+            for user in &self.set_by {
+                users.push_bind(user);
+            }
+            query.push("))");
+        }
+
+        if let Some(after) = self.after {
+            query.push(" AND reminders.utc_time >= ").push_bind(after);
+        }
+
+        if let Some(before) = self.before {
+            query.push(" AND reminders.utc_time < ").push_bind(before);
+        }
+
+        if let Some(repeating) = self.repeating {
+            query.push(if repeating { " AND " } else { " AND NOT " }).push(
+                "(reminders.interval_seconds IS NOT NULL
+                OR reminders.interval_days IS NOT NULL
+                OR reminders.interval_months IS NOT NULL)",
+            );
+        }
+
+        if let Some(enabled) = self.enabled {
+            query.push(" AND reminders.enabled = ").push_bind(enabled);
+        }
+
+        query.push(" ORDER BY reminders.id");
+
+        query.build_query_scalar().fetch_all(pool).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse_filters() {
+        let selector = Selector::parse(
+            "channel:<#123> channel:456 name:\"daily standup*\" by:<@!789> repeating:no",
+            "UTC",
+        )
+        .unwrap();
+
+        assert_eq!(
+            selector,
+            Selector {
+                channels: vec![123, 456],
+                name: Some("daily standup%".to_string()),
+                set_by: vec![789],
+                repeating: Some(false),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(Selector::parse("  ", "UTC").unwrap(), Selector::default());
+    }
+
+    #[test]
+    fn dates_in_timezone() {
+        let selector =
+            Selector::parse("after:2023-07-01 before:2023-07-01T09:30", "Europe/London").unwrap();
+
+        assert_eq!(selector.after, Some(Utc.with_ymd_and_hms(2023, 6, 30, 23, 0, 0).unwrap()));
+        assert_eq!(selector.before, Some(Utc.with_ymd_and_hms(2023, 7, 1, 8, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn name_wildcards() {
+        assert_eq!(like_pattern("100% d?ne*"), "100\\% d_ne%");
+        assert_eq!(like_pattern("snake_case"), "snake\\_case");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Selector::parse("name:\"unclosed", "UTC").is_err());
+        assert!(Selector::parse("colour:red", "UTC").is_err());
+        assert!(Selector::parse("standup", "UTC").is_err());
+        assert!(Selector::parse("channel:general", "UTC").is_err());
+        assert!(Selector::parse("enabled:maybe", "UTC").is_err());
+        assert!(Selector::parse("after:yesterday", "UTC").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Days, Duration, Months};
+use chrono::DateTime;
 use chrono_tz::Tz;
 use lazy_static::lazy_static;
 use log::{error, info, warn};
@@ -23,7 +23,12 @@ use sqlx::{
     Executor,
 };
 
-use crate::Database;
+use crate::{
+    guild_log::{self, LogEvent},
+    quiet_hours::{self, QuietPolicy},
+    recurrence::{BusinessDayPolicy, Calendar, IntervalPolicy, Recurrence},
+    Database,
+};
 
 lazy_static! {
     pub static ref TIMEFROM_REGEX: Regex =
@@ -235,6 +240,8 @@ pub struct Reminder {
     id: u32,
 
     channel_id: u64,
+    guild_id: Option<u32>,
+    weekend_days: Option<u8>,
     webhook_id: Option<u64>,
     webhook_token: Option<String>,
 
@@ -255,13 +262,21 @@ pub struct Reminder {
     interval_seconds: Option<u32>,
     interval_days: Option<u32>,
     interval_months: Option<u32>,
+    interval_policy: String,
+    business_day_policy: String,
+    shifted_from: Option<NaiveDateTime>,
 
     avatar: Option<String>,
     username: Option<String>,
 }
 
 impl Reminder {
-    pub async fn fetch_reminders(pool: impl Executor<'_, Database = Database> + Copy) -> Vec<Self> {
+    /// Fetch the earliest due reminder of each channel, or only the reminder `id` whether or not it
+    /// is due
+    async fn fetch_by(
+        pool: impl Executor<'_, Database = Database> + Copy,
+        id: Option<u32>,
+    ) -> Vec<Self> {
         match sqlx::query_as_unchecked!(
             Reminder,
             r#"
@@ -269,6 +284,8 @@ SELECT
     reminders.`id` AS id,
 
     channels.`channel` AS channel_id,
+    channels.`guild_id` AS guild_id,
+    guilds.`weekend_days` AS weekend_days,
     channels.`webhook_id` AS webhook_id,
     channels.`webhook_token` AS webhook_token,
 
@@ -289,6 +306,9 @@ SELECT
     reminders.`interval_seconds` AS 'interval_seconds',
     reminders.`interval_days` AS 'interval_days',
     reminders.`interval_months` AS 'interval_months',
+    reminders.`interval_policy` AS interval_policy,
+    reminders.`business_day_policy` AS business_day_policy,
+    reminders.`shifted_from` AS 'shifted_from',
 
     reminders.`avatar` AS avatar,
     reminders.`username` AS username
@@ -298,22 +318,31 @@ INNER JOIN
     channels
 ON
     reminders.channel_id = channels.id
+LEFT JOIN
+    guilds
+ON
+    channels.guild_id = guilds.id
 WHERE
-    reminders.`id` IN (
+    reminders.`id` = ?
+    OR (? IS NULL AND reminders.`id` IN (
         SELECT
             MIN(id)
         FROM
             reminders
         WHERE
             reminders.`utc_time` <= NOW()
+            AND (reminders.`deferred_until` IS NULL OR reminders.`deferred_until` <= NOW())
             AND (
                 reminders.`interval_seconds` IS NOT NULL
+                OR reminders.`interval_days` IS NOT NULL
                 OR reminders.`interval_months` IS NOT NULL
                 OR reminders.enabled
             )
         GROUP BY channel_id
-    )
+    ))
     "#,
+            id,
+            id
         )
         .fetch_all(pool)
         .await
@@ -335,81 +364,225 @@ WHERE
         }
     }
 
+    pub async fn fetch_reminders(pool: impl Executor<'_, Database = Database> + Copy) -> Vec<Self> {
+        Self::fetch_by(pool, None).await
+    }
+
+    /// Fetch a single reminder, whether or not it is due
+    async fn fetch(pool: impl Executor<'_, Database = Database> + Copy, id: u32) -> Option<Self> {
+        Self::fetch_by(pool, Some(id)).await.into_iter().next()
+    }
+
+    /// Forget the channel's webhook. The webhook check tries to recreate it on its next run
     async fn reset_webhook(&self, pool: impl Executor<'_, Database = Database> + Copy) {
         let _ = sqlx::query!(
-            "UPDATE channels SET webhook_id = NULL, webhook_token = NULL WHERE channel = ?",
+            "UPDATE channels
+            SET webhook_id = NULL, webhook_token = NULL, webhook_checked_at = NULL
+            WHERE channel = ?",
             self.channel_id
         )
         .execute(pool)
         .await;
     }
 
-    async fn refresh(&self, pool: impl Executor<'_, Database = Database> + Copy) {
-        if self.interval_seconds.is_some() || self.interval_months.is_some() {
-            let now = Utc::now();
-            let mut updated_reminder_time =
-                self.utc_time.with_timezone(&self.timezone.parse().unwrap_or(Tz::UTC));
-
-            while updated_reminder_time < now {
-                if let Some(interval) = self.interval_months {
-                    updated_reminder_time = updated_reminder_time
-                        .checked_add_months(Months::new(interval))
-                        .unwrap_or_else(|| {
-                            warn!("Could not add months to a reminder");
-
-                            updated_reminder_time
-                        });
+    /// The working days of the reminder's guild. Holidays are only loaded if the reminder
+    /// avoids non-working days
+    async fn calendar(
+        &self,
+        business_days: BusinessDayPolicy,
+        pool: impl Executor<'_, Database = Database> + Copy,
+    ) -> Calendar {
+        let mut calendar = Calendar::default();
+
+        if let Some(weekend_days) = self.weekend_days {
+            calendar.weekend_days = weekend_days;
+        }
+
+        match self.guild_id {
+            Some(guild_id) if business_days != BusinessDayPolicy::Ignore => {
+                match sqlx::query!("SELECT `date` FROM holidays WHERE guild_id = ?", guild_id)
+                    .fetch_all(pool)
+                    .await
+                {
+                    Ok(rows) => {
+                        calendar.holidays = rows.into_iter().map(|row| row.date).collect();
+                    }
+
+                    Err(e) => {
+                        warn!("Could not fetch holidays for Reminder {}: {:?}", self.id, e);
+                    }
                 }
+            }
+
+            _ => {}
+        }
 
-                if let Some(interval) = self.interval_days {
-                    updated_reminder_time = updated_reminder_time
-                        .checked_add_days(Days::new(interval as u64))
-                        .unwrap_or_else(|| {
-                            warn!("Could not add days to a reminder");
+        calendar
+    }
 
-                            updated_reminder_time
-                        });
+    async fn refresh(&self, pool: impl Executor<'_, Database = Database> + Copy) {
+        if self.interval_seconds.is_some()
+            || self.interval_days.is_some()
+            || self.interval_months.is_some()
+        {
+            let business_days = BusinessDayPolicy::from(self.business_day_policy.as_str());
+
+            let recurrence = Recurrence {
+                timezone: self.timezone.parse().unwrap_or(Tz::UTC),
+                policy: IntervalPolicy::from(self.interval_policy.as_str()),
+                seconds: self.interval_seconds,
+                days: self.interval_days,
+                months: self.interval_months,
+                business_days,
+                calendar: self.calendar(business_days, pool).await,
+            };
+
+            match recurrence.next_after(self.utc_time, self.shifted_from, Utc::now()) {
+                Some((updated_reminder_time, _))
+                    if self.expires.map_or(false, |expires| updated_reminder_time > expires) =>
+                {
+                    self.force_delete(pool).await;
                 }
 
-                if let Some(interval) = self.interval_seconds {
-                    updated_reminder_time =
-                        updated_reminder_time + Duration::seconds(interval as i64);
+                Some((updated_reminder_time, shifted_from)) => {
+                    sqlx::query!(
+                        "UPDATE reminders SET `utc_time` = ?, `shifted_from` = ?, `deferred_until` = NULL
+                        WHERE `id` = ?",
+                        updated_reminder_time,
+                        shifted_from,
+                        self.id
+                    )
+                    .execute(pool)
+                    .await
+                    .expect(&format!("Could not update time on Reminder {}", self.id));
                 }
-            }
 
-            if self.expires.map_or(false, |expires| updated_reminder_time > expires) {
-                self.force_delete(pool).await;
-            } else {
-                sqlx::query!(
-                    "UPDATE reminders SET `utc_time` = ? WHERE `id` = ?",
-                    updated_reminder_time.with_timezone(&Utc),
-                    self.id
-                )
-                .execute(pool)
-                .await
-                .expect(&format!("Could not update time on Reminder {}", self.id));
+                None => {
+                    warn!("Could not apply interval to Reminder {}. Deleting", self.id);
+
+                    self.force_delete(pool).await;
+                }
             }
         } else {
             self.force_delete(pool).await;
         }
     }
 
+    /// Hold the reminder until the end of quiet hours, keeping its schedule
+    async fn defer(
+        &self,
+        pool: impl Executor<'_, Database = Database> + Copy,
+        until: DateTime<Utc>,
+    ) {
+        if let Err(e) =
+            sqlx::query!("UPDATE reminders SET `deferred_until` = ? WHERE `id` = ?", until, self.id)
+                .execute(pool)
+                .await
+        {
+            warn!("Could not defer Reminder {}: {:?}", self.id, e);
+
+            self.refresh(pool).await;
+        }
+    }
+
+    /// Add the reminder to the summary sent at the end of quiet hours, then move it on as if sent
+    async fn batch(
+        &self,
+        pool: impl Executor<'_, Database = Database> + Copy,
+        until: DateTime<Utc>,
+    ) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO quiet_hours_batch (channel_id, name, content, due_time, release_time)
+            SELECT channel_id, name, ?, utc_time, ? FROM reminders WHERE id = ?",
+            self.content,
+            until,
+            self.id
+        )
+        .execute(pool)
+        .await
+        {
+            warn!("Could not batch Reminder {}: {:?}", self.id, e);
+        }
+
+        self.refresh(pool).await;
+    }
+
     async fn force_delete(&self, pool: impl Executor<'_, Database = Database> + Copy) {
         sqlx::query!("DELETE FROM reminders WHERE `id` = ?", self.id)
             .execute(pool)
             .await
             .expect(&format!("Could not delete Reminder {}", self.id));
+
+        // countdowns are finished once the reminder for their last phase is gone
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM timers
+            WHERE end_time <= NOW()
+                AND NOT EXISTS (SELECT 1 FROM reminders WHERE reminders.timer_id = timers.id)"
+        )
+        .execute(pool)
+        .await
+        {
+            warn!("Could not remove finished countdowns: {:?}", e);
+        }
+    }
+
+    /// Delete a reminder that can't be delivered, and post the removal to the log channel of the
+    /// guild it was set in. Reminders sent to DMs are found in their guild by their audit record
+    async fn remove_undeliverable(
+        &self,
+        pool: impl Executor<'_, Database = Database> + Copy,
+        http: &Http,
+        reason: &str,
+    ) {
+        let origin = sqlx::query!(
+            "SELECT reminders.name, guilds.guild
+            FROM reminders
+            INNER JOIN channels ON channels.id = reminders.channel_id
+            LEFT JOIN reminder_audit
+                ON reminder_audit.reminder_uid = reminders.uid AND reminder_audit.action = 'create'
+            INNER JOIN guilds ON guilds.id = IFNULL(channels.guild_id, reminder_audit.guild_id)
+            WHERE reminders.id = ?
+            LIMIT 1",
+            self.id
+        )
+        .fetch_optional(pool)
+        .await;
+
+        self.force_delete(pool).await;
+
+        match origin {
+            Ok(Some(origin)) => {
+                guild_log::post(
+                    http,
+                    pool,
+                    origin.guild,
+                    LogEvent::Removed,
+                    format!(
+                        "\"{}\" for <#{}> was removed because {}",
+                        origin.name, self.channel_id, reason
+                    ),
+                )
+                .await;
+            }
+
+            Ok(None) => {}
+
+            Err(e) => {
+                warn!("Could not find guild of Reminder {}: {:?}", self.id, e);
+            }
+        }
     }
 
     async fn pin_message<M: Into<u64>>(&self, message_id: M, http: impl AsRef<Http>) {
         let _ = http.as_ref().pin_message(self.channel_id, message_id.into(), None).await;
     }
 
-    pub async fn send(
+    /// Send the reminder's message to its channel, through the channel's webhook if it has one
+    async fn deliver(
         &self,
         pool: impl Executor<'_, Database = Database> + Copy,
         cache_http: impl CacheHttp,
-    ) {
+    ) -> Result<()> {
         async fn send_to_channel(
             cache_http: impl CacheHttp,
             reminder: &Reminder,
@@ -531,6 +704,30 @@ WHERE
             }
         }
 
+        let embed = Embed::from_id(pool, self.id).await.map(|e| e.into());
+
+        if let (Some(webhook_id), Some(webhook_token)) = (self.webhook_id, &self.webhook_token) {
+            let webhook_res =
+                cache_http.http().get_webhook_with_token(webhook_id, webhook_token).await;
+
+            if let Ok(webhook) = webhook_res {
+                send_to_webhook(&cache_http, &self, webhook, embed).await
+            } else {
+                warn!("Webhook vanished: {:?}", webhook_res);
+
+                self.reset_webhook(pool).await;
+                send_to_channel(&cache_http, &self, embed).await
+            }
+        } else {
+            send_to_channel(&cache_http, &self, embed).await
+        }
+    }
+
+    pub async fn send(
+        &self,
+        pool: impl Executor<'_, Database = Database> + Copy,
+        cache_http: impl CacheHttp,
+    ) {
         if self.enabled
             && !(self.channel_paused
                 && self
@@ -544,25 +741,23 @@ WHERE
             .execute(pool)
             .await;
 
-            let embed = Embed::from_id(pool, self.id).await.map(|e| e.into());
-
-            let result = if let (Some(webhook_id), Some(webhook_token)) =
-                (self.webhook_id, &self.webhook_token)
+            if let Some((policy, end)) =
+                quiet_hours::active_for_channel(pool, self.channel_id, Utc::now()).await
             {
-                let webhook_res =
-                    cache_http.http().get_webhook_with_token(webhook_id, webhook_token).await;
+                info!("Reminder {} is in quiet hours ({})", self.id, policy.as_str());
 
-                if let Ok(webhook) = webhook_res {
-                    send_to_webhook(cache_http, &self, webhook, embed).await
-                } else {
-                    warn!("Webhook vanished: {:?}", webhook_res);
+                match policy {
+                    QuietPolicy::Drop => self.refresh(pool).await,
+
+                    QuietPolicy::Defer => self.defer(pool, end).await,
 
-                    self.reset_webhook(pool).await;
-                    send_to_channel(cache_http, &self, embed).await
+                    QuietPolicy::Batch => self.batch(pool, end).await,
                 }
-            } else {
-                send_to_channel(cache_http, &self, embed).await
-            };
+
+                return;
+            }
+
+            let result = self.deliver(pool, &cache_http).await;
 
             if let Err(e) = result {
                 error!("Error sending reminder {}: {:?}", self.id, e);
@@ -570,11 +765,21 @@ WHERE
                 if let Error::Http(error) = e {
                     if error.status_code() == Some(StatusCode::NOT_FOUND) {
                         warn!("Seeing channel is deleted. Removing reminder");
-                        self.force_delete(pool).await;
+                        self.remove_undeliverable(
+                            pool,
+                            cache_http.http(),
+                            "its channel no longer exists",
+                        )
+                        .await;
                     } else if let HttpError::UnsuccessfulRequest(error) = *error {
                         if error.error.code == 50007 {
                             warn!("User cannot receive DMs");
-                            self.force_delete(pool).await;
+                            self.remove_undeliverable(
+                                pool,
+                                cache_http.http(),
+                                "the user it was for doesn't accept DMs",
+                            )
+                            .await;
                         } else {
                             self.refresh(pool).await;
                         }
@@ -592,3 +797,38 @@ WHERE
         }
     }
 }
+
+/// Send a reminder straight away, without changing when it is next sent. With `channel_id`, the
+/// reminder is sent to that channel instead of its own, through that channel's webhook if it has
+/// one. Unlike scheduled sends, disabled and paused reminders are sent, and quiet hours are ignored
+pub async fn send_now(
+    pool: impl Executor<'_, Database = Database> + Copy,
+    cache_http: impl CacheHttp,
+    id: u32,
+    channel_id: Option<u64>,
+) -> std::result::Result<(), String> {
+    let mut reminder = Reminder::fetch(pool, id).await.ok_or("Reminder not found")?;
+
+    if let Some(channel_id) = channel_id {
+        let webhook = sqlx::query!(
+            "SELECT webhook_id, webhook_token FROM channels WHERE channel = ?",
+            channel_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let (webhook_id, webhook_token) =
+            webhook.map_or((None, None), |w| (w.webhook_id, w.webhook_token));
+
+        reminder.channel_id = channel_id;
+        reminder.webhook_id = webhook_id;
+        reminder.webhook_token = webhook_token;
+    }
+
+    reminder.deliver(pool, cache_http).await.map_err(|e| {
+        warn!("Error sending reminder {} now: {:?}", id, e);
+
+        e.to_string()
+    })
+}
@@ -0,0 +1,244 @@
+//! Checks that the webhooks stored for channels still exist, so that a deleted webhook is found
+//! before a reminder has to be sent without its username and avatar. Missing webhooks are recreated
+//! where the bot has Manage Webhooks. Channels that can't be repaired have the problem stored in
+//! `channels.webhook_error` for the dashboard, and posted to the guild's log channel
+
+use std::env;
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serenity::{
+    client::Context,
+    http::StatusCode,
+    model::{channel::Channel, id::ChannelId, prelude::AttachmentType},
+    Error,
+};
+use sqlx::Executor;
+use tokio::time::{sleep_until, Duration, Instant};
+
+use crate::{
+    guild_log::{self, LogEvent},
+    Database,
+};
+
+// Channels checked every `RUN_INTERVAL` seconds, to spread the requests to Discord out
+const CHECKS_PER_RUN: u32 = 5;
+const RUN_INTERVAL: u64 = 30;
+
+lazy_static! {
+    static ref DEFAULT_AVATAR: AttachmentType<'static> = (
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/",
+            env!("WEBHOOK_AVATAR", "WEBHOOK_AVATAR not provided for compilation")
+        )) as &[u8],
+        env!("WEBHOOK_AVATAR"),
+    )
+        .into();
+    static ref CHECK_INTERVAL: u64 = env::var("WEBHOOK_CHECK_INTERVAL")
+        .map(|inner| inner.parse::<u64>().ok())
+        .ok()
+        .flatten()
+        .unwrap_or(86_400);
+}
+
+enum Health {
+    Healthy,
+    Repaired {
+        webhook_id: u64,
+        webhook_token: Option<String>,
+    },
+    /// The webhook is missing and can't be recreated
+    Broken(String),
+    /// Discord couldn't be asked, so the channel is checked again later
+    Unknown,
+}
+
+fn is_not_found(error: &Error) -> bool {
+    matches!(error, Error::Http(error) if error.status_code() == Some(StatusCode::NOT_FOUND))
+}
+
+async fn repair(ctx: &Context, channel_id: u64) -> Health {
+    let channel = match ChannelId(channel_id).to_channel(ctx).await {
+        Ok(Channel::Guild(channel)) => channel,
+
+        Ok(_) => return Health::Unknown,
+
+        Err(e) => {
+            // deleted channels are left to the postman, which removes their reminders
+            if !is_not_found(&e) {
+                warn!("Could not fetch channel {} to repair its webhook: {:?}", channel_id, e);
+            }
+
+            return Health::Unknown;
+        }
+    };
+
+    match channel.permissions_for_user(ctx, ctx.cache.current_user_id()) {
+        Ok(permissions) if permissions.manage_webhooks() => {}
+
+        Ok(_) => {
+            return Health::Broken(
+                "the bot needs the Manage Webhooks permission to recreate its webhook".to_string(),
+            );
+        }
+
+        Err(_) => return Health::Unknown,
+    }
+
+    match channel.create_webhook_with_avatar(&ctx.http, "Reminder", DEFAULT_AVATAR.clone()).await {
+        Ok(webhook) => Health::Repaired { webhook_id: webhook.id.0, webhook_token: webhook.token },
+
+        Err(e) => {
+            warn!("Could not recreate webhook of {}: {:?}", channel_id, e);
+
+            Health::Broken("Discord wouldn't let the bot recreate its webhook".to_string())
+        }
+    }
+}
+
+async fn check(
+    ctx: &Context,
+    channel_id: u64,
+    webhook_id: Option<u64>,
+    webhook_token: Option<&str>,
+) -> Health {
+    if let (Some(webhook_id), Some(webhook_token)) = (webhook_id, webhook_token) {
+        match ctx.http.get_webhook_with_token(webhook_id, webhook_token).await {
+            Ok(_) => return Health::Healthy,
+
+            Err(e) if is_not_found(&e) => {}
+
+            Err(e) => {
+                warn!("Could not check webhook of {}: {:?}", channel_id, e);
+
+                return Health::Unknown;
+            }
+        }
+    }
+
+    repair(ctx, channel_id).await
+}
+
+/// Keep checking the webhooks of channels, a few at a time
+pub async fn check_webhooks(pool: impl Executor<'_, Database = Database> + Copy, ctx: &Context) {
+    loop {
+        let sleep_to = Instant::now() + Duration::from_secs(RUN_INTERVAL);
+
+        check_next(pool, ctx).await;
+
+        sleep_until(sleep_to).await;
+    }
+}
+
+/// Check the webhooks of the channels that have gone longest without a check. Channels without
+/// reminders aren't checked, and channels whose webhook was reset are checked first
+async fn check_next(pool: impl Executor<'_, Database = Database> + Copy, ctx: &Context) {
+    let channels = match sqlx::query!(
+        "
+SELECT
+    channels.id,
+    channels.channel,
+    channels.webhook_id,
+    channels.webhook_token,
+    channels.webhook_error,
+    guilds.guild
+FROM channels
+INNER JOIN guilds ON guilds.id = channels.guild_id
+WHERE
+    (channels.webhook_checked_at IS NULL
+        OR channels.webhook_checked_at < DATE_SUB(NOW(), INTERVAL ? SECOND))
+    AND EXISTS (SELECT 1 FROM reminders WHERE reminders.channel_id = channels.id)
+ORDER BY channels.webhook_checked_at
+LIMIT ?",
+        *CHECK_INTERVAL,
+        CHECKS_PER_RUN
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(channels) => channels,
+
+        Err(e) => {
+            warn!("Could not fetch channels to check webhooks of: {:?}", e);
+
+            return;
+        }
+    };
+
+    for channel in channels {
+        let health =
+            check(ctx, channel.channel, channel.webhook_id, channel.webhook_token.as_deref()).await;
+
+        let result = match &health {
+            Health::Healthy => {
+                sqlx::query!(
+                "UPDATE channels SET webhook_checked_at = NOW(), webhook_error = NULL WHERE id = ?",
+                channel.id
+            )
+                .execute(pool)
+                .await
+            }
+
+            Health::Repaired { webhook_id, webhook_token } => {
+                info!("Recreated webhook of {}", channel.channel);
+
+                sqlx::query!(
+                    "
+UPDATE channels
+SET webhook_id = ?, webhook_token = ?, webhook_checked_at = NOW(), webhook_error = NULL
+WHERE id = ?",
+                    webhook_id,
+                    webhook_token,
+                    channel.id
+                )
+                .execute(pool)
+                .await
+            }
+
+            Health::Broken(reason) => {
+                sqlx::query!(
+                    "
+UPDATE channels
+SET webhook_id = NULL, webhook_token = NULL, webhook_checked_at = NOW(), webhook_error = ?
+WHERE id = ?",
+                    reason,
+                    channel.id
+                )
+                .execute(pool)
+                .await
+            }
+
+            Health::Unknown => {
+                sqlx::query!(
+                    "UPDATE channels SET webhook_checked_at = NOW() WHERE id = ?",
+                    channel.id
+                )
+                .execute(pool)
+                .await
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Could not store webhook check of {}: {:?}", channel.channel, e);
+        }
+
+        // only report a problem when it is first found, not on every check
+        if let Health::Broken(reason) = health {
+            if channel.webhook_error.as_deref() != Some(reason.as_str()) {
+                guild_log::post(
+                    &ctx.http,
+                    pool,
+                    channel.guild,
+                    LogEvent::Webhook,
+                    format!(
+                        "Reminders in <#{}> will be sent without their username and avatar, \
+                        because {}",
+                        channel.channel, reason
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+}
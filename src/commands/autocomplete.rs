@@ -2,22 +2,45 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use chrono_tz::TZ_VARIANTS;
 use poise::AutocompleteChoice;
+use postman::guild_log::LogEvent;
 
-use crate::{models::CtxData, time_parser::natural_parser, Context};
+use crate::{models::CtxData, time_parser::natural_parser, timezone_resolver, Context};
 
-pub async fn timezone_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
+pub async fn timezone_autocomplete(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<AutocompleteChoice<String>> {
     if partial.is_empty() {
-        ctx.data().popular_timezones.iter().map(|t| t.to_string()).collect::<Vec<String>>()
+        ctx.data()
+            .popular_timezones
+            .iter()
+            .map(|t| AutocompleteChoice { name: t.to_string(), value: t.to_string() })
+            .collect()
     } else {
-        TZ_VARIANTS
+        let mut choices = timezone_resolver::suggest(partial)
             .iter()
-            .filter(|tz| tz.to_string().contains(&partial))
-            .take(25)
-            .map(|t| t.to_string())
-            .collect::<Vec<String>>()
+            .map(|m| AutocompleteChoice { name: m.label(), value: m.timezone.to_string() })
+            .collect::<Vec<AutocompleteChoice<String>>>();
+
+        for tz in TZ_VARIANTS.iter().filter(|tz| tz.to_string().contains(&partial)) {
+            if !choices.iter().any(|c| c.value == tz.name()) {
+                choices.push(AutocompleteChoice { name: tz.to_string(), value: tz.to_string() });
+            }
+        }
+
+        choices.truncate(25);
+        choices
     }
 }
 
+pub async fn log_event_autocomplete(_ctx: Context<'_>, partial: &str) -> Vec<String> {
+    LogEvent::ALL
+        .iter()
+        .map(|event| event.name().to_string())
+        .filter(|name| name.starts_with(partial))
+        .collect()
+}
+
 pub async fn macro_name_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
     sqlx::query!(
         "
@@ -37,6 +60,89 @@ WHERE
     .collect()
 }
 
+pub async fn reminder_name_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    sqlx::query!(
+        "
+SELECT reminders.name
+FROM reminders
+INNER JOIN channels ON channels.id = reminders.channel_id
+WHERE
+    channels.channel = ?
+    AND reminders.name LIKE CONCAT(?, '%')
+ORDER BY reminders.name
+LIMIT 25",
+        ctx.reminder_channel().0,
+        partial,
+    )
+    .fetch_all(&ctx.data().database)
+    .await
+    .unwrap_or_default()
+    .iter()
+    .map(|s| s.name.clone())
+    .collect()
+}
+
+pub async fn template_name_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    match ctx.guild_id() {
+        Some(guild_id) => sqlx::query!(
+            "
+SELECT name
+FROM reminder_template
+WHERE
+    guild_id = (SELECT id FROM guilds WHERE guild = ?)
+    AND name LIKE CONCAT(?, '%')
+ORDER BY name
+LIMIT 25",
+            guild_id.0,
+            partial,
+        )
+        .fetch_all(&ctx.data().database)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|s| s.name.clone())
+        .collect(),
+
+        None => vec![],
+    }
+}
+
+pub async fn persona_autocomplete(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    match ctx.guild_id() {
+        Some(guild_id) => sqlx::query!(
+            "
+SELECT name
+FROM persona
+WHERE
+    guild_id = (SELECT id FROM guilds WHERE guild = ?)
+    AND name LIKE CONCAT(?, '%')
+ORDER BY name
+LIMIT 25",
+            guild_id.0,
+            partial,
+        )
+        .fetch_all(&ctx.data().database)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|s| s.name.clone())
+        .collect(),
+
+        None => vec![],
+    }
+}
+
+/// Roughly how long until a time `diff` seconds from now, to show that a time was understood
+pub fn time_hint(diff: i64) -> String {
+    if diff > 86400 {
+        format!("In approximately {} days, {} hours", diff / 86400, (diff % 86400) / 3600)
+    } else if diff > 3600 {
+        format!("In approximately {} hours", diff / 3600)
+    } else {
+        format!("In approximately {} minutes", diff / 60)
+    }
+}
+
 pub async fn time_hint_autocomplete(
     ctx: Context<'_>,
     partial: &str,
@@ -58,44 +164,16 @@ pub async fn time_hint_autocomplete(
                             value: "now".to_string(),
                         }]
                     } else {
-                        if diff > 86400 {
-                            vec![
-                                AutocompleteChoice {
-                                    name: partial.to_string(),
-                                    value: partial.to_string(),
-                                },
-                                AutocompleteChoice {
-                                    name: format!(
-                                        "In approximately {} days, {} hours",
-                                        diff / 86400,
-                                        (diff % 86400) / 3600
-                                    ),
-                                    value: partial.to_string(),
-                                },
-                            ]
-                        } else if diff > 3600 {
-                            vec![
-                                AutocompleteChoice {
-                                    name: partial.to_string(),
-                                    value: partial.to_string(),
-                                },
-                                AutocompleteChoice {
-                                    name: format!("In approximately {} hours", diff / 3600),
-                                    value: partial.to_string(),
-                                },
-                            ]
-                        } else {
-                            vec![
-                                AutocompleteChoice {
-                                    name: partial.to_string(),
-                                    value: partial.to_string(),
-                                },
-                                AutocompleteChoice {
-                                    name: format!("In approximately {} minutes", diff / 60),
-                                    value: partial.to_string(),
-                                },
-                            ]
-                        }
+                        vec![
+                            AutocompleteChoice {
+                                name: partial.to_string(),
+                                value: partial.to_string(),
+                            },
+                            AutocompleteChoice {
+                                name: time_hint(diff),
+                                value: partial.to_string(),
+                            },
+                        ]
                     }
                 }
                 Err(_) => {
@@ -1,4 +1,7 @@
-use crate::{Context, Error};
+use poise::serenity_prelude::CreateApplicationCommandOption;
+use reminder_web::{MacroCommand, MacroCommandParameter, MacroCommands};
+
+use crate::{Context, Data, Error};
 
 pub mod delete;
 pub mod list;
@@ -17,3 +20,51 @@ pub mod run;
 pub async fn macro_base(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
+
+/// Collect the slash commands that can be ran as steps of a macro, so that macros edited from the
+/// dashboard can be validated
+pub fn macro_commands(commands: &[poise::Command<Data, Error>]) -> MacroCommands {
+    fn collect(commands: &[poise::Command<Data, Error>], macro_commands: &mut MacroCommands) {
+        for command in commands {
+            // macros cannot record or run other macros
+            if command.identifying_name == "macro_base" {
+                continue;
+            }
+
+            if command.subcommands.is_empty() {
+                if command.slash_action.is_some() {
+                    macro_commands.insert(
+                        command.identifying_name.clone(),
+                        MacroCommand {
+                            qualified_name: command.qualified_name.clone(),
+                            parameters: command
+                                .parameters
+                                .iter()
+                                .map(|p| MacroCommandParameter {
+                                    name: p.name.clone(),
+                                    required: p.required,
+                                    kind: p.type_setter.and_then(|type_setter| {
+                                        let mut option = CreateApplicationCommandOption::default();
+                                        type_setter(&mut option);
+
+                                        option.0.get("type").and_then(|kind| {
+                                            serde_json::from_value(kind.clone()).ok()
+                                        })
+                                    }),
+                                    choices: p.choices.iter().map(|c| c.name.clone()).collect(),
+                                })
+                                .collect(),
+                        },
+                    );
+                }
+            } else {
+                collect(&command.subcommands, macro_commands);
+            }
+        }
+    }
+
+    let mut macro_commands = MacroCommands::new();
+    collect(commands, &mut macro_commands);
+
+    macro_commands
+}
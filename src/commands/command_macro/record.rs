@@ -1,8 +1,12 @@
 use std::collections::hash_map::Entry;
 
-use crate::{consts::THEME_COLOR, models::command_macro::CommandMacro, Context, Error};
+use crate::{
+    consts::{MACRO_MAX_COMMANDS, MACRO_MAX_PARAMETERS, THEME_COLOR},
+    models::command_macro::CommandMacro,
+    Context, Error,
+};
 
-/// Start recording up to 5 commands to replay
+/// Start recording commands to replay
 #[poise::command(
     slash_command,
     rename = "record",
@@ -14,6 +18,8 @@ pub async fn record_macro(
     ctx: Context<'_>,
     #[description = "Name for the new macro"] name: String,
     #[description = "Description for the new macro"] description: Option<String>,
+    #[description = "Stop running the macro if a command fails (default: continue)"]
+    abort_on_failure: Option<bool>,
 ) -> Result<(), Error> {
     if name.len() > 100 {
         ctx.say("Name must be less than 100 characters").await?;
@@ -55,7 +61,13 @@ Please select a unique name for your macro.",
             let mut lock = ctx.data().recording_macros.write().await;
 
             if let Entry::Vacant(e) = lock.entry((guild_id, ctx.author().id)) {
-                e.insert(CommandMacro { guild_id, name, description, commands: vec![] });
+                e.insert(CommandMacro {
+                    guild_id,
+                    name,
+                    description,
+                    abort_on_failure: abort_on_failure.unwrap_or(false),
+                    commands: vec![],
+                });
                 true
             } else {
                 false
@@ -67,8 +79,9 @@ Please select a unique name for your macro.",
                 m.ephemeral(true).embed(|e| {
                     e.title("Macro Recording Started")
                         .description(
-                            "Run up to 5 commands, or type `/macro finish` to stop at any point.
-Any commands ran as part of recording will be inconsequential",
+                            format!("Run up to {} commands, or type `/macro finish` to stop at any point.
+Any commands ran as part of recording will be inconsequential.
+Write placeholders such as `{{time}}` or `{{channel}}` into options to fill them in each time the macro is ran", *MACRO_MAX_COMMANDS),
                         )
                         .color(*THEME_COLOR)
                 })
@@ -116,15 +129,28 @@ pub async fn finish_macro(ctx: Context<'_>) -> Result<(), Error> {
                 })
             })
             .await?;
+        } else if contained.unwrap().parameters().len() > MACRO_MAX_PARAMETERS {
+            ctx.send(|m| {
+                m.embed(|e| {
+                    e.title("Too Many Parameters")
+                        .description(format!(
+                            "Macros can use at most {} different placeholders. Please record the macro again using fewer placeholders.",
+                            MACRO_MAX_PARAMETERS
+                        ))
+                        .color(*THEME_COLOR)
+                })
+            })
+            .await?;
         } else {
             let command_macro = contained.unwrap();
             let json = serde_json::to_string(&command_macro.commands).unwrap();
 
             sqlx::query!(
-                "INSERT INTO macro (guild_id, name, description, commands) VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?)",
+                "INSERT INTO macro (guild_id, name, description, abort_on_failure, commands) VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?)",
                 command_macro.guild_id.0,
                 command_macro.name,
                 command_macro.description,
+                command_macro.abort_on_failure,
                 json
             )
                 .execute(&ctx.data().database)
@@ -1,6 +1,75 @@
+use std::collections::HashMap;
+
+use log::warn;
+use poise::{
+    serenity_prelude::{
+        model::application::{
+            component::{ActionRowComponent, InputTextStyle},
+            interaction::{modal::ModalSubmitInteractionData, InteractionResponseType},
+        },
+        CreateInteractionResponse,
+    },
+    FrameworkError, Modal,
+};
+
 use super::super::autocomplete::macro_name_autocomplete;
 use crate::{models::command_macro::guild_command_macro, Context, Data, Error, THEME_COLOR};
 
+/// Values for the `{parameter}` placeholders of a macro, collected through a modal
+struct ParameterModal {
+    parameters: Vec<(String, String)>,
+}
+
+impl Modal for ParameterModal {
+    fn create(defaults: Option<Self>, custom_id: String) -> CreateInteractionResponse<'static> {
+        let parameters = defaults.map_or(vec![], |d| d.parameters);
+
+        let mut b = CreateInteractionResponse::default();
+        b.kind(InteractionResponseType::Modal).interaction_response_data(|d| {
+            d.custom_id(custom_id).title("Macro Parameters").components(|c| {
+                for (name, value) in parameters {
+                    c.create_action_row(|r| {
+                        r.create_input_text(|t| {
+                            t.custom_id(&name)
+                                .label(&name)
+                                .style(InputTextStyle::Short)
+                                .required(true)
+                                .max_length(1000)
+                                .value(value)
+                        })
+                    });
+                }
+
+                c
+            })
+        });
+
+        b
+    }
+
+    fn parse(data: ModalSubmitInteractionData) -> Result<Self, &'static str> {
+        let parameters = data
+            .components
+            .into_iter()
+            .flat_map(|row| row.components)
+            .filter_map(|component| match component {
+                ActionRowComponent::InputText(input) => Some((input.custom_id, input.value)),
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self { parameters })
+    }
+}
+
+fn describe_error(error: &FrameworkError<'_, Data, Error>) -> String {
+    match error {
+        FrameworkError::Command { error, .. } => error.to_string(),
+        FrameworkError::ArgumentParse { error, .. } => format!("invalid options ({})", error),
+        _ => "unknown error".to_string(),
+    }
+}
+
 /// Run a recorded macro
 #[poise::command(
     slash_command,
@@ -16,7 +85,25 @@ pub async fn run_macro(
     name: String,
 ) -> Result<(), Error> {
     match guild_command_macro(&Context::Application(ctx), &name).await {
-        Some(command_macro) => {
+        Some(mut command_macro) => {
+            let parameters = command_macro.parameters();
+
+            if !parameters.is_empty() {
+                let defaults = ParameterModal {
+                    parameters: parameters.into_iter().map(|p| (p, String::new())).collect(),
+                };
+                let values: HashMap<String, String> =
+                    ParameterModal::execute_with_defaults(ctx, defaults)
+                        .await?
+                        .parameters
+                        .into_iter()
+                        .collect();
+
+                for command in &mut command_macro.commands {
+                    command.substitute(&values);
+                }
+            }
+
             Context::Application(ctx)
                 .send(|b| {
                     b.embed(|e| {
@@ -29,22 +116,52 @@ pub async fn run_macro(
                 })
                 .await?;
 
+            let mut outcomes = vec![];
+            let mut failed = false;
+
             for command in command_macro.commands {
+                if failed && command_macro.abort_on_failure {
+                    outcomes.push(format!("⏭️ `{}` skipped", command.command_name));
+                    continue;
+                }
+
                 if let Some(action) = command.action {
                     match (action)(poise::ApplicationContext { args: &command.options, ..ctx })
                         .await
                     {
-                        Ok(()) => {}
+                        Ok(()) => {
+                            outcomes.push(format!("✅ `{}` succeeded", command.command_name));
+                        }
                         Err(e) => {
-                            println!("{:?}", e);
+                            warn!("Macro command failed: {:?}", e);
+
+                            failed = true;
+                            outcomes.push(format!(
+                                "❌ `{}` failed: {}",
+                                command.command_name,
+                                describe_error(&e)
+                            ));
                         }
                     }
                 } else {
-                    Context::Application(ctx)
-                        .say(format!("Command \"{}\" not found", command.command_name))
-                        .await?;
+                    failed = true;
+                    outcomes.push(format!("❌ `{}` not found", command.command_name));
                 }
             }
+
+            Context::Application(ctx)
+                .send(|b| {
+                    b.embed(|e| {
+                        e.title(if failed {
+                            "Macro Finished With Errors"
+                        } else {
+                            "Macro Finished"
+                        })
+                        .color(*THEME_COLOR)
+                        .description(outcomes.join("\n"))
+                    })
+                })
+                .await?;
         }
 
         None => {
@@ -34,22 +34,34 @@ pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
 *run these commands with no options*
 
 __Reminder Commands__
-`/remind` - Create a new reminder that will send a message at a certain time
-`/timer` - Start a timer from now, that will count time passed. Also used to view and remove timers
+`/remind` - Create a new reminder that will send a message at a certain time. Give it a `name` to refer to it later
+`/timer` - Start a timer from now, that will count time passed, or a countdown that sends a reminder when it completes. Also used to view and remove timers
+`/remind-embed` - Create a reminder with an embed, previewing it before it is saved
+`/template` - Save a reminder as a template, to create similar reminders with `/remind template:`
+`/channeltimer` - View and manage timers shared with everyone in a channel
+`Remind me about this` - From a message's Apps menu, get a DM reminder about the message
 
 __Reminder Management__
 `/del` - Delete reminders
-`/look` - View reminders
+`/reminder` - Show, edit, test-send, enable, disable or delete a reminder in this channel by its name
+`/look` - View reminders, searching and filtering by creator, due time or whether they repeat
 `/pause` - Pause all reminders on the channel
-`/offset` - Move all reminders by a certain time
+`/offset` - Move all reminders, or those matching a selector, by a certain time
+`/bulk` - Enable, disable or delete the reminders matching a selector, e.g. `channel:#general name:standup* by:@someone after:2023-01-01 repeating:yes enabled:no`
 `/nudge` - Move all new reminders on this channel by a certain time
+`/audit` - View who created, edited and deleted reminders
 
 __Todo Commands__
 `/todo` - Add, view and manage the server, channel or user todo lists
 
 __Setup Commands__
-`/timezone` - Set your timezone (necessary for `/remind` to work properly)
+`/timezone user` - Set your timezone (necessary for `/remind` to work properly)
+`/timezone server` - Set a timezone for everyone in the server
+`/timezone channel` - Set a timezone for everyone in a channel
 `/dm allow/block` - Change your DM settings for reminders.
+`/logchannel` - Post reminder activity in the server to a channel
+`/persona` - Save display names and avatars for reminders to be sent with, using `/remind username:`
+`/quiethours` - Hold reminders in the server or a channel during nights, weekends or other quiet hours
 
 __Advanced Commands__
 `/macro` - Record and replay command sequences
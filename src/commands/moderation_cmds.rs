@@ -1,16 +1,119 @@
-use chrono::offset::Utc;
+use chrono::{offset::Utc, NaiveTime};
 use chrono_tz::{Tz, TZ_VARIANTS};
 use levenshtein::levenshtein;
 use log::warn;
+use poise::serenity_prelude::Channel;
+use postman::{
+    guild_log::LogEvent,
+    quiet_hours::{describe_days, parse_days, QuietPolicy},
+};
 
-use super::autocomplete::timezone_autocomplete;
-use crate::{consts::THEME_COLOR, models::CtxData, Context, Error};
+use super::autocomplete::{log_event_autocomplete, persona_autocomplete, timezone_autocomplete};
+use crate::{
+    consts::{EMBED_DESCRIPTION_MAX_LENGTH, MAX_QUIET_HOURS, THEME_COLOR},
+    models::{
+        persona::{identity_error, Persona},
+        CtxData,
+    },
+    refuse, timezone_resolver, Context, Error,
+};
+
+/// Configure the timezones used for reminders
+#[poise::command(slash_command, rename = "timezone", identifying_name = "timezone_base")]
+pub async fn timezone_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+async fn timezone_not_recognized(
+    ctx: Context<'_>,
+    timezone: &str,
+    footer_text: String,
+) -> Result<(), Error> {
+    let filtered_tz = TZ_VARIANTS
+        .iter()
+        .filter(|tz| {
+            timezone.contains(&tz.to_string())
+                || tz.to_string().contains(timezone)
+                || levenshtein(&tz.to_string(), timezone) < 4
+        })
+        .take(25)
+        .map(|t| t.to_owned())
+        .collect::<Vec<Tz>>();
+
+    let fields = filtered_tz.iter().map(|tz| {
+        (tz.to_string(), format!("🕗 `{}`", Utc::now().with_timezone(tz).format("%H:%M")), true)
+    });
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("Timezone Not Recognized")
+                .description("Possibly you meant one of the following timezones, otherwise click [here](https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee):")
+                .color(*THEME_COLOR)
+                .fields(fields)
+                .footer(|f| f.text(footer_text))
+                .url("https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee")
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Resolve a timezone name, location, abbreviation or UTC offset. If the input is ambiguous or
+/// not recognized, the user is told and `None` is returned
+async fn resolve_timezone_input(
+    ctx: Context<'_>,
+    timezone: &str,
+    footer_text: String,
+) -> Result<Option<Tz>, Error> {
+    let matches = timezone_resolver::resolve(timezone);
+
+    match matches.as_slice() {
+        [] => {
+            timezone_not_recognized(ctx, timezone, footer_text).await?;
+
+            Ok(None)
+        }
+
+        [single] => Ok(Some(single.timezone)),
+
+        _ => {
+            let fields = matches.iter().take(25).map(|m| {
+                (
+                    m.timezone.to_string(),
+                    format!(
+                        "{}\n🕗 `{}`",
+                        m.description,
+                        Utc::now().with_timezone(&m.timezone).format("%H:%M")
+                    ),
+                    true,
+                )
+            });
+
+            ctx.send(|m| {
+                m.embed(|e| {
+                    e.title("Ambiguous Timezone")
+                        .description(format!(
+                            "`{}` could refer to several timezones. Please run the command again with one of the following:",
+                            timezone
+                        ))
+                        .color(*THEME_COLOR)
+                        .fields(fields)
+                        .footer(|f| f.text(footer_text))
+                })
+            })
+            .await?;
+
+            Ok(None)
+        }
+    }
+}
 
 /// Select your timezone
-#[poise::command(slash_command, identifying_name = "timezone")]
+#[poise::command(slash_command, rename = "user", identifying_name = "timezone")]
 pub async fn timezone(
     ctx: Context<'_>,
-    #[description = "Timezone to use from this list: https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee"]
+    #[description = "Timezone name, city, country, abbreviation or UTC offset (e.g. Europe/London, Tokyo, GMT+2)"]
     #[autocomplete = "timezone_autocomplete"]
     timezone: Option<String>,
 ) -> Result<(), Error> {
@@ -19,84 +122,187 @@ pub async fn timezone(
     let footer_text = format!("Current timezone: {}", user_data.timezone);
 
     if let Some(timezone) = timezone {
-        match timezone.parse::<Tz>() {
-            Ok(tz) => {
-                user_data.timezone = timezone.clone();
-                user_data.commit_changes(&ctx.data().database).await;
+        if let Some(tz) = resolve_timezone_input(ctx, &timezone, footer_text).await? {
+            user_data.timezone = tz.to_string();
+            user_data.commit_changes(&ctx.data().database).await;
+
+            let now = Utc::now().with_timezone(&tz);
+
+            ctx.send(|m| {
+                m.embed(|e| {
+                    e.title("Timezone Set")
+                        .description(format!(
+                            "Timezone has been set to **{}**. Your current time should be `{}`",
+                            tz,
+                            now.format("%H:%M")
+                        ))
+                        .color(*THEME_COLOR)
+                })
+            })
+            .await?;
+        }
+    } else {
+        let popular_timezones_iter = ctx.data().popular_timezones.iter().map(|t| {
+            (t.to_string(), format!("🕗 `{}`", Utc::now().with_timezone(t).format("%H:%M")), true)
+        });
+
+        ctx.send(|m| {
+            m.embed(|e| {
+                e.title("Timezone Usage")
+                    .description(
+                        "**Usage:**
+`/timezone user Name`
 
+**Example:**
+`/timezone user Europe/London`
+`/timezone user Tokyo`
+`/timezone user GMT+2`
+
+You may want to use one of the popular timezones below, otherwise click [here](https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee):",
+                    )
+                    .color(*THEME_COLOR)
+                    .fields(popular_timezones_iter)
+                    .footer(|f| f.text(footer_text))
+                    .url("https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee")
+            })
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Shared handling for `/timezone server` and `/timezone channel`. Returns the new setting if it
+/// has changed and should be saved
+async fn update_scoped_timezone(
+    ctx: Context<'_>,
+    scope: &str,
+    current: &Option<String>,
+    timezone: Option<String>,
+    clear: Option<bool>,
+) -> Result<Option<Option<String>>, Error> {
+    let footer_text =
+        format!("Current {} timezone: {}", scope, current.as_deref().unwrap_or("None"));
+
+    if clear.unwrap_or(false) {
+        ctx.send(|m| {
+            m.embed(|e| {
+                e.title("Timezone Cleared")
+                    .description(format!(
+                        "The {} timezone has been removed. Times will be read using {}.",
+                        scope,
+                        if scope == "channel" {
+                            "the server timezone, or each user's own timezone"
+                        } else {
+                            "each user's own timezone"
+                        }
+                    ))
+                    .color(*THEME_COLOR)
+            })
+        })
+        .await?;
+
+        Ok(Some(None))
+    } else if let Some(timezone) = timezone {
+        match resolve_timezone_input(ctx, &timezone, footer_text).await? {
+            Some(tz) => {
                 let now = Utc::now().with_timezone(&tz);
 
                 ctx.send(|m| {
                     m.embed(|e| {
                         e.title("Timezone Set")
                             .description(format!(
-                                "Timezone has been set to **{}**. Your current time should be `{}`",
-                                timezone,
+                                "The {} timezone has been set to **{}**. Times in this {} will be read in this timezone, and the current time should be `{}`",
+                                scope,
+                                tz,
+                                scope,
                                 now.format("%H:%M")
                             ))
                             .color(*THEME_COLOR)
                     })
                 })
                 .await?;
-            }
 
-            Err(_) => {
-                let filtered_tz = TZ_VARIANTS
-                    .iter()
-                    .filter(|tz| {
-                        timezone.contains(&tz.to_string())
-                            || tz.to_string().contains(&timezone)
-                            || levenshtein(&tz.to_string(), &timezone) < 4
-                    })
-                    .take(25)
-                    .map(|t| t.to_owned())
-                    .collect::<Vec<Tz>>();
-
-                let fields = filtered_tz.iter().map(|tz| {
-                    (
-                        tz.to_string(),
-                        format!("🕗 `{}`", Utc::now().with_timezone(tz).format("%H:%M")),
-                        true,
-                    )
-                });
-
-                ctx.send(|m| {
-                    m.embed(|e| {
-                        e.title("Timezone Not Recognized")
-                            .description("Possibly you meant one of the following timezones, otherwise click [here](https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee):")
-                            .color(*THEME_COLOR)
-                            .fields(fields)
-                            .footer(|f| f.text(footer_text))
-                            .url("https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee")
-                    })
-                })
-                .await?;
+                Ok(Some(Some(tz.to_string())))
             }
+
+            None => Ok(None),
         }
     } else {
-        let popular_timezones_iter = ctx.data().popular_timezones.iter().map(|t| {
-            (t.to_string(), format!("🕗 `{}`", Utc::now().with_timezone(t).format("%H:%M")), true)
-        });
-
         ctx.send(|m| {
             m.embed(|e| {
                 e.title("Timezone Usage")
-                    .description(
+                    .description(format!(
                         "**Usage:**
-`/timezone Name`
+`/timezone {0} Name`
 
 **Example:**
-`/timezone Europe/London`
+`/timezone {0} Europe/London`
 
-You may want to use one of the popular timezones below, otherwise click [here](https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee):",
-                    )
+Times are read in the timezone of the channel, then the server, then the user's own timezone.",
+                        scope
+                    ))
                     .color(*THEME_COLOR)
-                    .fields(popular_timezones_iter)
                     .footer(|f| f.text(footer_text))
-                    .url("https://gist.github.com/JellyWX/913dfc8b63d45192ad6cb54c829324ee")
             })
         })
         .await?;
+
+        Ok(None)
+    }
+}
+
+/// Set the timezone used for reminders set in this server
+#[poise::command(
+    slash_command,
+    rename = "server",
+    guild_only = true,
+    identifying_name = "timezone_server",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn timezone_server(
+    ctx: Context<'_>,
+    #[description = "Timezone to use for this server"]
+    #[autocomplete = "timezone_autocomplete"]
+    timezone: Option<String>,
+    #[description = "Remove the server timezone, using each user's own timezone instead"]
+    clear: Option<bool>,
+) -> Result<(), Error> {
+    let mut guild_data = ctx.guild_data().await.unwrap()?;
+
+    if let Some(timezone) =
+        update_scoped_timezone(ctx, "server", &guild_data.timezone, timezone, clear).await?
+    {
+        guild_data.timezone = timezone;
+        guild_data.commit_changes(&ctx.data().database).await;
+    }
+
+    Ok(())
+}
+
+/// Set the timezone used for reminders set in this channel
+#[poise::command(
+    slash_command,
+    rename = "channel",
+    guild_only = true,
+    identifying_name = "timezone_channel",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn timezone_channel(
+    ctx: Context<'_>,
+    #[description = "Timezone to use for this channel"]
+    #[autocomplete = "timezone_autocomplete"]
+    timezone: Option<String>,
+    #[description = "Remove the channel timezone, using the server's timezone instead"]
+    clear: Option<bool>,
+) -> Result<(), Error> {
+    let mut channel_data = ctx.channel_data().await?;
+
+    if let Some(timezone) =
+        update_scoped_timezone(ctx, "channel", &channel_data.timezone, timezone, clear).await?
+    {
+        channel_data.timezone = timezone;
+        channel_data.commit_changes(&ctx.data().database).await;
     }
 
     Ok(())
@@ -181,3 +387,459 @@ Do not share it!
 
     Ok(())
 }
+
+/// Post reminder activity in this server to a log channel
+#[poise::command(
+    slash_command,
+    rename = "logchannel",
+    identifying_name = "log_channel_base",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn log_channel_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+async fn show_log_settings(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = sqlx::query!(
+        "SELECT log_channel, log_events FROM guilds WHERE guild = ?",
+        ctx.guild_id().unwrap().0
+    )
+    .fetch_one(&ctx.data().database)
+    .await?;
+
+    let enabled = LogEvent::enabled(settings.log_events);
+    let events = LogEvent::ALL
+        .iter()
+        .map(|event| {
+            format!("{} {}", if enabled.contains(event) { "✅" } else { "❌" }, event.name())
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    ctx.send(|r| {
+        r.ephemeral(true).embed(|e| {
+            e.title("Log Channel")
+                .description(match settings.log_channel {
+                    Some(channel) => format!("Posting reminder activity to <#{}>", channel),
+                    None => "Reminder activity isn't being posted".to_string(),
+                })
+                .field("Events", events, false)
+                .color(*THEME_COLOR)
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Set the channel to post reminder activity to
+#[poise::command(
+    slash_command,
+    rename = "set",
+    guild_only = true,
+    identifying_name = "set_log_channel",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn set_log_channel(
+    ctx: Context<'_>,
+    #[description = "Channel to post to"] channel: Channel,
+) -> Result<(), Error> {
+    match channel.guild() {
+        Some(channel) if Some(channel.guild_id) == ctx.guild_id() => {
+            ctx.guild_data().await.unwrap()?;
+
+            sqlx::query!(
+                "UPDATE guilds SET log_channel = ? WHERE guild = ?",
+                channel.id.0,
+                channel.guild_id.0
+            )
+            .execute(&ctx.data().database)
+            .await?;
+
+            show_log_settings(ctx).await
+        }
+
+        _ => refuse("Please choose a channel in this server"),
+    }
+}
+
+/// Stop posting reminder activity
+#[poise::command(
+    slash_command,
+    rename = "disable",
+    guild_only = true,
+    identifying_name = "disable_log_channel",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn disable_log_channel(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.guild_data().await.unwrap()?;
+
+    sqlx::query!("UPDATE guilds SET log_channel = NULL WHERE guild = ?", ctx.guild_id().unwrap().0)
+        .execute(&ctx.data().database)
+        .await?;
+
+    show_log_settings(ctx).await
+}
+
+/// Choose whether a type of reminder activity is posted
+#[poise::command(
+    slash_command,
+    rename = "event",
+    guild_only = true,
+    identifying_name = "log_channel_event",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn log_channel_event(
+    ctx: Context<'_>,
+    #[description = "Type of activity"]
+    #[autocomplete = "log_event_autocomplete"]
+    event: String,
+    #[description = "Whether to post this activity"] enabled: bool,
+) -> Result<(), Error> {
+    match LogEvent::from_name(&event) {
+        Some(event) => {
+            ctx.guild_data().await.unwrap()?;
+
+            if enabled {
+                sqlx::query!(
+                    "UPDATE guilds SET log_events = log_events | ? WHERE guild = ?",
+                    event.bit(),
+                    ctx.guild_id().unwrap().0
+                )
+                .execute(&ctx.data().database)
+                .await?;
+            } else {
+                sqlx::query!(
+                    "UPDATE guilds SET log_events = log_events & ~? WHERE guild = ?",
+                    event.bit(),
+                    ctx.guild_id().unwrap().0
+                )
+                .execute(&ctx.data().database)
+                .await?;
+            }
+
+            show_log_settings(ctx).await
+        }
+
+        None => refuse(format!(
+            "Unknown activity type. Choose one of: {}",
+            LogEvent::ALL.iter().map(|e| e.name()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+#[derive(poise::ChoiceParameter)]
+pub enum QuietPolicyOption {
+    #[name = "Don't send them"]
+    Drop,
+    #[name = "Send them when quiet hours end"]
+    Defer,
+    #[name = "List them in one message when quiet hours end"]
+    Batch,
+}
+
+impl From<QuietPolicyOption> for QuietPolicy {
+    fn from(option: QuietPolicyOption) -> Self {
+        match option {
+            QuietPolicyOption::Drop => QuietPolicy::Drop,
+            QuietPolicyOption::Defer => QuietPolicy::Defer,
+            QuietPolicyOption::Batch => QuietPolicy::Batch,
+        }
+    }
+}
+
+/// Hold reminders during recurring quiet hours, such as nights and weekends
+#[poise::command(
+    slash_command,
+    rename = "quiethours",
+    identifying_name = "quiet_hours_base",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn quiet_hours_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+fn parse_local_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&format!("{}:00", time.trim()), "%H:%M"))
+        .ok()
+}
+
+/// Add quiet hours to this server or channel, in the channel's timezone
+#[poise::command(
+    slash_command,
+    rename = "add",
+    guild_only = true,
+    identifying_name = "add_quiet_hours",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn add_quiet_hours(
+    ctx: Context<'_>,
+    #[description = "Time quiet hours start, e.g. 22:00"] start: String,
+    #[description = "Time quiet hours end, e.g. 07:00. Can be on the following day"] end: String,
+    #[description = "Days quiet hours start on, e.g. \"weekdays\" or \"mon, wed, fri\" (default: daily)"]
+    days: Option<String>,
+    #[description = "What to do with reminders due during quiet hours (default: send them when quiet hours end)"]
+    policy: Option<QuietPolicyOption>,
+    #[description = "Only apply to this channel"] channel: Option<bool>,
+) -> Result<(), Error> {
+    let (start, end) = match (parse_local_time(&start), parse_local_time(&end)) {
+        (Some(start), Some(end)) => (start, end),
+
+        _ => return refuse("Please give start and end times as hours and minutes, e.g. 22:00"),
+    };
+
+    let days = match parse_days(days.as_deref().unwrap_or("daily")) {
+        Some(days) => days,
+
+        None => {
+            return refuse(
+                "Please give days as \"daily\", \"weekdays\", \"weekends\" or a list of days, e.g. \"mon, wed, fri\"",
+            );
+        }
+    };
+
+    let policy = policy.map_or(QuietPolicy::Defer, QuietPolicy::from);
+
+    let guild_data = ctx.guild_data().await.unwrap()?;
+    let (guild_id, channel_id) = if channel.unwrap_or(false) {
+        (None, Some(ctx.channel_data().await?.id))
+    } else {
+        (Some(guild_data.id), None)
+    };
+
+    let windows = sqlx::query!(
+        "SELECT COUNT(*) AS count FROM quiet_hours
+        LEFT JOIN channels ON channels.id = quiet_hours.channel_id
+        WHERE quiet_hours.guild_id = ? OR channels.guild_id = ?",
+        guild_data.id,
+        guild_data.id
+    )
+    .fetch_one(&ctx.data().database)
+    .await?
+    .count;
+
+    if windows >= MAX_QUIET_HOURS {
+        return refuse(format!(
+            "This server already has {} quiet hours. Please remove some with `/quiethours remove`",
+            MAX_QUIET_HOURS
+        ));
+    }
+
+    sqlx::query!(
+        "INSERT INTO quiet_hours (guild_id, channel_id, days, start_time, end_time, policy)
+        VALUES (?, ?, ?, ?, ?, ?)",
+        guild_id,
+        channel_id,
+        days,
+        start,
+        end,
+        policy.as_str()
+    )
+    .execute(&ctx.data().database)
+    .await?;
+
+    show_quiet_hours(ctx).await
+}
+
+async fn show_quiet_hours(ctx: Context<'_>) -> Result<(), Error> {
+    let windows = sqlx::query!(
+        "SELECT quiet_hours.id, quiet_hours.days, quiet_hours.start_time, quiet_hours.end_time,
+            quiet_hours.policy, channels.channel
+        FROM quiet_hours
+        LEFT JOIN channels ON channels.id = quiet_hours.channel_id
+        WHERE quiet_hours.guild_id = (SELECT id FROM guilds WHERE guild = ?)
+            OR channels.guild_id = (SELECT id FROM guilds WHERE guild = ?)
+        ORDER BY quiet_hours.channel_id IS NOT NULL, quiet_hours.id",
+        ctx.guild_id().unwrap().0,
+        ctx.guild_id().unwrap().0
+    )
+    .fetch_all(&ctx.data().database)
+    .await?;
+
+    let description = if windows.is_empty() {
+        "No quiet hours are set. Add some with `/quiethours add`".to_string()
+    } else {
+        windows
+            .iter()
+            .map(|window| {
+                format!(
+                    "`{}` {}, {} to {}, {} ({})",
+                    window.id,
+                    window.channel.map_or("Server".to_string(), |c| format!("<#{}>", c)),
+                    window.start_time.format("%H:%M"),
+                    window.end_time.format("%H:%M"),
+                    describe_days(window.days),
+                    window.policy
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    ctx.send(|r| {
+        r.ephemeral(true).embed(|e| {
+            e.title("Quiet Hours")
+                .description(description)
+                .footer(|f| {
+                    f.text("Channels with quiet hours of their own ignore the server's quiet hours")
+                })
+                .color(*THEME_COLOR)
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// View the quiet hours of this server and its channels
+#[poise::command(
+    slash_command,
+    rename = "list",
+    guild_only = true,
+    identifying_name = "list_quiet_hours",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn list_quiet_hours(ctx: Context<'_>) -> Result<(), Error> {
+    show_quiet_hours(ctx).await
+}
+
+/// Remove quiet hours
+#[poise::command(
+    slash_command,
+    rename = "remove",
+    guild_only = true,
+    identifying_name = "remove_quiet_hours",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn remove_quiet_hours(
+    ctx: Context<'_>,
+    #[description = "ID of the quiet hours, from `/quiethours list`"] id: u32,
+) -> Result<(), Error> {
+    let removed = sqlx::query!(
+        "DELETE quiet_hours FROM quiet_hours
+        LEFT JOIN channels ON channels.id = quiet_hours.channel_id
+        WHERE quiet_hours.id = ?
+            AND (quiet_hours.guild_id = (SELECT id FROM guilds WHERE guild = ?)
+                OR channels.guild_id = (SELECT id FROM guilds WHERE guild = ?))",
+        id,
+        ctx.guild_id().unwrap().0,
+        ctx.guild_id().unwrap().0
+    )
+    .execute(&ctx.data().database)
+    .await?
+    .rows_affected();
+
+    if removed == 0 {
+        refuse("No quiet hours in this server have that ID")
+    } else {
+        show_quiet_hours(ctx).await
+    }
+}
+
+/// Save display names and avatars for reminders to be sent as
+#[poise::command(
+    slash_command,
+    rename = "persona",
+    identifying_name = "persona_base",
+    guild_only = true,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn persona_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save a display name and avatar, to choose as the username of "/remind"
+#[poise::command(
+    slash_command,
+    rename = "save",
+    guild_only = true,
+    identifying_name = "save_persona",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn save_persona(
+    ctx: Context<'_>,
+    #[description = "Display name to send reminders with"] name: String,
+    #[description = "Link to an avatar image to send reminders with"] avatar: Option<String>,
+) -> Result<(), Error> {
+    if let Some(error) = identity_error(Some(&name), avatar.as_deref()) {
+        return refuse(error);
+    }
+
+    ctx.guild_data().await.unwrap()?;
+
+    let persona = Persona { name: name.trim().to_string(), avatar };
+    persona.save(&ctx.data().database, ctx.guild_id().unwrap()).await?;
+
+    ctx.send(|r| {
+        r.ephemeral(true).content(format!(
+            "Persona \"{}\" saved. Use it with `/remind username:{}`",
+            persona.name, persona.name
+        ))
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// List the personas saved in this server
+#[poise::command(
+    slash_command,
+    rename = "list",
+    guild_only = true,
+    identifying_name = "list_personas",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn list_personas(ctx: Context<'_>) -> Result<(), Error> {
+    let personas = Persona::from_guild(&ctx.data().database, ctx.guild_id().unwrap()).await?;
+
+    if personas.is_empty() {
+        ctx.send(|r| r.ephemeral(true).content("No personas saved. Save one with `/persona save`"))
+            .await?;
+    } else {
+        let mut description = String::new();
+
+        for persona in &personas {
+            let line = match &persona.avatar {
+                Some(avatar) => format!("**{}** - <{}>\n", persona.name, avatar),
+                None => format!("**{}**\n", persona.name),
+            };
+
+            if description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_MAX_LENGTH {
+                break;
+            }
+            description.push_str(&line);
+        }
+
+        ctx.send(|r| {
+            r.ephemeral(true)
+                .embed(|e| e.title("Personas").description(description).color(*THEME_COLOR))
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Delete a persona. Reminders set with the persona keep its name and avatar
+#[poise::command(
+    slash_command,
+    rename = "delete",
+    guild_only = true,
+    identifying_name = "delete_persona",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn delete_persona(
+    ctx: Context<'_>,
+    #[description = "Name of the persona"]
+    #[autocomplete = "persona_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    if !Persona::delete(&ctx.data().database, ctx.guild_id().unwrap(), &name).await? {
+        return refuse(format!("No persona in this server is named \"{}\"", name.trim()));
+    }
+
+    ctx.send(|r| r.ephemeral(true).content(format!("Persona \"{}\" deleted", name.trim()))).await?;
+
+    Ok(())
+}
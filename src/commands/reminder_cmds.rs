@@ -1,42 +1,77 @@
-use std::{collections::HashSet, string::ToString};
+use std::{
+    collections::HashSet,
+    string::ToString,
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 use chrono_tz::Tz;
 use num_integer::Integer;
 use poise::{
     serenity_prelude::{
-        builder::CreateEmbed, component::ButtonStyle, model::channel::Channel, ReactionType,
+        builder::{CreateComponents, CreateEmbed},
+        component::ButtonStyle,
+        model::{
+            application::interaction::InteractionResponseType,
+            channel::{Channel, Message},
+        },
+        CollectComponentInteraction, CollectModalInteraction, Mentionable, ReactionType, Role,
+        RoleId, User,
     },
     CreateReply, Modal,
 };
+use postman::{
+    guild_log::{self, LogEvent},
+    names,
+    selector::Selector,
+};
 
 use crate::{
-    commands::autocomplete::{time_hint_autocomplete, timezone_autocomplete},
+    commands::autocomplete::{
+        persona_autocomplete, reminder_name_autocomplete, template_name_autocomplete, time_hint,
+        time_hint_autocomplete, timezone_autocomplete,
+    },
     component_models::{
         pager::{DelPager, LookPager, Pager},
-        ComponentDataModel, DelSelector, UndoReminder,
+        BulkAction, BulkConfirm, BulkSelection, ComponentDataModel, DelSelector, EmbedPreview,
+        EmbedPreviewAction, UndoReminder,
     },
     consts::{
-        EMBED_DESCRIPTION_MAX_LENGTH, HOUR, MINUTE, REGEX_CHANNEL_USER, SELECT_MAX_ENTRIES,
+        AUDIT_ENTRY_MAX_LENGTH, AUDIT_PAGE_ENTRIES, BULK_CONFIRM_TIMEOUT, BULK_PREVIEW_ENTRIES,
+        DAY, DEFAULT_MAX_TIMERS, EMBED_DESCRIPTION_MAX_LENGTH, EMBED_MAX_FIELDS,
+        EMBED_PREVIEW_TIMEOUT, HOUR, MAX_ATTACHMENT_SIZE, MAX_TIME, MAX_TIMERS_LIMIT,
+        MAX_TIMER_CYCLES, MESSAGE_MAX_LENGTH, MINUTE, REGEX_CHANNEL_USER, SELECT_MAX_ENTRIES,
         THEME_COLOR,
     },
-    interval_parser::parse_duration,
+    interval_parser::{parse_duration, Interval},
     models::{
+        audit::{self, ActionFilter, AuditAction, ReminderSnapshot},
+        persona::{identity_error, Persona},
         reminder::{
             builder::{MultiReminderBuilder, ReminderScope},
             content::Content,
+            embed::{parse_color, Embed, EmbedField},
             errors::ReminderError,
-            look_flags::{LookFlags, TimeDisplayType},
+            look_flags::{LookFlags, LookSort, TimeDisplayType},
+            template::ReminderTemplate,
             Reminder,
         },
-        timer::Timer,
+        timer::{CountdownPhase, Timer},
         CtxData,
     },
+    refuse,
     time_parser::natural_parser,
     utils::{check_guild_subscription, check_subscription},
     ApplicationContext, Context, Error,
 };
 
+/// Post an event to the log channel of the current guild
+async fn post_to_log(ctx: Context<'_>, event: LogEvent, description: String) {
+    if let Some(guild_id) = ctx.guild_id() {
+        guild_log::post(ctx.discord(), &ctx.data().database, guild_id.0, event, description).await;
+    }
+}
+
 /// Pause all reminders on the current channel until a certain time or indefinitely
 #[poise::command(
     slash_command,
@@ -68,20 +103,30 @@ pub async fn pause(
                             timestamp
                         ))
                         .await?;
+
+                        post_to_log(
+                            ctx,
+                            LogEvent::Paused,
+                            format!(
+                                "Reminders in {} paused until <t:{}:D> by {}",
+                                ctx.channel_id().mention(),
+                                timestamp,
+                                ctx.author().mention()
+                            ),
+                        )
+                        .await;
                     }
 
                     None => {
-                        ctx.say(format!(
+                        return refuse(format!(
                             "Time processed could not be interpreted as `DateTime`. Please write the time as clearly as possible",
-                        ))
-                        .await?;
+                        ));
                     }
                 }
             } else {
-                ctx.say(
+                return refuse(
                     "Time could not be processed. Please write the time as clearly as possible",
-                )
-                .await?;
+                );
             }
         }
         _ => {
@@ -95,13 +140,115 @@ pub async fn pause(
             } else {
                 ctx.say("Reminders in this channel have been unsilenced").await?;
             }
+
+            post_to_log(
+                ctx,
+                LogEvent::Paused,
+                format!(
+                    "Reminders in {} {} by {}",
+                    ctx.channel_id().mention(),
+                    if channel.paused { "paused indefinitely" } else { "unpaused" },
+                    ctx.author().mention()
+                ),
+            )
+            .await;
         }
     }
 
     Ok(())
 }
 
-/// Move all reminders in the current server by a certain amount of time. Times get added together
+/// Footer of a bulk preview, showing the selector used
+const BULK_SELECTOR_FOOTER: &str = "Selector: ";
+/// Shown in place of an empty selector
+const BULK_SELECT_ALL: &str = "all reminders";
+
+/// Show the reminders in the current server that a bulk change would apply to, with a button to
+/// apply it
+async fn show_bulk_preview(
+    ctx: Context<'_>,
+    action: BulkAction,
+    selector: Option<String>,
+) -> Result<(), Error> {
+    let selector = selector.map(|s| s.trim().to_string()).unwrap_or_default();
+    let timezone = ctx.timezone().await;
+
+    let ids = match Selector::parse(&selector, &timezone.to_string()) {
+        Ok(parsed) => parsed.matching(&ctx.data().database, ctx.guild_id().unwrap().0).await?,
+
+        Err(e) => {
+            return refuse(e);
+        }
+    };
+
+    if ids.is_empty() {
+        return refuse("No reminders match that selector");
+    }
+
+    let snapshots = ReminderSnapshot::of(
+        &ctx.data().database,
+        &ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","),
+    )
+    .await;
+
+    let mut description = snapshots
+        .iter()
+        .take(BULK_PREVIEW_ENTRIES)
+        .map(|snapshot| snapshot.describe())
+        .collect::<Vec<String>>()
+        .join("\n");
+    if ids.len() > BULK_PREVIEW_ENTRIES {
+        description.push_str(&format!("\n...and {} more", ids.len() - BULK_PREVIEW_ENTRIES));
+    }
+
+    {
+        let mut selections = ctx.data().bulk_selections.write().await;
+
+        selections.retain(|_, selection| {
+            selection.created.elapsed() < Duration::from_secs(BULK_CONFIRM_TIMEOUT)
+        });
+        selections.insert(
+            ctx.id(),
+            BulkSelection { selector: selector.clone(), ids: ids.clone(), created: Instant::now() },
+        );
+    }
+
+    let confirm = ComponentDataModel::BulkConfirm(BulkConfirm {
+        user_id: ctx.author().id,
+        action,
+        selection: ctx.id(),
+    });
+
+    ctx.send(|r| {
+        r.ephemeral(true)
+            .embed(|e| {
+                e.title(action.question(ids.len()))
+                    .description(description)
+                    .footer(|f| {
+                        f.text(format!(
+                            "{}{}",
+                            BULK_SELECTOR_FOOTER,
+                            if selector.is_empty() { BULK_SELECT_ALL } else { &selector }
+                        ))
+                    })
+                    .color(*THEME_COLOR)
+            })
+            .components(|c| {
+                c.create_action_row(|r| {
+                    r.create_button(|b| {
+                        b.label("Confirm")
+                            .style(ButtonStyle::Danger)
+                            .custom_id(confirm.to_custom_id())
+                    })
+                })
+            })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Move reminders in the current server by a certain amount of time. Times get added together
 #[poise::command(
     slash_command,
     identifying_name = "offset",
@@ -112,49 +259,35 @@ pub async fn offset(
     #[description = "Number of hours to offset by"] hours: Option<isize>,
     #[description = "Number of minutes to offset by"] minutes: Option<isize>,
     #[description = "Number of seconds to offset by"] seconds: Option<isize>,
+    #[description = "Which reminders to move, e.g. channel:#general name:standup* (default: all)"]
+    selector: Option<String>,
 ) -> Result<(), Error> {
     let combined_time = hours.map_or(0, |h| h * HOUR as isize)
         + minutes.map_or(0, |m| m * MINUTE as isize)
         + seconds.map_or(0, |s| s);
 
     if combined_time == 0 {
-        ctx.say("Please specify one of `hours`, `minutes` or `seconds`").await?;
+        return refuse("Please specify one of `hours`, `minutes` or `seconds`");
+    } else if ctx.guild_id().is_some() {
+        show_bulk_preview(ctx, BulkAction::Offset(combined_time as i64), selector).await?;
     } else {
-        if let Some(guild) = ctx.guild() {
-            let channels = guild
-                .channels
-                .iter()
-                .filter(|(_, channel)| match channel {
-                    Channel::Guild(guild_channel) => guild_channel.is_text_based(),
-                    _ => false,
-                })
-                .map(|(id, _)| id.0.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
-
-            sqlx::query!(
-                "
-UPDATE reminders
-INNER JOIN
-    `channels` ON `channels`.id = reminders.channel_id
-SET reminders.`utc_time` = DATE_ADD(reminders.`utc_time`, INTERVAL ? SECOND)
-WHERE FIND_IN_SET(channels.`channel`, ?)",
-                combined_time as i64,
-                channels
-            )
-            .execute(&ctx.data().database)
-            .await
-            .unwrap();
-        } else {
-            sqlx::query!(
-                "UPDATE reminders INNER JOIN `channels` ON `channels`.id = reminders.channel_id SET reminders.`utc_time` = reminders.`utc_time` + ? WHERE channels.`channel` = ?",
-                combined_time as i64,
-                ctx.channel_id().0
-            )
-            .execute(&ctx.data().database)
-            .await
-            .unwrap();
-        }
+        audit::record_offset(
+            &ctx.data().database,
+            ctx.author().id.0,
+            audit::source_of(&ctx),
+            &ctx.channel_id().0.to_string(),
+            combined_time as i64,
+        )
+        .await;
+
+        sqlx::query!(
+            "UPDATE reminders INNER JOIN `channels` ON `channels`.id = reminders.channel_id SET reminders.`utc_time` = reminders.`utc_time` + ? WHERE channels.`channel` = ?",
+            combined_time as i64,
+            ctx.channel_id().0
+        )
+        .execute(&ctx.data().database)
+        .await
+        .unwrap();
 
         ctx.say(format!("All reminders offset by {} seconds", combined_time)).await?;
     }
@@ -162,6 +295,65 @@ WHERE FIND_IN_SET(channels.`channel`, ?)",
     Ok(())
 }
 
+/// Enable, disable or delete many reminders in this server at once
+#[poise::command(
+    slash_command,
+    rename = "bulk",
+    identifying_name = "bulk_base",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn bulk_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Enable the reminders matching a selector
+#[poise::command(
+    slash_command,
+    rename = "enable",
+    guild_only = true,
+    identifying_name = "bulk_enable",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn bulk_enable(
+    ctx: Context<'_>,
+    #[description = "Which reminders to enable, e.g. channel:#general enabled:no (default: all)"]
+    selector: Option<String>,
+) -> Result<(), Error> {
+    show_bulk_preview(ctx, BulkAction::Enable, selector).await
+}
+
+/// Disable the reminders matching a selector
+#[poise::command(
+    slash_command,
+    rename = "disable",
+    guild_only = true,
+    identifying_name = "bulk_disable",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn bulk_disable(
+    ctx: Context<'_>,
+    #[description = "Which reminders to disable, e.g. by:@someone repeating:yes (default: all)"]
+    selector: Option<String>,
+) -> Result<(), Error> {
+    show_bulk_preview(ctx, BulkAction::Disable, selector).await
+}
+
+/// Delete the reminders matching a selector
+#[poise::command(
+    slash_command,
+    rename = "delete",
+    guild_only = true,
+    identifying_name = "bulk_delete",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn bulk_delete(
+    ctx: Context<'_>,
+    #[description = "Which reminders to delete, e.g. name:\"old*\" before:2023-01-01 (default: all)"]
+    selector: Option<String>,
+) -> Result<(), Error> {
+    show_bulk_preview(ctx, BulkAction::Delete, selector).await
+}
+
 /// Nudge all future reminders on this channel by a certain amount (don't use for DST! See `/offset`)
 #[poise::command(
     slash_command,
@@ -176,7 +368,7 @@ pub async fn nudge(
     let combined_time = minutes.map_or(0, |m| m * MINUTE as isize) + seconds.map_or(0, |s| s);
 
     if combined_time < i16::MIN as isize || combined_time > i16::MAX as isize {
-        ctx.say("Nudge times must be less than 500 minutes").await?;
+        return refuse("Nudge times must be less than 500 minutes");
     } else {
         let mut channel_data = ctx.channel_data().await.unwrap();
 
@@ -184,6 +376,18 @@ pub async fn nudge(
         channel_data.commit_changes(&ctx.data().database).await;
 
         ctx.say(format!("Future reminders will be nudged by {} seconds", combined_time)).await?;
+
+        post_to_log(
+            ctx,
+            LogEvent::Nudged,
+            format!(
+                "New reminders in {} will be nudged by {} seconds, set by {}",
+                ctx.channel_id().mention(),
+                combined_time,
+                ctx.author().mention()
+            ),
+        )
+        .await;
     }
 
     Ok(())
@@ -200,9 +404,26 @@ pub async fn look(
     #[description = "Channel to view reminders on"] channel: Option<Channel>,
     #[description = "Whether to show disabled reminders or not"] disabled: Option<bool>,
     #[description = "Whether to display times as relative or exact times"] relative: Option<bool>,
+    #[description = "Only show reminders containing this text"] search: Option<String>,
+    #[description = "Only show reminders set by this user"] creator: Option<User>,
+    #[description = "Only show reminders due within a time from now, e.g. 24 hours"] within: Option<
+        String,
+    >,
+    #[description = "Only show repeating reminders, or only one-off reminders"] repeating: Option<
+        bool,
+    >,
+    #[description = "Order to show reminders in"] sort: Option<LookSort>,
 ) -> Result<(), Error> {
     let timezone = ctx.timezone().await;
 
+    let within = match within.as_ref().map(|w| parse_duration(w)) {
+        Some(Ok(interval)) => Some(interval_seconds(interval)),
+        Some(Err(_)) => {
+            return refuse("Time could not be processed. Try similar to `24 hours` or `7 days`");
+        }
+        None => None,
+    };
+
     let flags = LookFlags {
         show_disabled: disabled.unwrap_or(true),
         channel_id: channel.map(|c| c.id()),
@@ -213,6 +434,11 @@ pub async fn look(
                 TimeDisplayType::Absolute
             }
         }),
+        search: search.filter(|s| !s.trim().is_empty()),
+        set_by: creator.map(|u| u.id),
+        within,
+        repeating,
+        sort: sort.unwrap_or_default(),
     };
 
     let channel_opt = ctx.channel_id().to_channel_cached(&ctx.discord());
@@ -237,7 +463,7 @@ pub async fn look(
     let reminders = Reminder::from_channel(&ctx.data().database, channel_id, &flags).await;
 
     if reminders.is_empty() {
-        let _ = ctx.say("No reminders on specified channel").await;
+        let _ = ctx.say("No matching reminders on specified channel").await;
     } else {
         let mut char_count = 0;
 
@@ -260,6 +486,10 @@ pub async fn look(
 
         let pager = LookPager::new(flags, timezone);
 
+        if !pager.fits(pages) {
+            return refuse("The search is too long to page through. Please shorten it");
+        }
+
         ctx.send(|r| {
             r.ephemeral(true)
                 .embed(|e| {
@@ -308,94 +538,631 @@ pub async fn delete(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn max_delete_page(reminders: &[Reminder], timezone: &Tz) -> usize {
-    let mut rows = 0;
-    let mut char_count = 0;
+/// Why a name can't be given to a reminder, if it can't
+fn name_error(name: &str) -> Option<String> {
+    if name.trim().is_empty() || name.chars().count() > names::MAX_NAME_LENGTH {
+        Some(format!("Names must be between 1 and {} characters", names::MAX_NAME_LENGTH))
+    } else {
+        None
+    }
+}
 
-    reminders
-        .iter()
-        .enumerate()
-        .map(|(count, reminder)| reminder.display_del(count, timezone))
-        .fold(1, |mut pages, reminder| {
-            rows += 1;
-            char_count += reminder.len();
+/// Find the reminder in the current channel with a name, telling the user if there isn't one
+async fn named_reminder(ctx: Context<'_>, name: &str) -> Result<Option<Reminder>, Error> {
+    let reminder = Reminder::from_name(&ctx.data().database, ctx.reminder_channel(), name).await;
 
-            if char_count > EMBED_DESCRIPTION_MAX_LENGTH || rows > SELECT_MAX_ENTRIES {
-                rows = 1;
-                char_count = reminder.len();
-                pages += 1;
-            }
+    if reminder.is_none() {
+        ctx.send(|r| {
+            r.ephemeral(true).content(format!("No reminder in this channel is named \"{}\"", name))
+        })
+        .await?;
+    }
 
-            pages
+    Ok(reminder)
+}
+
+/// Record a change to a single reminder in the audit trail and the server's log channel
+async fn record_change(
+    ctx: Context<'_>,
+    action: AuditAction,
+    old: Option<&ReminderSnapshot>,
+    new: Option<&ReminderSnapshot>,
+    description: String,
+) {
+    audit::record(
+        &ctx.data().database,
+        ctx.author().id.0,
+        audit::source_of(&ctx),
+        action,
+        old,
+        new,
+    )
+    .await;
+
+    let event = match action {
+        AuditAction::Create => LogEvent::Created,
+        AuditAction::Edit => LogEvent::Edited,
+        AuditAction::Delete => LogEvent::Deleted,
+    };
+    post_to_log(ctx, event, format!("{} by {}", description, ctx.author().mention())).await;
+}
+
+/// View and change reminders in this channel by name
+#[poise::command(
+    slash_command,
+    rename = "reminder",
+    identifying_name = "reminder_base",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the details of a reminder
+#[poise::command(
+    slash_command,
+    rename = "show",
+    identifying_name = "reminder_show",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_show(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    if let Some(reminder) = named_reminder(ctx, &name).await? {
+        let repeating = reminder.interval_seconds.is_some()
+            || reminder.interval_days.is_some()
+            || reminder.interval_months.is_some();
+
+        ctx.send(|r| {
+            r.ephemeral(true).embed(|e| {
+                e.title(&reminder.name)
+                    .description(reminder.display_content())
+                    .field("Next", format!("<t:{}:F>", reminder.utc_time.timestamp()), true)
+                    .field("Repeating", if repeating { "Yes" } else { "No" }, true)
+                    .field("Enabled", if reminder.enabled { "Yes" } else { "No" }, true)
+                    .field(
+                        "Set by",
+                        reminder
+                            .set_by
+                            .map(|i| format!("<@{}>", i))
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        true,
+                    )
+                    .footer(|f| f.text(&reminder.uid))
+                    .color(*THEME_COLOR)
+            })
         })
+        .await?;
+    }
+
+    Ok(())
 }
 
-pub fn show_delete_page(reminders: &[Reminder], page: usize, timezone: Tz) -> CreateReply {
-    let pager = DelPager::new(page, timezone);
+async fn set_reminder_enabled(ctx: Context<'_>, name: String, enabled: bool) -> Result<(), Error> {
+    if let Some(reminder) = named_reminder(ctx, &name).await? {
+        let old = ReminderSnapshot::of(&ctx.data().database, &reminder.id.to_string()).await;
 
-    if reminders.is_empty() {
-        let mut reply = CreateReply::default();
+        sqlx::query!("UPDATE reminders SET enabled = ? WHERE id = ?", enabled, reminder.id)
+            .execute(&ctx.data().database)
+            .await?;
 
-        reply
-            .embed(|e| e.title("Delete Reminders").description("No Reminders").color(*THEME_COLOR))
-            .components(|comp| {
-                pager.create_button_row(0, comp);
-                comp
-            });
+        let new = ReminderSnapshot::of(&ctx.data().database, &reminder.id.to_string()).await;
+        let done = if enabled { "enabled" } else { "disabled" };
 
-        return reply;
+        record_change(
+            ctx,
+            AuditAction::Edit,
+            old.first(),
+            new.first(),
+            format!("\"{}\" for <#{}> {}", reminder.name, reminder.channel, done),
+        )
+        .await;
+
+        ctx.send(|r| r.ephemeral(true).content(format!("Reminder \"{}\" {}", reminder.name, done)))
+            .await?;
     }
 
-    let pages = max_delete_page(reminders, &timezone);
+    Ok(())
+}
 
-    let mut page = page;
-    if page >= pages {
-        page = pages - 1;
+/// Enable a reminder
+#[poise::command(
+    slash_command,
+    rename = "enable",
+    identifying_name = "reminder_enable",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_enable(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    set_reminder_enabled(ctx, name, true).await
+}
+
+/// Disable a reminder, so that it isn't sent until it is enabled again
+#[poise::command(
+    slash_command,
+    rename = "disable",
+    identifying_name = "reminder_disable",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_disable(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    set_reminder_enabled(ctx, name, false).await
+}
+
+/// Delete a reminder
+#[poise::command(
+    slash_command,
+    rename = "delete",
+    identifying_name = "reminder_delete",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_delete(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+) -> Result<(), Error> {
+    if let Some(reminder) = named_reminder(ctx, &name).await? {
+        let old = ReminderSnapshot::of(&ctx.data().database, &reminder.id.to_string()).await;
+
+        reminder.delete(&ctx.data().database).await?;
+
+        record_change(
+            ctx,
+            AuditAction::Delete,
+            old.first(),
+            None,
+            format!("\"{}\" for <#{}> deleted", reminder.name, reminder.channel),
+        )
+        .await;
+
+        ctx.send(|r| r.ephemeral(true).content(format!("Reminder \"{}\" deleted", reminder.name)))
+            .await?;
     }
 
-    let mut char_count = 0;
-    let mut rows = 0;
-    let mut skipped_rows = 0;
-    let mut skipped_char_count = 0;
-    let mut first_num = 0;
+    Ok(())
+}
 
-    let mut skipped_pages = 0;
+/// Change the time, content or name of a reminder
+#[poise::command(
+    slash_command,
+    rename = "edit",
+    identifying_name = "reminder_edit",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_edit(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+    #[description = "A description of the new time to send the reminder"]
+    #[autocomplete = "time_hint_autocomplete"]
+    time: Option<String>,
+    #[description = "The new message content"] content: Option<String>,
+    #[description = "A new name for the reminder"] rename: Option<String>,
+) -> Result<(), Error> {
+    if time.is_none() && content.is_none() && rename.is_none() {
+        return refuse("Please specify one of `time`, `content` or `rename`");
+    }
 
-    let (shown_reminders, display_vec): (Vec<&Reminder>, Vec<String>) = reminders
-        .iter()
-        .enumerate()
-        .map(|(count, reminder)| (reminder, reminder.display_del(count, &timezone)))
-        .skip_while(|(_, p)| {
-            first_num += 1;
-            skipped_rows += 1;
-            skipped_char_count += p.len();
+    let reminder = match named_reminder(ctx, &name).await? {
+        Some(reminder) => reminder,
 
-            if skipped_char_count > EMBED_DESCRIPTION_MAX_LENGTH
-                || skipped_rows > SELECT_MAX_ENTRIES
-            {
-                skipped_rows = 1;
-                skipped_char_count = p.len();
-                skipped_pages += 1;
+        None => return Ok(()),
+    };
+
+    let utc_time = match time {
+        Some(time) => match natural_parser(&time, &ctx.timezone().await.to_string()).await {
+            Some(timestamp) if timestamp > Utc::now().timestamp() => {
+                NaiveDateTime::from_timestamp_opt(timestamp, 0)
             }
 
-            skipped_pages < page
-        })
-        .take_while(|(_, p)| {
-            rows += 1;
-            char_count += p.len();
+            Some(_) => {
+                return refuse(ReminderError::PastTime.to_string());
+            }
 
-            char_count < EMBED_DESCRIPTION_MAX_LENGTH && rows <= SELECT_MAX_ENTRIES
-        })
-        .unzip();
+            None => {
+                return refuse("Time could not be processed");
+            }
+        },
 
-    let display = display_vec.join("\n");
+        None => None,
+    };
 
-    let del_selector = ComponentDataModel::DelSelector(DelSelector { page, timezone });
+    if let Some(rename) = &rename {
+        let channel_id = sqlx::query!("SELECT channel_id FROM reminders WHERE id = ?", reminder.id)
+            .fetch_one(&ctx.data().database)
+            .await?
+            .channel_id;
+        let taken =
+            names::is_taken(&ctx.data().database, channel_id, rename, Some(&reminder.uid)).await?;
+        let error =
+            name_error(rename).or_else(|| taken.then(|| ReminderError::NameTaken.to_string()));
+
+        if let Some(error) = error {
+            return refuse(error);
+        }
+    }
 
-    let mut reply = CreateReply::default();
+    let old = ReminderSnapshot::of(&ctx.data().database, &reminder.id.to_string()).await;
 
-    reply
-        .embed(|e| {
+    sqlx::query!(
+        "
+UPDATE reminders
+SET
+    utc_time = IFNULL(?, utc_time),
+    content = IFNULL(?, content),
+    name = IFNULL(?, name)
+WHERE id = ?",
+        utc_time,
+        content,
+        rename.as_ref().map(|n| n.trim()),
+        reminder.id
+    )
+    .execute(&ctx.data().database)
+    .await?;
+
+    let new = ReminderSnapshot::of(&ctx.data().database, &reminder.id.to_string()).await;
+    let new_name = rename.map_or(reminder.name, |n| n.trim().to_string());
+
+    record_change(
+        ctx,
+        AuditAction::Edit,
+        old.first(),
+        new.first(),
+        format!("\"{}\" for <#{}> edited", new_name, reminder.channel),
+    )
+    .await;
+
+    ctx.send(|r| r.ephemeral(true).content(format!("Reminder \"{}\" edited", new_name))).await?;
+
+    Ok(())
+}
+
+/// Send a reminder now to check how it looks. It is still sent at its usual time
+#[poise::command(
+    slash_command,
+    rename = "send-now",
+    identifying_name = "reminder_send_now",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn reminder_send_now(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    name: String,
+    #[description = "Send to this channel instead, e.g. a test channel"] channel: Option<Channel>,
+) -> Result<(), Error> {
+    let channel_id = match channel.map(|c| c.guild()) {
+        Some(Some(channel)) if Some(channel.guild_id) == ctx.guild_id() => Some(channel.id.0),
+
+        Some(_) => {
+            return refuse("Please choose a channel in this server");
+        }
+
+        None => None,
+    };
+
+    if let Some(reminder) = named_reminder(ctx, &name).await? {
+        ctx.defer_ephemeral().await?;
+
+        let result =
+            postman::send_now(&ctx.data().database, ctx.discord(), reminder.id, channel_id).await;
+        let content = match result {
+            Ok(()) => format!("Reminder \"{}\" sent", reminder.name),
+
+            Err(e) => format!("Reminder \"{}\" could not be sent: {}", reminder.name, e),
+        };
+
+        ctx.send(|r| r.ephemeral(true).content(content)).await?;
+    }
+
+    Ok(())
+}
+
+/// Save reminders as templates, to create similar reminders with "/remind"
+#[poise::command(
+    slash_command,
+    rename = "template",
+    identifying_name = "template_base",
+    guild_only = true,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn template_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save the message, embed, username and avatar of a reminder in this channel as a template
+#[poise::command(
+    slash_command,
+    rename = "save",
+    identifying_name = "template_save",
+    guild_only = true,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn template_save(
+    ctx: Context<'_>,
+    #[description = "Name of the reminder"]
+    #[autocomplete = "reminder_name_autocomplete"]
+    reminder: String,
+    #[description = "Name for the template, if not the reminder's"] name: Option<String>,
+) -> Result<(), Error> {
+    if let Some(reminder) = named_reminder(ctx, &reminder).await? {
+        let name = name.unwrap_or_else(|| reminder.name.clone());
+        let guild_id = ctx.guild_id().unwrap();
+
+        let taken = ReminderTemplate::names(&ctx.data().database, guild_id)
+            .await?
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(name.trim()));
+        let error = name_error(&name).or_else(|| {
+            taken.then(|| format!("A template named \"{}\" already exists", name.trim()))
+        });
+
+        if let Some(error) = error {
+            return refuse(error);
+        }
+
+        ReminderTemplate::save_reminder(&ctx.data().database, guild_id, reminder.id, name.trim())
+            .await?;
+
+        ctx.send(|r| {
+            r.ephemeral(true).content(format!(
+                "Reminder \"{}\" saved as template \"{}\". Use it with `/remind template:{}`",
+                reminder.name,
+                name.trim(),
+                name.trim()
+            ))
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// List the templates in this server
+#[poise::command(
+    slash_command,
+    rename = "list",
+    identifying_name = "template_list",
+    guild_only = true,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn template_list(ctx: Context<'_>) -> Result<(), Error> {
+    let names = ReminderTemplate::names(&ctx.data().database, ctx.guild_id().unwrap()).await?;
+
+    if names.is_empty() {
+        ctx.send(|r| {
+            r.ephemeral(true)
+                .content("No templates saved. Save a reminder as a template with `/template save`")
+        })
+        .await?;
+    } else {
+        let mut description = String::new();
+
+        for name in &names {
+            let line = format!("• {}\n", name);
+
+            if description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_MAX_LENGTH {
+                break;
+            }
+            description.push_str(&line);
+        }
+
+        ctx.send(|r| {
+            r.ephemeral(true).embed(|e| {
+                e.title(format!("{} Templates", names.len()))
+                    .description(description)
+                    .footer(|f| f.text("Create a reminder from a template with /remind template:"))
+                    .color(*THEME_COLOR)
+            })
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// View who created, edited and deleted reminders in this server
+#[poise::command(
+    slash_command,
+    identifying_name = "audit",
+    guild_only = true,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn audit(
+    ctx: Context<'_>,
+    #[description = "Name or ID of a reminder to view changes to"] reminder: Option<String>,
+    #[description = "User to view changes by"] user: Option<User>,
+    #[description = "Type of change to view"] action: Option<ActionFilter>,
+) -> Result<(), Error> {
+    let entries = sqlx::query!(
+        "
+SELECT
+    reminder_uid,
+    reminder_name,
+    actor,
+    source,
+    action,
+    CAST(changes AS CHAR) AS \"changes!: String\",
+    UNIX_TIMESTAMP(created_at) AS \"created_at!: i64\"
+FROM reminder_audit
+WHERE
+    guild_id = (SELECT id FROM guilds WHERE guild = ?)
+    AND (? IS NULL OR reminder_name = ? OR reminder_uid = ?)
+    AND (? IS NULL OR actor = ?)
+    AND (? IS NULL OR action = ?)
+ORDER BY id DESC
+LIMIT ?",
+        ctx.guild_id().unwrap().0,
+        reminder,
+        reminder,
+        reminder,
+        user.as_ref().map(|u| u.id.0),
+        user.as_ref().map(|u| u.id.0),
+        action.map(|a| AuditAction::from(a).as_str()),
+        action.map(|a| AuditAction::from(a).as_str()),
+        AUDIT_PAGE_ENTRIES as u32
+    )
+    .fetch_all(&ctx.data().database)
+    .await?;
+
+    if entries.is_empty() {
+        ctx.send(|b| b.ephemeral(true).content("No matching changes found")).await?;
+
+        return Ok(());
+    }
+
+    let fields = entries.iter().map(|entry| {
+        let verb = match entry.action.as_str() {
+            "create" => "Created",
+            "edit" => "Edited",
+            _ => "Deleted",
+        };
+
+        // Creations and deletions list every field, so only edits show their changes
+        let changes = if entry.action == "edit" {
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&entry.changes)
+                .unwrap_or_default()
+                .iter()
+                .map(|(field, change)| {
+                    format!("`{}`: {} → {}", field, change["old"], change["new"])
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        } else {
+            String::new()
+        };
+
+        let mut value = format!(
+            "by <@{}> via {} <t:{}:R>\n{}",
+            entry.actor, entry.source, entry.created_at, changes
+        );
+        if value.len() > AUDIT_ENTRY_MAX_LENGTH {
+            let mut end = AUDIT_ENTRY_MAX_LENGTH - 1;
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            value.truncate(end);
+            value.push('…');
+        }
+
+        (format!("{} \"{}\"", verb, entry.reminder_name), value, false)
+    });
+
+    ctx.send(|b| {
+        b.ephemeral(true).embed(|e| {
+            e.title("Reminder Changes")
+                .description("Most recent changes first")
+                .color(*THEME_COLOR)
+                .fields(fields)
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+pub fn max_delete_page(reminders: &[Reminder], timezone: &Tz) -> usize {
+    let mut rows = 0;
+    let mut char_count = 0;
+
+    reminders
+        .iter()
+        .enumerate()
+        .map(|(count, reminder)| reminder.display_del(count, timezone))
+        .fold(1, |mut pages, reminder| {
+            rows += 1;
+            char_count += reminder.len();
+
+            if char_count > EMBED_DESCRIPTION_MAX_LENGTH || rows > SELECT_MAX_ENTRIES {
+                rows = 1;
+                char_count = reminder.len();
+                pages += 1;
+            }
+
+            pages
+        })
+}
+
+pub fn show_delete_page(reminders: &[Reminder], page: usize, timezone: Tz) -> CreateReply {
+    let pager = DelPager::new(page, timezone);
+
+    if reminders.is_empty() {
+        let mut reply = CreateReply::default();
+
+        reply
+            .embed(|e| e.title("Delete Reminders").description("No Reminders").color(*THEME_COLOR))
+            .components(|comp| {
+                pager.create_button_row(0, comp);
+                comp
+            });
+
+        return reply;
+    }
+
+    let pages = max_delete_page(reminders, &timezone);
+
+    let mut page = page;
+    if page >= pages {
+        page = pages - 1;
+    }
+
+    let mut char_count = 0;
+    let mut rows = 0;
+    let mut skipped_rows = 0;
+    let mut skipped_char_count = 0;
+    let mut first_num = 0;
+
+    let mut skipped_pages = 0;
+
+    let (shown_reminders, display_vec): (Vec<&Reminder>, Vec<String>) = reminders
+        .iter()
+        .enumerate()
+        .map(|(count, reminder)| (reminder, reminder.display_del(count, &timezone)))
+        .skip_while(|(_, p)| {
+            first_num += 1;
+            skipped_rows += 1;
+            skipped_char_count += p.len();
+
+            if skipped_char_count > EMBED_DESCRIPTION_MAX_LENGTH
+                || skipped_rows > SELECT_MAX_ENTRIES
+            {
+                skipped_rows = 1;
+                skipped_char_count = p.len();
+                skipped_pages += 1;
+            }
+
+            skipped_pages < page
+        })
+        .take_while(|(_, p)| {
+            rows += 1;
+            char_count += p.len();
+
+            char_count < EMBED_DESCRIPTION_MAX_LENGTH && rows <= SELECT_MAX_ENTRIES
+        })
+        .unzip();
+
+    let display = display_vec.join("\n");
+
+    let del_selector = ComponentDataModel::DelSelector(DelSelector { page, timezone });
+
+    let mut reply = CreateReply::default();
+
+    reply
+        .embed(|e| {
             e.title("Delete Reminders")
                 .description(display)
                 .footer(|f| f.text(format!("Page {} of {}", page + 1, pages)))
@@ -438,8 +1205,10 @@ pub fn show_delete_page(reminders: &[Reminder], page: usize, timezone: Tz) -> Cr
 }
 
 fn time_difference(start_time: DateTime<Utc>) -> String {
-    let delta = (Utc::now() - start_time).num_seconds();
+    format_seconds((Utc::now() - start_time).num_seconds())
+}
 
+fn format_seconds(delta: i64) -> String {
     let (minutes, seconds) = delta.div_rem(&60);
     let (hours, minutes) = minutes.div_rem(&60);
     let (days, hours) = hours.div_rem(&24);
@@ -447,106 +1216,601 @@ fn time_difference(start_time: DateTime<Utc>) -> String {
     format!("{} days, {:02}:{:02}:{:02}", days, hours, minutes, seconds)
 }
 
-/// Manage timers
-#[poise::command(
-    slash_command,
-    rename = "timer",
-    identifying_name = "timer_base",
-    default_member_permissions = "MANAGE_GUILD"
-)]
-pub async fn timer_base(_ctx: Context<'_>) -> Result<(), Error> {
+fn display_countdown(timer: &Timer, now: DateTime<Utc>) -> String {
+    match timer.countdown_phase(now) {
+        CountdownPhase::Work { cycle, remaining } => {
+            if timer.cycles > 1 {
+                format!(
+                    "⏳ `{}` remaining (work, cycle {} of {})",
+                    format_seconds(remaining),
+                    cycle,
+                    timer.cycles
+                )
+            } else {
+                format!("⏳ `{}` remaining", format_seconds(remaining))
+            }
+        }
+
+        CountdownPhase::Break { cycle, remaining } => format!(
+            "☕ `{}` remaining (break, cycle {} of {})",
+            format_seconds(remaining),
+            cycle,
+            timer.cycles
+        ),
+
+        CountdownPhase::Finished => "✅ Finished".to_string(),
+    }
+}
+
+/// Manage timers
+#[poise::command(
+    slash_command,
+    rename = "timer",
+    identifying_name = "timer_base",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn timer_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List the timers in this server or DM channel
+#[poise::command(
+    slash_command,
+    rename = "list",
+    identifying_name = "list_timer",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn list_timer(ctx: Context<'_>) -> Result<(), Error> {
+    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+
+    let timers = Timer::from_owner(owner, &ctx.data().database).await;
+
+    if !timers.is_empty() {
+        let now = Utc::now();
+
+        ctx.send(|m| {
+            m.embed(|e| {
+                e.fields(timers.iter().map(|timer| {
+                    if timer.is_countdown() {
+                        (&timer.name, display_countdown(timer, now), false)
+                    } else {
+                        (&timer.name, format!("⌚ `{}`", time_difference(timer.start_time)), false)
+                    }
+                }))
+                .color(*THEME_COLOR)
+            })
+        })
+        .await?;
+    } else {
+        ctx.say("No timers currently. Use `/timer start` to create a new timer").await?;
+    }
+
+    Ok(())
+}
+
+/// Start a new timer from now
+#[poise::command(
+    slash_command,
+    rename = "start",
+    identifying_name = "start_timer",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn start_timer(
+    ctx: Context<'_>,
+    #[description = "Name for the new timer"] name: String,
+) -> Result<(), Error> {
+    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+
+    let count = Timer::count_from_owner(owner, &ctx.data().database).await;
+    let max_timers = max_timers(ctx).await?;
+
+    if count >= max_timers {
+        return refuse(format!(
+            "You already have {} timers. Please delete some timers before creating a new one",
+            max_timers
+        ));
+    } else if name.len() <= 32 {
+        Timer::create(&name, owner, &ctx.data().database).await;
+
+        ctx.say("Created a new timer").await?;
+    } else {
+        return refuse(format!(
+            "Please name your timer something shorted (max. 32 characters, you used {})",
+            name.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Start a timer counting down, with a reminder sent when it completes
+#[poise::command(
+    slash_command,
+    rename = "countdown",
+    identifying_name = "countdown_timer",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn countdown_timer(
+    ctx: Context<'_>,
+    #[description = "Time to count down, e.g. `25 minutes`"] duration: String,
+    #[description = "Name for the new timer"] name: Option<String>,
+    #[description = "For pomodoro-style timers, the length of the break between work cycles"]
+    break_length: Option<String>,
+    #[description = "For pomodoro-style timers, the number of work cycles"] cycles: Option<u32>,
+) -> Result<(), Error> {
+    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+    let name = name.unwrap_or_else(|| "Countdown".to_string());
+
+    let work_seconds = match parse_duration(&duration) {
+        Ok(interval) => interval_seconds(interval),
+        Err(_) => {
+            return refuse(
+                "Duration could not be processed. Try similar to `25 minutes` or `1 hour`",
+            );
+        }
+    };
+
+    let break_seconds = match break_length.as_ref().map(|b| parse_duration(b)) {
+        Some(Ok(interval)) => Some(interval_seconds(interval)),
+        Some(Err(_)) => {
+            return refuse("Break length could not be processed. Try similar to `5 minutes`");
+        }
+        None => None,
+    };
+
+    let cycles = cycles.unwrap_or(1);
+    let max_timers = max_timers(ctx).await?;
+
+    if Timer::count_from_owner(owner, &ctx.data().database).await >= max_timers {
+        return refuse(format!(
+            "You already have {} timers. Please delete some timers before creating a new one",
+            max_timers
+        ));
+    } else if name.len() > 32 {
+        return refuse(format!(
+            "Please name your timer something shorted (max. 32 characters, you used {})",
+            name.len()
+        ));
+    } else if work_seconds == 0 || work_seconds as i64 > *MAX_TIME {
+        return refuse("Please specify a duration between 1 second and the maximum reminder time");
+    } else if cycles == 0 || cycles > MAX_TIMER_CYCLES {
+        return refuse(format!("Please specify between 1 and {} cycles", MAX_TIMER_CYCLES));
+    } else if break_seconds.is_some() && cycles == 1 {
+        return refuse("`break_length` can only be used with more than one cycle");
+    } else {
+        ctx.defer().await?;
+
+        let user_data = ctx.author_data().await?;
+        let now = Utc::now().timestamp();
+
+        // each cycle ends with a reminder, and each break between cycles ends with another
+        let mut phase_ends = vec![];
+        let mut elapsed = 0;
+        for cycle in 1..=cycles {
+            elapsed += work_seconds as i64;
+
+            if cycle == cycles {
+                phase_ends.push((
+                    elapsed,
+                    format!("{} ⏰ Timer **{}** has finished!", ctx.author().mention(), name),
+                ));
+            } else {
+                phase_ends.push((
+                    elapsed,
+                    format!(
+                        "{} ☕ Timer **{}**: cycle {} of {} complete. Time for a break!",
+                        ctx.author().mention(),
+                        name,
+                        cycle,
+                        cycles
+                    ),
+                ));
+
+                elapsed += break_seconds.unwrap_or(0) as i64;
+
+                if break_seconds.is_some() {
+                    phase_ends.push((
+                        elapsed,
+                        format!(
+                            "{} ⏳ Timer **{}**: break over. Starting cycle {} of {}",
+                            ctx.author().mention(),
+                            name,
+                            cycle + 1,
+                            cycles
+                        ),
+                    ));
+                }
+            }
+        }
+
+        let end_time = NaiveDateTime::from_timestamp_opt(now + elapsed, 0).unwrap();
+
+        let timer_id = Timer::create_countdown(
+            &name,
+            owner,
+            end_time,
+            work_seconds,
+            break_seconds,
+            cycles,
+            &ctx.data().database,
+        )
+        .await?;
+
+        let mut errors = HashSet::new();
+        let phase_count = phase_ends.len();
+
+        for (offset, content) in phase_ends {
+            let scope = if ctx.guild_id().is_some() {
+                ReminderScope::Channel(ctx.channel_id().0)
+            } else {
+                ReminderScope::User(ctx.author().id.0)
+            };
+
+            let mut builder = MultiReminderBuilder::new(&ctx, ctx.guild_id())
+                .author(user_data.clone())
+                .content(Content { content, tts: false, attachment: None, attachment_name: None })
+                .time(now + offset)
+                .timer(Some(timer_id));
+
+            builder.set_scopes(vec![scope]);
+
+            let (phase_errors, _) = builder.build().await;
+            errors.extend(phase_errors);
+        }
+
+        if errors.is_empty() {
+            ctx.say(format!("Started countdown **{}**, finishing <t:{}:R>", name, now + elapsed))
+                .await?;
+
+            post_to_log(
+                ctx,
+                LogEvent::Created,
+                format!(
+                    "Countdown **{}** ({} reminders) started by {}, finishing <t:{}:F>",
+                    name,
+                    phase_count,
+                    ctx.author().mention(),
+                    now + elapsed
+                ),
+            )
+            .await;
+        } else {
+            sqlx::query!("DELETE FROM timers WHERE id = ?", timer_id)
+                .execute(&ctx.data().database)
+                .await?;
+
+            ctx.say(format!(
+                "Countdown could not be started:\n{}",
+                errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n")
+            ))
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn interval_seconds(interval: Interval) -> u32 {
+    (interval.sec + interval.day * DAY + interval.month * 30 * DAY).min(u32::MAX as u64) as u32
+}
+
+/// Delete a timer
+#[poise::command(
+    slash_command,
+    rename = "delete",
+    identifying_name = "delete_timer",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn delete_timer(
+    ctx: Context<'_>,
+    #[description = "Name of timer to delete"] name: String,
+) -> Result<(), Error> {
+    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+
+    let exists = sqlx::query!(
+        "SELECT 1 as _r FROM timers WHERE owner = ? AND name = ? AND channel_id IS NULL",
+        owner,
+        name
+    )
+    .fetch_one(&ctx.data().database)
+    .await;
+
+    if exists.is_ok() {
+        sqlx::query!(
+            "DELETE FROM timers WHERE owner = ? AND name = ? AND channel_id IS NULL",
+            owner,
+            name
+        )
+        .execute(&ctx.data().database)
+        .await
+        .unwrap();
+
+        ctx.say("Deleted a timer").await?;
+    } else {
+        return refuse("Could not find a timer by that name");
+    }
+
+    Ok(())
+}
+
+async fn max_timers(ctx: Context<'_>) -> Result<u32, Error> {
+    match ctx.guild_data().await {
+        Some(guild_data) => Ok(guild_data?.max_timers),
+
+        None => Ok(DEFAULT_MAX_TIMERS),
+    }
+}
+
+/// Configure timers in this server
+#[poise::command(
+    slash_command,
+    rename = "settings",
+    guild_only = true,
+    identifying_name = "timer_settings",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn timer_settings(
+    ctx: Context<'_>,
+    #[description = "Role allowed to start, stop and reset shared timers"] role: Option<Role>,
+    #[description = "Remove the role allowed to manage shared timers"] clear_role: Option<bool>,
+    #[description = "Maximum number of timers in this server"] limit: Option<u32>,
+) -> Result<(), Error> {
+    let mut guild_data = ctx.guild_data().await.unwrap()?;
+
+    if let Some(role) = role {
+        guild_data.timer_manager_role = Some(role.id.0);
+    } else if clear_role.unwrap_or(false) {
+        guild_data.timer_manager_role = None;
+    }
+
+    if let Some(limit) = limit {
+        if limit > MAX_TIMERS_LIMIT {
+            return refuse(format!("The timer limit must be at most {}", MAX_TIMERS_LIMIT));
+        }
+
+        guild_data.max_timers = limit;
+    }
+
+    guild_data.commit_changes(&ctx.data().database).await;
+
+    ctx.send(|m| {
+        m.embed(|e| {
+            e.title("Timer Settings")
+                .description(format!(
+                    "**Shared timer role:** {}\n**Timer limit:** {}",
+                    guild_data
+                        .timer_manager_role
+                        .map_or("None (Manage Server only)".to_string(), |r| format!("<@&{}>", r)),
+                    guild_data.max_timers
+                ))
+                .color(*THEME_COLOR)
+        })
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// View and manage timers shared in this channel
+#[poise::command(
+    slash_command,
+    rename = "channeltimer",
+    guild_only = true,
+    identifying_name = "shared_timer_base"
+)]
+pub async fn shared_timer_base(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+async fn can_manage_shared_timers(ctx: Context<'_>) -> Result<bool, Error> {
+    let guild_data = ctx.guild_data().await.unwrap()?;
+    let member = ctx.guild_id().unwrap().member(&ctx.discord(), ctx.author().id).await?;
+
+    if guild_data.timer_manager_role.map_or(false, |r| member.roles.contains(&RoleId(r))) {
+        Ok(true)
+    } else if let Some(guild) = ctx.guild() {
+        Ok(guild
+            .member_permissions(&ctx.discord(), ctx.author().id)
+            .await
+            .map_or(false, |p| p.manage_guild()))
+    } else {
+        Ok(false)
+    }
+}
+
+async fn shared_timer(ctx: Context<'_>, name: &str) -> Option<Timer> {
+    Timer::from_channel(ctx.channel_id().0, &ctx.data().database)
+        .await
+        .into_iter()
+        .find(|timer| timer.name == name)
+}
+
+/// List the timers shared in this channel
+#[poise::command(
+    slash_command,
+    rename = "list",
+    guild_only = true,
+    identifying_name = "list_shared_timer"
+)]
+pub async fn list_shared_timer(ctx: Context<'_>) -> Result<(), Error> {
+    let timers = Timer::from_channel(ctx.channel_id().0, &ctx.data().database).await;
+
+    if !timers.is_empty() {
+        let now = Utc::now();
+
+        ctx.send(|m| {
+            m.embed(|e| {
+                e.fields(timers.iter().map(|timer| {
+                    if timer.stopped_at.is_some() {
+                        (
+                            &timer.name,
+                            format!("⏸️ `{}` (stopped)", format_seconds(timer.elapsed_until(now))),
+                            false,
+                        )
+                    } else {
+                        (
+                            &timer.name,
+                            format!("⌚ `{}`", format_seconds(timer.elapsed_until(now))),
+                            false,
+                        )
+                    }
+                }))
+                .color(*THEME_COLOR)
+            })
+        })
+        .await?;
+    } else {
+        ctx.say(
+            "No timers shared in this channel. Use `/channeltimer start` to create a new timer",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Start a new shared timer, or restart a stopped one
+#[poise::command(
+    slash_command,
+    rename = "start",
+    guild_only = true,
+    identifying_name = "start_shared_timer"
+)]
+pub async fn start_shared_timer(
+    ctx: Context<'_>,
+    #[description = "Name of the timer"] name: String,
+) -> Result<(), Error> {
+    if !can_manage_shared_timers(ctx).await? {
+        return refuse("You do not have the role required to manage shared timers");
+    }
+
+    match shared_timer(ctx, &name).await {
+        Some(timer) => {
+            if timer.stopped_at.is_some() {
+                timer.resume(&ctx.data().database).await;
+
+                ctx.say(format!("Timer **{}** started", name)).await?;
+            } else {
+                return refuse(format!("Timer **{}** is already running", name));
+            }
+        }
+
+        None => {
+            let owner = ctx.guild_id().unwrap().0;
+            let count = Timer::count_from_owner(owner, &ctx.data().database).await;
+            let max_timers = max_timers(ctx).await?;
+
+            if count >= max_timers {
+                return refuse(format!(
+                    "This server already has {} timers. Please delete some timers before creating a new one",
+                    max_timers
+                ));
+            } else if name.len() > 32 {
+                return refuse(format!(
+                    "Please name your timer something shorted (max. 32 characters, you used {})",
+                    name.len()
+                ));
+            } else {
+                let channel_data = ctx.channel_data().await?;
+
+                Timer::create_shared(&name, owner, channel_data.id, &ctx.data().database).await;
+
+                ctx.say(format!("Created a new shared timer **{}**", name)).await?;
+            }
+        }
+    }
+
     Ok(())
 }
 
-/// List the timers in this server or DM channel
+/// Stop a shared timer
 #[poise::command(
     slash_command,
-    rename = "list",
-    identifying_name = "list_timer",
-    default_member_permissions = "MANAGE_GUILD"
+    rename = "stop",
+    guild_only = true,
+    identifying_name = "stop_shared_timer"
 )]
-pub async fn list_timer(ctx: Context<'_>) -> Result<(), Error> {
-    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+pub async fn stop_shared_timer(
+    ctx: Context<'_>,
+    #[description = "Name of the timer"] name: String,
+) -> Result<(), Error> {
+    if !can_manage_shared_timers(ctx).await? {
+        return refuse("You do not have the role required to manage shared timers");
+    }
 
-    let timers = Timer::from_owner(owner, &ctx.data().database).await;
+    match shared_timer(ctx, &name).await {
+        Some(timer) => {
+            timer.stop(&ctx.data().database).await;
 
-    if !timers.is_empty() {
-        ctx.send(|m| {
-            m.embed(|e| {
-                e.fields(timers.iter().map(|timer| {
-                    (&timer.name, format!("⌚ `{}`", time_difference(timer.start_time)), false)
-                }))
-                .color(*THEME_COLOR)
-            })
-        })
-        .await?;
-    } else {
-        ctx.say("No timers currently. Use `/timer start` to create a new timer").await?;
+            ctx.say(format!("Timer **{}** stopped", name)).await?;
+        }
+
+        None => {
+            return refuse("Could not find a timer by that name");
+        }
     }
 
     Ok(())
 }
 
-/// Start a new timer from now
+/// Reset a shared timer to zero
 #[poise::command(
     slash_command,
-    rename = "start",
-    identifying_name = "start_timer",
-    default_member_permissions = "MANAGE_GUILD"
+    rename = "reset",
+    guild_only = true,
+    identifying_name = "reset_shared_timer"
 )]
-pub async fn start_timer(
+pub async fn reset_shared_timer(
     ctx: Context<'_>,
-    #[description = "Name for the new timer"] name: String,
+    #[description = "Name of the timer"] name: String,
 ) -> Result<(), Error> {
-    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+    if !can_manage_shared_timers(ctx).await? {
+        return refuse("You do not have the role required to manage shared timers");
+    }
 
-    let count = Timer::count_from_owner(owner, &ctx.data().database).await;
+    match shared_timer(ctx, &name).await {
+        Some(timer) => {
+            timer.reset(&ctx.data().database).await;
 
-    if count >= 25 {
-        ctx.say("You already have 25 timers. Please delete some timers before creating a new one")
-            .await?;
-    } else if name.len() <= 32 {
-        Timer::create(&name, owner, &ctx.data().database).await;
+            ctx.say(format!("Timer **{}** reset", name)).await?;
+        }
 
-        ctx.say("Created a new timer").await?;
-    } else {
-        ctx.say(format!(
-            "Please name your timer something shorted (max. 32 characters, you used {})",
-            name.len()
-        ))
-        .await?;
+        None => {
+            return refuse("Could not find a timer by that name");
+        }
     }
 
     Ok(())
 }
 
-/// Delete a timer
+/// Delete a shared timer
 #[poise::command(
     slash_command,
     rename = "delete",
-    identifying_name = "delete_timer",
-    default_member_permissions = "MANAGE_GUILD"
+    guild_only = true,
+    identifying_name = "delete_shared_timer"
 )]
-pub async fn delete_timer(
+pub async fn delete_shared_timer(
     ctx: Context<'_>,
-    #[description = "Name of timer to delete"] name: String,
+    #[description = "Name of the timer"] name: String,
 ) -> Result<(), Error> {
-    let owner = ctx.guild_id().map(|g| g.0).unwrap_or_else(|| ctx.author().id.0);
+    if !can_manage_shared_timers(ctx).await? {
+        return refuse("You do not have the role required to manage shared timers");
+    }
 
-    let exists =
-        sqlx::query!("SELECT 1 as _r FROM timers WHERE owner = ? AND name = ?", owner, name)
-            .fetch_one(&ctx.data().database)
-            .await;
+    match shared_timer(ctx, &name).await {
+        Some(timer) => {
+            sqlx::query!("DELETE FROM timers WHERE id = ?", timer.id)
+                .execute(&ctx.data().database)
+                .await
+                .unwrap();
 
-    if exists.is_ok() {
-        sqlx::query!("DELETE FROM timers WHERE owner = ? AND name = ?", owner, name)
-            .execute(&ctx.data().database)
-            .await
-            .unwrap();
+            ctx.say("Deleted a timer").await?;
+        }
 
-        ctx.say("Deleted a timer").await?;
-    } else {
-        ctx.say("Could not find a timer by that name").await?;
+        None => {
+            return refuse("Could not find a timer by that name");
+        }
     }
 
     Ok(())
@@ -562,6 +1826,111 @@ struct ContentModal {
     content: String,
 }
 
+#[derive(poise::Modal)]
+#[name = "Remind me about this"]
+struct RemindMeModal {
+    #[name = "When"]
+    #[placeholder = "e.g. in 2 hours, tomorrow at 9am, friday 5pm"]
+    #[max_length = 100]
+    time: String,
+}
+
+/// A link to a message, followed by its content as a quote
+fn remind_me_content(ctx: Context<'_>, message: &Message) -> String {
+    let link = format!(
+        "Reminder about https://discord.com/channels/{}/{}/{}",
+        ctx.guild_id().map_or("@me".to_string(), |g| g.to_string()),
+        message.channel_id,
+        message.id
+    );
+    let quote =
+        message.content.lines().map(|l| format!("> {}", l)).collect::<Vec<String>>().join("\n");
+
+    if quote.is_empty() {
+        link
+    } else {
+        let space = MESSAGE_MAX_LENGTH - link.chars().count() - 1;
+
+        if quote.chars().count() > space {
+            format!("{}\n{}…", link, quote.chars().take(space - 1).collect::<String>())
+        } else {
+            format!("{}\n{}", link, quote)
+        }
+    }
+}
+
+/// Get a DM reminder about a message
+#[poise::command(context_menu_command = "Remind me about this")]
+pub async fn remind_me_context_menu(
+    ctx: ApplicationContext<'_>,
+    message: Message,
+) -> Result<(), Error> {
+    let data = RemindMeModal::execute(ctx).await?;
+    let ctx = Context::Application(ctx);
+
+    let user_data = ctx.author_data().await?;
+    let timezone = user_data.timezone();
+
+    let time = match natural_parser(&data.time, &timezone.to_string()).await {
+        Some(time) if time > Utc::now().timestamp() => time,
+
+        Some(_) => {
+            return refuse("Time is in the past");
+        }
+
+        None => {
+            return refuse("Time not recognised");
+        }
+    };
+
+    let mut content = Content {
+        content: remind_me_content(ctx, &message),
+        tts: false,
+        attachment: None,
+        attachment_name: None,
+    };
+
+    // the first attachment is carried over, if it is small enough to upload again
+    if let Some(attachment) = message.attachments.first().filter(|a| a.size <= MAX_ATTACHMENT_SIZE)
+    {
+        if let Ok(data) = attachment.download().await {
+            content.attachment = Some(data);
+            content.attachment_name = Some(attachment.filename.clone());
+        }
+    }
+
+    // a personal reminder, so it isn't shown in the server's log
+    let mut builder =
+        MultiReminderBuilder::new(&ctx, None).author(user_data).content(content).time(time);
+
+    builder.set_scopes(vec![ReminderScope::User(ctx.author().id.0)]);
+
+    let (errors, successes) = builder.build().await;
+
+    if successes.is_empty() {
+        let embed = create_response(&successes, &errors, time);
+
+        ctx.send(|r| {
+            r.ephemeral(true).embed(|e| {
+                *e = embed;
+                e
+            })
+        })
+        .await?;
+    } else {
+        ctx.send(|r| {
+            r.ephemeral(true).content(format!(
+                "I'll remind you <t:{}:F> ({})",
+                time,
+                time_hint(time - Utc::now().timestamp()).to_lowercase()
+            ))
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
 /// Create a reminder with multi-line content. Press "+4 more" for other options.
 #[poise::command(
     slash_command,
@@ -583,6 +1952,7 @@ pub async fn multiline(
     #[description = "Set a timezone override for this reminder only"]
     #[autocomplete = "timezone_autocomplete"]
     timezone: Option<String>,
+    #[description = "A name to refer to the reminder by"] name: Option<String>,
 ) -> Result<(), Error> {
     let tz = timezone.map(|t| t.parse::<Tz>().ok()).flatten();
     let data = ContentModal::execute(ctx).await?;
@@ -590,16 +1960,278 @@ pub async fn multiline(
     create_reminder(
         Context::Application(ctx),
         time,
-        data.content,
+        Content {
+            content: data.content,
+            tts: tts.unwrap_or(false),
+            attachment: None,
+            attachment_name: None,
+        },
         channels,
         interval,
         expires,
-        tts,
         tz,
+        name,
+        None,
+        None,
+        None,
     )
     .await
 }
 
+#[derive(poise::Modal)]
+#[name = "Reminder Embed"]
+struct EmbedModal {
+    #[name = "Title"]
+    #[max_length = 256]
+    title: Option<String>,
+    #[name = "Description"]
+    #[paragraph]
+    #[max_length = 4000]
+    description: Option<String>,
+    #[name = "Colour"]
+    #[placeholder = "e.g. #8fb677"]
+    #[max_length = 7]
+    color: Option<String>,
+    #[name = "Author"]
+    #[max_length = 256]
+    author: Option<String>,
+    #[name = "Footer"]
+    #[paragraph]
+    #[max_length = 2048]
+    footer: Option<String>,
+}
+
+#[derive(poise::Modal)]
+#[name = "Embed Field"]
+struct EmbedFieldModal {
+    #[name = "Title"]
+    #[max_length = 256]
+    title: String,
+    #[name = "Value"]
+    #[paragraph]
+    #[max_length = 1024]
+    value: String,
+    #[name = "Inline"]
+    #[placeholder = "yes or no"]
+    #[max_length = 3]
+    inline: Option<String>,
+}
+
+fn embed_preview_buttons(c: &mut CreateComponents, embed: &Embed) -> &mut CreateComponents {
+    let button = |action| ComponentDataModel::EmbedPreview(EmbedPreview { action }).to_custom_id();
+
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b.label("Add field")
+                .style(ButtonStyle::Secondary)
+                .disabled(embed.fields.len() >= EMBED_MAX_FIELDS)
+                .custom_id(button(EmbedPreviewAction::AddField))
+        })
+        .create_button(|b| {
+            b.label("Save").style(ButtonStyle::Success).custom_id(button(EmbedPreviewAction::Save))
+        })
+        .create_button(|b| {
+            b.label("Cancel")
+                .style(ButtonStyle::Danger)
+                .custom_id(button(EmbedPreviewAction::Cancel))
+        })
+    })
+}
+
+/// Create a reminder with an embed, written in a pop-up and previewed before it is saved
+#[poise::command(
+    slash_command,
+    rename = "remind-embed",
+    identifying_name = "remind_embed",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn remind_embed(
+    ctx: ApplicationContext<'_>,
+    #[description = "A description of the time to set the reminder for"]
+    #[autocomplete = "time_hint_autocomplete"]
+    time: String,
+    #[description = "Message content to send above the embed"] content: Option<String>,
+    #[description = "Channel or user mentions to set the reminder for"] channels: Option<String>,
+    #[description = "(Patreon only) Time to wait before repeating the reminder. Leave blank for one-shot reminder"]
+    interval: Option<String>,
+    #[description = "(Patreon only) For repeating reminders, the time at which the reminder will stop repeating"]
+    expires: Option<String>,
+    #[description = "Set a timezone override for this reminder only"]
+    #[autocomplete = "timezone_autocomplete"]
+    timezone: Option<String>,
+    #[description = "A name to refer to the reminder by"] name: Option<String>,
+) -> Result<(), Error> {
+    let deadline = Instant::now() + Duration::from_secs(EMBED_PREVIEW_TIMEOUT);
+    let tz = timezone.map(|t| t.parse::<Tz>().ok()).flatten();
+    let data = EmbedModal::execute(ctx).await?;
+    let ctx = Context::Application(ctx);
+
+    let color = match data.color.filter(|c| !c.trim().is_empty()) {
+        Some(color) => match parse_color(&color) {
+            Some(color) => color,
+
+            None => {
+                return refuse("Colours should be written in hex, e.g. `#8fb677`");
+            }
+        },
+
+        None => 0,
+    };
+
+    let mut embed = Embed {
+        title: data.title.unwrap_or_default(),
+        description: data.description.unwrap_or_default(),
+        author: data.author.unwrap_or_default(),
+        footer: data.footer.unwrap_or_default(),
+        color,
+        ..Default::default()
+    };
+
+    if let Some(error) = embed.error() {
+        return refuse(error);
+    }
+
+    let reply = ctx
+        .send(|r| {
+            r.ephemeral(true)
+                .content("Preview of the reminder's embed")
+                .embed(|e| {
+                    *e = embed.preview();
+                    e
+                })
+                .components(|c| embed_preview_buttons(c, &embed))
+        })
+        .await?;
+    let preview = reply.message().await?;
+
+    loop {
+        let interaction = CollectComponentInteraction::new(ctx.discord())
+            .message_id(preview.id)
+            .author_id(ctx.author().id)
+            .timeout(deadline.saturating_duration_since(Instant::now()))
+            .await;
+
+        let interaction = match interaction {
+            Some(interaction) => interaction,
+
+            None => {
+                reply
+                    .edit(ctx, |r| r.content("This preview has expired").components(|c| c))
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+        let action = match ComponentDataModel::from_custom_id(&interaction.data.custom_id) {
+            ComponentDataModel::EmbedPreview(button) => button.action,
+
+            _ => continue,
+        };
+
+        match action {
+            EmbedPreviewAction::AddField => {
+                let custom_id = interaction.id.to_string();
+
+                interaction
+                    .create_interaction_response(ctx.discord(), |r| {
+                        *r = EmbedFieldModal::create(None, custom_id.clone());
+                        r
+                    })
+                    .await?;
+
+                let submit = CollectModalInteraction::new(ctx.discord())
+                    .filter(move |m| m.data.custom_id == custom_id)
+                    .timeout(deadline.saturating_duration_since(Instant::now()))
+                    .await;
+
+                if let Some(submit) = submit {
+                    let mut message = "Preview of the reminder's embed".to_string();
+
+                    if let Ok(field) = EmbedFieldModal::parse(submit.data.clone()) {
+                        embed.fields.push(EmbedField {
+                            title: field.title,
+                            value: field.value,
+                            inline: field.inline.map_or(false, |i| {
+                                ["yes", "y", "true"].contains(&i.trim().to_lowercase().as_str())
+                            }),
+                        });
+
+                        // fields that would make the embed too long to send aren't kept
+                        if let Some(error) = embed.error() {
+                            embed.fields.pop();
+                            message = format!("Field not added: {}", error);
+                        }
+                    }
+
+                    submit
+                        .create_interaction_response(ctx.discord(), |r| {
+                            r.kind(InteractionResponseType::UpdateMessage)
+                                .interaction_response_data(|d| {
+                                    d.content(message)
+                                        .set_embed(embed.preview())
+                                        .components(|c| embed_preview_buttons(c, &embed))
+                                })
+                        })
+                        .await?;
+                }
+            }
+
+            EmbedPreviewAction::Save => {
+                if let Some(error) = embed.error() {
+                    interaction
+                        .create_interaction_response(ctx.discord(), |r| {
+                            r.kind(InteractionResponseType::UpdateMessage)
+                                .interaction_response_data(|d| d.content(error))
+                        })
+                        .await?;
+
+                    continue;
+                }
+
+                interaction
+                    .create_interaction_response(ctx.discord(), |r| {
+                        r.kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|d| d.components(|c| c))
+                    })
+                    .await?;
+
+                return create_reminder(
+                    ctx,
+                    time,
+                    Content {
+                        content: content.unwrap_or_default(),
+                        tts: false,
+                        attachment: None,
+                        attachment_name: None,
+                    },
+                    channels,
+                    interval,
+                    expires,
+                    tz,
+                    name,
+                    Some(embed),
+                    None,
+                    None,
+                )
+                .await;
+            }
+
+            EmbedPreviewAction::Cancel => {
+                interaction
+                    .create_interaction_response(ctx.discord(), |r| {
+                        r.kind(InteractionResponseType::UpdateMessage).interaction_response_data(
+                            |d| d.content("Reminder cancelled").components(|c| c),
+                        )
+                    })
+                    .await?;
+
+                return Ok(());
+            }
+        }
+    }
+}
+
 /// Create a reminder. Press "+4 more" for other options. Use "/multiline" for multiline content.
 #[poise::command(
     slash_command,
@@ -611,7 +2243,7 @@ pub async fn remind(
     #[description = "A description of the time to set the reminder for"]
     #[autocomplete = "time_hint_autocomplete"]
     time: String,
-    #[description = "The message content to send"] content: String,
+    #[description = "The message content to send"] content: Option<String>,
     #[description = "Channel or user mentions to set the reminder for"] channels: Option<String>,
     #[description = "(Patreon only) Time to wait before repeating the reminder. Leave blank for one-shot reminder"]
     interval: Option<String>,
@@ -622,27 +2254,102 @@ pub async fn remind(
     #[description = "Set a timezone override for this reminder only"]
     #[autocomplete = "timezone_autocomplete"]
     timezone: Option<String>,
+    #[description = "A name to refer to the reminder by"] name: Option<String>,
+    #[description = "A template to copy the message, embed, username and avatar from"]
+    #[autocomplete = "template_name_autocomplete"]
+    template: Option<String>,
+    #[description = "A display name to send the reminder with, or a persona from /persona"]
+    #[autocomplete = "persona_autocomplete"]
+    username: Option<String>,
+    #[description = "Link to an avatar image to send the reminder with"] avatar: Option<String>,
 ) -> Result<(), Error> {
+    let ctx = Context::Application(ctx);
     let tz = timezone.map(|t| t.parse::<Tz>().ok()).flatten();
 
-    create_reminder(Context::Application(ctx), time, content, channels, interval, expires, tts, tz)
-        .await
+    if let Some(error) = identity_error(username.as_deref(), avatar.as_deref()) {
+        return refuse(error);
+    }
+
+    let template = match (template, ctx.guild_id()) {
+        (Some(template), Some(guild_id)) => {
+            match ReminderTemplate::from_name(&ctx.data().database, guild_id, &template).await {
+                Some(template) => Some(template),
+
+                None => {
+                    return refuse(format!("No template in this server is named \"{}\"", template));
+                }
+            }
+        }
+
+        (Some(_), None) => {
+            return refuse("Templates can only be used in servers");
+        }
+
+        (None, _) => None,
+    };
+
+    // options given with a template replace the template's
+    let (content, embed, template_username, template_avatar) = match (template, content) {
+        (Some(template), content) => (
+            Content {
+                content: content.unwrap_or(template.content.content),
+                tts: tts.unwrap_or(template.content.tts),
+                attachment: template.content.attachment,
+                attachment_name: template.content.attachment_name,
+            },
+            Some(template.embed),
+            template.username,
+            template.avatar,
+        ),
+
+        (None, Some(content)) => (
+            Content { content, tts: tts.unwrap_or(false), attachment: None, attachment_name: None },
+            None,
+            None,
+            None,
+        ),
+
+        (None, None) => {
+            return refuse("Please specify `content` or a `template`");
+        }
+    };
+
+    // a persona's avatar is used with its name, unless another avatar is given
+    let persona = match (&username, ctx.guild_id()) {
+        (Some(username), Some(guild_id)) => {
+            Persona::from_name(&ctx.data().database, guild_id, username).await
+        }
+
+        _ => None,
+    };
+    let username = username.map(|u| u.trim().to_string()).or(template_username);
+    let avatar = avatar.or_else(|| persona.and_then(|p| p.avatar)).or(template_avatar);
+
+    create_reminder(
+        ctx, time, content, channels, interval, expires, tz, name, embed, username, avatar,
+    )
+    .await
 }
 
 async fn create_reminder(
     ctx: Context<'_>,
     time: String,
-    content: String,
+    content: Content,
     channels: Option<String>,
     interval: Option<String>,
     expires: Option<String>,
-    tts: Option<bool>,
     timezone: Option<Tz>,
+    name: Option<String>,
+    embed: Option<Embed>,
+    username: Option<String>,
+    avatar: Option<String>,
 ) -> Result<(), Error> {
     if interval.is_none() && expires.is_some() {
-        ctx.say("`expires` can only be used with `interval`").await?;
+        return refuse("`expires` can only be used with `interval`");
+    }
 
-        return Ok(());
+    if let Some(error) = name.as_deref().and_then(name_error) {
+        return refuse(error);
     }
 
     ctx.defer().await?;
@@ -654,12 +2361,6 @@ async fn create_reminder(
 
     match time {
         Some(time) => {
-            let content = {
-                let tts = tts.unwrap_or(false);
-
-                Content { content, tts, attachment: None, attachment_name: None }
-            };
-
             let scopes = {
                 let list = channels.map(|arg| parse_mention_list(&arg)).unwrap_or_default();
 
@@ -692,25 +2393,22 @@ async fn create_reminder(
                         },
                     )
                 } else {
-                    ctx.say(
+                    return refuse(
                         "`repeat` is only available to Patreon subscribers or self-hosted users",
-                    )
-                    .await?;
-
-                    return Ok(());
+                    );
                 }
             } else {
                 (None, None)
             };
 
             if processed_interval.is_none() && interval.is_some() {
-                ctx.say(
+                return refuse(
                     "Repeat interval could not be processed. Try similar to `1 hour` or `4 days`",
-                )
-                .await?;
+                );
             } else if processed_expires.is_none() && expires.is_some() {
-                ctx.say("Expiry time failed to process. Please make it as clear as possible")
-                    .await?;
+                return refuse(
+                    "Expiry time failed to process. Please make it as clear as possible",
+                );
             } else {
                 let mut builder = MultiReminderBuilder::new(&ctx, ctx.guild_id())
                     .author(user_data)
@@ -718,12 +2416,22 @@ async fn create_reminder(
                     .time(time)
                     .timezone(timezone)
                     .expires(processed_expires)
-                    .interval(processed_interval);
+                    .interval(processed_interval)
+                    .embed(embed.unwrap_or_default())
+                    .identity(username, avatar)
+                    .name(name);
 
                 builder.set_scopes(scopes);
 
                 let (errors, successes) = builder.build().await;
 
+                // a macro should stop if none of the reminders could be set
+                if successes.is_empty() && !errors.is_empty() {
+                    return refuse(
+                        errors.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"),
+                    );
+                }
+
                 let embed = create_response(&successes, &errors, time);
 
                 if successes.len() == 1 {
@@ -769,7 +2477,7 @@ async fn create_reminder(
         }
 
         None => {
-            ctx.say("Time could not be processed").await?;
+            return refuse("Time could not be processed");
         }
     }
 
@@ -786,8 +2494,11 @@ fn create_response(
         n => format!(
             "Reminder{s} for {locations} set for <t:{offset}:R>",
             s = if n > 1 { "s" } else { "" },
-            locations =
-                successes.iter().map(|(_, l)| l.mention()).collect::<Vec<String>>().join(", "),
+            locations = successes
+                .iter()
+                .map(|(r, l)| format!("{} as **{}**", l.mention(), r.name))
+                .collect::<Vec<String>>()
+                .join(", "),
             offset = time
         ),
     };
@@ -1,6 +1,9 @@
 pub(crate) mod pager;
 
-use std::io::Cursor;
+use std::{
+    io::Cursor,
+    time::{Duration, Instant},
+};
 
 use chrono_tz::Tz;
 use log::warn;
@@ -15,9 +18,10 @@ use poise::{
             },
             channel::Channel,
         },
-        Context,
+        Context, Mentionable,
     },
 };
+use postman::guild_log::{self, LogEvent};
 use rmp_serde::Serializer;
 use serde::{Deserialize, Serialize};
 
@@ -28,8 +32,11 @@ use crate::{
         todo_cmds::{max_todo_page, show_todo_page},
     },
     component_models::pager::{DelPager, LookPager, MacroPager, Pager, TodoPager},
-    consts::{EMBED_DESCRIPTION_MAX_LENGTH, THEME_COLOR},
-    models::reminder::Reminder,
+    consts::{BULK_CONFIRM_TIMEOUT, EMBED_DESCRIPTION_MAX_LENGTH, THEME_COLOR},
+    models::{
+        audit::{self, AuditAction, AuditSource, ReminderSnapshot},
+        reminder::Reminder,
+    },
     utils::send_as_initial_response,
     Data,
 };
@@ -45,6 +52,8 @@ pub enum ComponentDataModel {
     TodoSelector(TodoSelector),
     MacroPager(MacroPager),
     UndoReminder(UndoReminder),
+    BulkConfirm(BulkConfirm),
+    EmbedPreview(EmbedPreview),
 }
 
 impl ComponentDataModel {
@@ -65,7 +74,7 @@ impl ComponentDataModel {
     pub async fn act(&self, ctx: &Context, data: &Data, component: &MessageComponentInteraction) {
         match self {
             ComponentDataModel::LookPager(pager) => {
-                let flags = pager.flags;
+                let flags = &pager.flags;
 
                 let channel_opt = component.channel_id.to_channel_cached(&ctx);
 
@@ -79,11 +88,11 @@ impl ComponentDataModel {
                     component.channel_id
                 };
 
-                let reminders = Reminder::from_channel(&data.database, channel_id, &flags).await;
+                let reminders = Reminder::from_channel(&data.database, channel_id, flags).await;
 
                 let pages = reminders
                     .iter()
-                    .map(|reminder| reminder.display(&flags, &pager.timezone))
+                    .map(|reminder| reminder.display(flags, &pager.timezone))
                     .fold(0, |t, r| t + r.len())
                     .div_ceil(EMBED_DESCRIPTION_MAX_LENGTH);
 
@@ -101,7 +110,7 @@ impl ComponentDataModel {
 
                 let display = reminders
                     .iter()
-                    .map(|reminder| reminder.display(&flags, &pager.timezone))
+                    .map(|reminder| reminder.display(flags, &pager.timezone))
                     .skip_while(|p| {
                         skip_char_count += p.len();
 
@@ -165,12 +174,42 @@ impl ComponentDataModel {
             }
             ComponentDataModel::DelSelector(selector) => {
                 let selected_id = component.data.values.join(",");
+                let snapshots = ReminderSnapshot::of(&data.database, &selected_id).await;
 
                 sqlx::query!("DELETE FROM reminders WHERE FIND_IN_SET(id, ?)", selected_id)
                     .execute(&data.database)
                     .await
                     .unwrap();
 
+                for snapshot in &snapshots {
+                    audit::record(
+                        &data.database,
+                        component.user.id.0,
+                        AuditSource::Command,
+                        AuditAction::Delete,
+                        Some(snapshot),
+                        None,
+                    )
+                    .await;
+                }
+
+                if let Some(guild_id) = component.guild_id {
+                    for snapshot in &snapshots {
+                        guild_log::post(
+                            ctx,
+                            &data.database,
+                            guild_id.0,
+                            LogEvent::Deleted,
+                            format!(
+                                "{} deleted by {}",
+                                snapshot.describe(),
+                                component.user.mention()
+                            ),
+                        )
+                        .await;
+                    }
+                }
+
                 let reminders = Reminder::from_guild(
                     &ctx,
                     &data.database,
@@ -347,8 +386,38 @@ WHERE guilds.guild = ?",
                         Reminder::from_id(&data.database, undo_reminder.reminder_id).await;
 
                     if let Some(reminder) = reminder {
+                        let snapshots =
+                            ReminderSnapshot::of(&data.database, &reminder.id.to_string()).await;
+
                         match reminder.delete(&data.database).await {
                             Ok(()) => {
+                                audit::record(
+                                    &data.database,
+                                    component.user.id.0,
+                                    AuditSource::Command,
+                                    AuditAction::Delete,
+                                    snapshots.first(),
+                                    None,
+                                )
+                                .await;
+
+                                if let (Some(guild_id), Some(snapshot)) =
+                                    (component.guild_id, snapshots.first())
+                                {
+                                    guild_log::post(
+                                        ctx,
+                                        &data.database,
+                                        guild_id.0,
+                                        LogEvent::Deleted,
+                                        format!(
+                                            "{} canceled by {}",
+                                            snapshot.describe(),
+                                            component.user.mention()
+                                        ),
+                                    )
+                                    .await;
+                                }
+
                                 let _ = component
                                     .create_interaction_response(&ctx, |f| {
                                         f.kind(InteractionResponseType::UpdateMessage)
@@ -405,6 +474,44 @@ WHERE guilds.guild = ?",
                         .await;
                 }
             }
+            ComponentDataModel::BulkConfirm(confirm) => {
+                if component.user.id == confirm.user_id {
+                    let result = confirm.apply(ctx, data, component).await;
+
+                    let _ = component
+                        .create_interaction_response(&ctx, |f| {
+                            f.kind(InteractionResponseType::UpdateMessage)
+                                .interaction_response_data(|d| {
+                                    d.embed(|e| {
+                                        e.title(match &result {
+                                            Ok(_) => "Bulk Change Applied",
+                                            Err(_) => "Bulk Change Failed",
+                                        })
+                                        .description(match result {
+                                            Ok(done) => done,
+                                            Err(e) => e,
+                                        })
+                                        .color(*THEME_COLOR)
+                                    })
+                                    .components(|c| c)
+                                })
+                        })
+                        .await;
+                } else {
+                    let _ = component
+                        .create_interaction_response(&ctx, |f| {
+                            f.kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|d| {
+                                    d.content(
+                                        "Only the user who performed the command can use this button.")
+                                        .ephemeral(true)
+                                })
+                        })
+                        .await;
+                }
+            }
+            // answered by the `/remind-embed` command that is waiting on the preview
+            ComponentDataModel::EmbedPreview(_) => {}
         }
     }
 }
@@ -428,3 +535,172 @@ pub struct UndoReminder {
     pub user_id: serenity::UserId,
     pub reminder_id: u32,
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum EmbedPreviewAction {
+    AddField,
+    Save,
+    Cancel,
+}
+
+/// A button on the preview of a reminder embed
+#[derive(Serialize, Deserialize)]
+pub struct EmbedPreview {
+    pub action: EmbedPreviewAction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum BulkAction {
+    Enable,
+    Disable,
+    Delete,
+    /// Move the reminders by a number of seconds
+    Offset(i64),
+}
+
+impl BulkAction {
+    /// The question asked before applying the action to `count` reminders
+    pub fn question(&self, count: usize) -> String {
+        match self {
+            BulkAction::Enable => format!("Enable {} reminders?", count),
+            BulkAction::Disable => format!("Disable {} reminders?", count),
+            BulkAction::Delete => format!("Delete {} reminders?", count),
+            BulkAction::Offset(seconds) => {
+                format!("Move {} reminders by {} seconds?", count, seconds)
+            }
+        }
+    }
+
+    /// A description of the action having been applied to `count` reminders
+    pub fn done(&self, count: usize) -> String {
+        match self {
+            BulkAction::Enable => format!("{} reminders enabled", count),
+            BulkAction::Disable => format!("{} reminders disabled", count),
+            BulkAction::Delete => format!("{} reminders deleted", count),
+            BulkAction::Offset(seconds) => {
+                format!("{} reminders moved by {} seconds", count, seconds)
+            }
+        }
+    }
+}
+
+/// The reminders shown by a bulk preview. These are too many to keep in the custom ID, so are kept
+/// in `Data` under the ID of the preview's command
+pub struct BulkSelection {
+    pub selector: String,
+    pub ids: Vec<u32>,
+    pub created: Instant,
+}
+
+/// Confirms a bulk change previewed by `show_bulk_preview`. The change applies to exactly the
+/// reminders that were previewed, rather than whatever the selector matches by then
+#[derive(Serialize, Deserialize)]
+pub struct BulkConfirm {
+    pub user_id: serenity::UserId,
+    pub action: BulkAction,
+    pub selection: u64,
+}
+
+impl BulkConfirm {
+    async fn apply(
+        &self,
+        ctx: &Context,
+        data: &Data,
+        component: &MessageComponentInteraction,
+    ) -> Result<String, String> {
+        let guild_id = component.guild_id.ok_or("Bulk changes can only be made in servers")?;
+
+        let selection = data
+            .bulk_selections
+            .write()
+            .await
+            .remove(&self.selection)
+            .filter(|selection| {
+                selection.created.elapsed() < Duration::from_secs(BULK_CONFIRM_TIMEOUT)
+            })
+            .ok_or("This preview has expired. Please run the command again")?;
+
+        let ids = selection.ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(",");
+
+        let old = ReminderSnapshot::of(&data.database, &ids).await;
+
+        let result = match self.action {
+            BulkAction::Enable | BulkAction::Disable => {
+                sqlx::query!(
+                    "UPDATE reminders SET enabled = ? WHERE FIND_IN_SET(id, ?)",
+                    matches!(self.action, BulkAction::Enable),
+                    ids
+                )
+                .execute(&data.database)
+                .await
+            }
+
+            BulkAction::Delete => {
+                sqlx::query!("DELETE FROM reminders WHERE FIND_IN_SET(id, ?)", ids)
+                    .execute(&data.database)
+                    .await
+            }
+
+            BulkAction::Offset(seconds) => {
+                sqlx::query!(
+                    "UPDATE reminders SET utc_time = DATE_ADD(utc_time, INTERVAL ? SECOND)
+                    WHERE FIND_IN_SET(id, ?)",
+                    seconds,
+                    ids
+                )
+                .execute(&data.database)
+                .await
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Could not apply bulk change: {:?}", e);
+
+            return Err("The change could not be applied".to_string());
+        }
+
+        let (audit_action, event) = match self.action {
+            BulkAction::Delete => (AuditAction::Delete, LogEvent::Deleted),
+            BulkAction::Offset(_) => (AuditAction::Edit, LogEvent::Offset),
+            BulkAction::Enable | BulkAction::Disable => (AuditAction::Edit, LogEvent::Edited),
+        };
+
+        let new = ReminderSnapshot::of(&data.database, &ids).await;
+        audit::record_all(
+            &data.database,
+            component.user.id.0,
+            AuditSource::Command,
+            audit_action,
+            &old,
+            &new,
+        )
+        .await;
+
+        let done = self.action.done(old.len());
+        let selected = if selection.selector.is_empty() {
+            "all reminders".to_string()
+        } else {
+            format!("`{}`", selection.selector)
+        };
+
+        guild_log::post(
+            ctx,
+            &data.database,
+            guild_id.0,
+            event,
+            format!("{} by {}, selecting {}", done, component.user.mention(), selected),
+        )
+        .await;
+
+        // reminders can be sent or deleted while the preview is open
+        let missing = selection.ids.len().saturating_sub(old.len());
+        if missing > 0 {
+            Ok(format!(
+                "{}. {} of the reminders previewed no longer exist, so were left out",
+                done, missing
+            ))
+        } else {
+            Ok(done)
+        }
+    }
+}
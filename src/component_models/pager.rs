@@ -6,7 +6,10 @@ use poise::serenity_prelude::{
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 
-use crate::{component_models::ComponentDataModel, models::reminder::look_flags::LookFlags};
+use crate::{
+    component_models::ComponentDataModel, consts::MAX_CUSTOM_ID_LENGTH,
+    models::reminder::look_flags::LookFlags,
+};
 
 pub trait Pager {
     fn next_page(&self, max_pages: usize) -> usize;
@@ -47,7 +50,7 @@ impl Pager for LookPager {
         let next_page = self.next_page(max_pages);
 
         let (page_first, page_prev, page_refresh, page_next, page_last) =
-            LookPager::buttons(self.flags, next_page, self.timezone);
+            LookPager::buttons(self.flags.clone(), next_page, self.timezone);
 
         comp.create_action_row(|row| {
             row.create_button(|b| {
@@ -86,6 +89,19 @@ impl LookPager {
         Self { flags, page: 0, action: PageAction::First, timezone }
     }
 
+    /// Whether the pager's buttons fit in a custom ID, which a long search may not
+    pub fn fits(&self, max_pages: usize) -> bool {
+        ComponentDataModel::LookPager(LookPager {
+            flags: self.flags.clone(),
+            page: max_pages,
+            action: PageAction::Last,
+            timezone: self.timezone,
+        })
+        .to_custom_id()
+        .len()
+            <= MAX_CUSTOM_ID_LENGTH
+    }
+
     pub fn buttons(
         flags: LookFlags,
         page: usize,
@@ -99,25 +115,25 @@ impl LookPager {
     ) {
         (
             ComponentDataModel::LookPager(LookPager {
-                flags,
+                flags: flags.clone(),
                 page,
                 action: PageAction::First,
                 timezone,
             }),
             ComponentDataModel::LookPager(LookPager {
-                flags,
+                flags: flags.clone(),
                 page,
                 action: PageAction::Previous,
                 timezone,
             }),
             ComponentDataModel::LookPager(LookPager {
-                flags,
+                flags: flags.clone(),
                 page,
                 action: PageAction::Refresh,
                 timezone,
             }),
             ComponentDataModel::LookPager(LookPager {
-                flags,
+                flags: flags.clone(),
                 page,
                 action: PageAction::Next,
                 timezone,
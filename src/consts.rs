@@ -2,17 +2,41 @@ pub const DAY: u64 = 86_400;
 pub const HOUR: u64 = 3_600;
 pub const MINUTE: u64 = 60;
 
-pub const EMBED_DESCRIPTION_MAX_LENGTH: usize = 4096;
 pub const SELECT_MAX_ENTRIES: usize = 25;
+pub const MAX_CUSTOM_ID_LENGTH: usize = 100;
+pub const MESSAGE_MAX_LENGTH: usize = 2000;
+// Limits of the `username` and `avatar` columns that webhooks are sent with
+pub const WEBHOOK_USERNAME_MAX_LENGTH: usize = 32;
+pub const URL_MAX_LENGTH: usize = 512;
+// Largest file a bot can upload with a message
+pub const MAX_ATTACHMENT_SIZE: u64 = 8 * 1024 * 1024;
+// Keeps `/audit` within the 6000 character limit of an embed
+pub const AUDIT_PAGE_ENTRIES: usize = 10;
+pub const AUDIT_ENTRY_MAX_LENGTH: usize = 500;
+pub const BULK_PREVIEW_ENTRIES: usize = 10;
+// How long the reminders shown by a bulk preview are kept for it to be confirmed
+pub const BULK_CONFIRM_TIMEOUT: u64 = 15 * MINUTE;
+// Interaction tokens last 15 minutes, so previews have to be confirmed sooner
+pub const EMBED_PREVIEW_TIMEOUT: u64 = 10 * MINUTE;
 
 pub const CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
 
 const THEME_COLOR_FALLBACK: u32 = 0x8fb677;
-pub const MACRO_MAX_COMMANDS: usize = 5;
+// Discord modals can hold at most 5 text inputs
+pub const MACRO_MAX_PARAMETERS: usize = 5;
+pub const MAX_TIMER_CYCLES: u32 = 8;
+pub const DEFAULT_MAX_TIMERS: u32 = 25;
+pub const MAX_TIMERS_LIMIT: u32 = 250;
+pub const MAX_QUIET_HOURS: i64 = 20;
 
 use std::{collections::HashSet, env, iter::FromIterator};
 
 use poise::serenity_prelude::model::prelude::AttachmentType;
+pub use postman::consts::{
+    EMBED_AUTHOR_MAX_LENGTH, EMBED_DESCRIPTION_MAX_LENGTH, EMBED_FIELD_TITLE_MAX_LENGTH,
+    EMBED_FIELD_VALUE_MAX_LENGTH, EMBED_FOOTER_MAX_LENGTH, EMBED_MAX_FIELDS, EMBED_MAX_LENGTH,
+    EMBED_TITLE_MAX_LENGTH, MACRO_MAX_COMMANDS,
+};
 use regex::Regex;
 
 lazy_static! {
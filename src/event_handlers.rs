@@ -43,7 +43,7 @@ pub async fn listener(
                             m.embed(|e| {
                                 e.title("Thank you for adding Reminder Bot!").description(
                                     "To get started:
-• Set your timezone with `/timezone`
+• Set your timezone with `/timezone user`
 • Set up permissions in Server Settings 🠚 Integrations 🠚 Reminder Bot (desktop only)
 • Create your first reminder with `/remind`
 
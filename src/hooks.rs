@@ -14,10 +14,10 @@ async fn macro_check(ctx: Context<'_>) -> bool {
                     let mut lock = ctx.data().recording_macros.write().await;
 
                     if let Some(command_macro) = lock.get_mut(&(guild_id, ctx.author().id)) {
-                        if command_macro.commands.len() >= MACRO_MAX_COMMANDS {
+                        if command_macro.commands.len() >= *MACRO_MAX_COMMANDS {
                             let _ = ctx.send(|m| {
                             m.ephemeral(true).content(
-                                format!("{} commands already recorded. Please use `/macro finish` to end recording.", MACRO_MAX_COMMANDS),
+                                format!("{} commands already recorded. Please use `/macro finish` to end recording.", *MACRO_MAX_COMMANDS),
                             )
                         })
                             .await;
@@ -11,6 +11,7 @@ mod hooks;
 mod interval_parser;
 mod models;
 mod time_parser;
+mod timezone_resolver;
 mod utils;
 
 use std::{
@@ -32,6 +33,7 @@ use tokio::sync::{broadcast, broadcast::Sender, RwLock};
 
 use crate::{
     commands::{command_macro, info_cmds, moderation_cmds, reminder_cmds, todo_cmds},
+    component_models::BulkSelection,
     consts::THEME_COLOR,
     event_handlers::listener,
     hooks::all_checks,
@@ -49,6 +51,8 @@ pub struct Data {
     database: Pool<Database>,
     http: reqwest::Client,
     recording_macros: RwLock<HashMap<(GuildId, UserId), CommandMacro<Data, Error>>>,
+    // reminders shown by bulk previews, until the change is confirmed
+    bulk_selections: RwLock<HashMap<u64, BulkSelection>>,
     popular_timezones: Vec<Tz>,
     _broadcast: Sender<()>,
 }
@@ -75,6 +79,40 @@ impl Display for Ended {
 
 impl StdError for Ended {}
 
+/// A command refusing to do what was asked, e.g. on invalid options. The message is shown to the
+/// user, and macros count the command as failed
+#[derive(Debug)]
+pub struct Refusal(String);
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl StdError for Refusal {}
+
+/// Refuse to run a command, with a message explaining why
+pub fn refuse<T>(message: impl ToString) -> Result<T, Error> {
+    Err(Box::new(Refusal(message.to_string())))
+}
+
+async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    if let poise::FrameworkError::Command { error, ctx } = &error {
+        if let Some(refusal) = error.downcast_ref::<Refusal>() {
+            if let Err(e) = ctx.send(|r| r.ephemeral(true).content(&refusal.0)).await {
+                warn!("Could not send refusal: {:?}", e);
+            }
+
+            return;
+        }
+    }
+
+    if let Err(e) = poise::builtins::on_error(error).await {
+        error!("Error while handling error: {:?}", e);
+    }
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), Box<dyn StdError + Send + Sync>> {
     let (tx, mut rx) = broadcast::channel(16);
@@ -104,7 +142,14 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
             info_cmds::clock(),
             info_cmds::clock_context_menu(),
             info_cmds::dashboard(),
-            moderation_cmds::timezone(),
+            poise::Command {
+                subcommands: vec![
+                    moderation_cmds::timezone(),
+                    moderation_cmds::timezone_server(),
+                    moderation_cmds::timezone_channel(),
+                ],
+                ..moderation_cmds::timezone_base()
+            },
             poise::Command {
                 subcommands: vec![
                     moderation_cmds::set_allowed_dm(),
@@ -113,6 +158,30 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
                 ..moderation_cmds::allowed_dm()
             },
             moderation_cmds::webhook(),
+            poise::Command {
+                subcommands: vec![
+                    moderation_cmds::save_persona(),
+                    moderation_cmds::list_personas(),
+                    moderation_cmds::delete_persona(),
+                ],
+                ..moderation_cmds::persona_base()
+            },
+            poise::Command {
+                subcommands: vec![
+                    moderation_cmds::set_log_channel(),
+                    moderation_cmds::disable_log_channel(),
+                    moderation_cmds::log_channel_event(),
+                ],
+                ..moderation_cmds::log_channel_base()
+            },
+            poise::Command {
+                subcommands: vec![
+                    moderation_cmds::add_quiet_hours(),
+                    moderation_cmds::list_quiet_hours(),
+                    moderation_cmds::remove_quiet_hours(),
+                ],
+                ..moderation_cmds::quiet_hours_base()
+            },
             poise::Command {
                 subcommands: vec![
                     command_macro::delete::delete_macro(),
@@ -126,19 +195,57 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
             },
             reminder_cmds::pause(),
             reminder_cmds::offset(),
+            poise::Command {
+                subcommands: vec![
+                    reminder_cmds::bulk_enable(),
+                    reminder_cmds::bulk_disable(),
+                    reminder_cmds::bulk_delete(),
+                ],
+                ..reminder_cmds::bulk_base()
+            },
             reminder_cmds::nudge(),
             reminder_cmds::look(),
             reminder_cmds::delete(),
+            poise::Command {
+                subcommands: vec![
+                    reminder_cmds::reminder_show(),
+                    reminder_cmds::reminder_edit(),
+                    reminder_cmds::reminder_send_now(),
+                    reminder_cmds::reminder_enable(),
+                    reminder_cmds::reminder_disable(),
+                    reminder_cmds::reminder_delete(),
+                ],
+                ..reminder_cmds::reminder_base()
+            },
+            poise::Command {
+                subcommands: vec![reminder_cmds::template_save(), reminder_cmds::template_list()],
+                ..reminder_cmds::template_base()
+            },
+            reminder_cmds::audit(),
             poise::Command {
                 subcommands: vec![
                     reminder_cmds::list_timer(),
                     reminder_cmds::start_timer(),
+                    reminder_cmds::countdown_timer(),
                     reminder_cmds::delete_timer(),
+                    reminder_cmds::timer_settings(),
                 ],
                 ..reminder_cmds::timer_base()
             },
+            poise::Command {
+                subcommands: vec![
+                    reminder_cmds::list_shared_timer(),
+                    reminder_cmds::start_shared_timer(),
+                    reminder_cmds::stop_shared_timer(),
+                    reminder_cmds::reset_shared_timer(),
+                    reminder_cmds::delete_shared_timer(),
+                ],
+                ..reminder_cmds::shared_timer_base()
+            },
             reminder_cmds::multiline(),
             reminder_cmds::remind(),
+            reminder_cmds::remind_embed(),
+            reminder_cmds::remind_me_context_menu(),
             poise::Command {
                 subcommands: vec![
                     poise::Command {
@@ -165,6 +272,7 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
         ],
         allowed_mentions: None,
         command_check: Some(|ctx| Box::pin(all_checks(ctx))),
+        on_error: |error| Box::pin(on_error(error)),
         listener: |ctx, event, _framework, data| Box::pin(listener(ctx, event, data)),
         ..Default::default()
     };
@@ -204,6 +312,8 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
                 let pool1 = database.clone();
                 let pool2 = database.clone();
 
+                let macro_commands = command_macro::macro_commands(&framework.options().commands);
+
                 let run_settings = env::var("DONTRUN").unwrap_or_else(|_| "".to_string());
 
                 if !run_settings.contains("postman") {
@@ -221,7 +331,9 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
 
                 if !run_settings.contains("web") {
                     tokio::spawn(async move {
-                        reminder_web::initialize(kill_tx, ctx2, pool2).await.unwrap();
+                        reminder_web::initialize(kill_tx, ctx2, pool2, macro_commands)
+                            .await
+                            .unwrap();
                     });
                 } else {
                     warn!("Not running web");
@@ -232,6 +344,7 @@ async fn _main(tx: Sender<()>) -> Result<(), Box<dyn StdError + Send + Sync>> {
                     database,
                     popular_timezones,
                     recording_macros: Default::default(),
+                    bulk_selections: Default::default(),
                     _broadcast: tx,
                 })
             })
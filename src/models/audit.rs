@@ -0,0 +1,35 @@
+pub use postman::audit::{
+    record, record_all, record_offset, AuditAction, AuditSource, ReminderSnapshot,
+};
+
+use crate::Context;
+
+/// Commands run by a macro are given the context of the macro's own command
+pub fn source_of(ctx: &Context<'_>) -> AuditSource {
+    if ctx.command().identifying_name == "run_macro" {
+        AuditSource::Macro
+    } else {
+        AuditSource::Command
+    }
+}
+
+/// The kinds of change that `/audit` can be filtered to
+#[derive(Clone, Copy, poise::ChoiceParameter)]
+pub enum ActionFilter {
+    #[name = "Created"]
+    Create,
+    #[name = "Edited"]
+    Edit,
+    #[name = "Deleted"]
+    Delete,
+}
+
+impl From<ActionFilter> for AuditAction {
+    fn from(filter: ActionFilter) -> Self {
+        match filter {
+            ActionFilter::Create => AuditAction::Create,
+            ActionFilter::Edit => AuditAction::Edit,
+            ActionFilter::Delete => AuditAction::Delete,
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use lazy_regex::regex;
 use poise::serenity_prelude::model::{
     application::interaction::application_command::CommandDataOption, id::GuildId,
 };
@@ -23,13 +26,64 @@ pub struct RecordedCommand<U, E> {
     pub options: Vec<CommandDataOption>,
 }
 
+impl<U, E> RecordedCommand<U, E> {
+    /// Replace `{parameter}` placeholders in the recorded options with the values provided
+    pub fn substitute(&mut self, values: &HashMap<String, String>) {
+        fn substitute_options(options: &mut [CommandDataOption], values: &HashMap<String, String>) {
+            for option in options {
+                if let Some(Value::String(s)) = &option.value {
+                    let substituted =
+                        regex!(r"\{(\w{1,32})\}").replace_all(s, |caps: &regex::Captures| {
+                            values.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+                        });
+
+                    option.value = Some(Value::String(substituted.into_owned()));
+                }
+
+                substitute_options(&mut option.options, values);
+            }
+        }
+
+        substitute_options(&mut self.options, values);
+    }
+}
+
 pub struct CommandMacro<U, E> {
     pub guild_id: GuildId,
     pub name: String,
     pub description: Option<String>,
+    /// Whether to stop running the macro when one of its commands fails
+    pub abort_on_failure: bool,
     pub commands: Vec<RecordedCommand<U, E>>,
 }
 
+impl<U, E> CommandMacro<U, E> {
+    /// Names of the `{parameter}` placeholders used in the recorded commands, in order of first
+    /// appearance
+    pub fn parameters(&self) -> Vec<String> {
+        fn collect_parameters(options: &[CommandDataOption], parameters: &mut Vec<String>) {
+            for option in options {
+                if let Some(Value::String(s)) = &option.value {
+                    for caps in regex!(r"\{(\w{1,32})\}").captures_iter(s) {
+                        if !parameters.iter().any(|p| p == &caps[1]) {
+                            parameters.push(caps[1].to_string());
+                        }
+                    }
+                }
+
+                collect_parameters(&option.options, parameters);
+            }
+        }
+
+        let mut parameters = vec![];
+        for command in &self.commands {
+            collect_parameters(&command.options, &mut parameters);
+        }
+
+        parameters
+    }
+}
+
 pub struct RawCommandMacro {
     pub guild_id: GuildId,
     pub name: String,
@@ -43,7 +97,8 @@ pub async fn guild_command_macro(
 ) -> Option<CommandMacro<Data, Error>> {
     let row = sqlx::query!(
         "
-SELECT * FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?
+SELECT name, description, abort_on_failure AS \"abort_on_failure: bool\", commands
+FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?
         ",
         ctx.guild_id().unwrap().0,
         name
@@ -55,13 +110,22 @@ SELECT * FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND
     let mut commands: Vec<RecordedCommand<Data, Error>> =
         serde_json::from_str(&row.commands).unwrap();
 
+    fn find_command<'a>(
+        commands: &'a [poise::Command<Data, Error>],
+        identifying_name: &str,
+    ) -> Option<&'a poise::Command<Data, Error>> {
+        commands.iter().find_map(|c| {
+            if c.identifying_name == identifying_name {
+                Some(c)
+            } else {
+                find_command(&c.subcommands, identifying_name)
+            }
+        })
+    }
+
     for recorded_command in &mut commands {
-        let command = &ctx
-            .framework()
-            .options()
-            .commands
-            .iter()
-            .find(|c| c.identifying_name == recorded_command.command_name);
+        let command =
+            find_command(&ctx.framework().options().commands, &recorded_command.command_name);
 
         recorded_command.action = command.map(|c| c.slash_action).flatten();
     }
@@ -70,6 +134,7 @@ SELECT * FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND
         guild_id: ctx.guild_id().unwrap(),
         name: row.name,
         description: row.description,
+        abort_on_failure: row.abort_on_failure,
         commands,
     };
 
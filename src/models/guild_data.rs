@@ -0,0 +1,91 @@
+use chrono_tz::Tz;
+use poise::serenity_prelude::model::id::{ChannelId, GuildId};
+use sqlx::MySqlPool;
+
+pub struct GuildData {
+    pub id: u32,
+    pub guild: u64,
+    pub timer_manager_role: Option<u64>,
+    pub max_timers: u32,
+    pub timezone: Option<String>,
+}
+
+impl GuildData {
+    pub async fn from_guild<G: Into<GuildId>>(
+        guild: G,
+        pool: &MySqlPool,
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let guild_id = guild.into().0;
+
+        if let Ok(g) = sqlx::query_as_unchecked!(
+            Self,
+            "
+SELECT id, guild, timer_manager_role, max_timers, timezone FROM guilds WHERE guild = ?
+            ",
+            guild_id
+        )
+        .fetch_one(pool)
+        .await
+        {
+            Ok(g)
+        } else {
+            sqlx::query!(
+                "
+INSERT IGNORE INTO guilds (guild) VALUES (?)
+                ",
+                guild_id
+            )
+            .execute(pool)
+            .await?;
+
+            Ok(sqlx::query_as_unchecked!(
+                Self,
+                "
+SELECT id, guild, timer_manager_role, max_timers, timezone FROM guilds WHERE guild = ?
+                ",
+                guild_id
+            )
+            .fetch_one(pool)
+            .await?)
+        }
+    }
+
+    /// The timezone configured for a channel, falling back to the timezone configured for the
+    /// guild. `None` if neither is set, in which case the user's timezone should be used
+    pub async fn timezone_of<G: Into<GuildId>, C: Into<ChannelId>>(
+        guild: G,
+        channel: C,
+        pool: &MySqlPool,
+    ) -> Option<Tz> {
+        sqlx::query!(
+            "
+SELECT IFNULL(
+    (SELECT timezone FROM channels WHERE channel = ?),
+    (SELECT timezone FROM guilds WHERE guild = ?)
+) AS timezone
+            ",
+            channel.into().0,
+            guild.into().0
+        )
+        .fetch_one(pool)
+        .await
+        .ok()
+        .and_then(|r| r.timezone)
+        .and_then(|t| t.parse().ok())
+    }
+
+    pub async fn commit_changes(&self, pool: &MySqlPool) {
+        sqlx::query!(
+            "
+UPDATE guilds SET timer_manager_role = ?, max_timers = ?, timezone = ? WHERE id = ?
+            ",
+            self.timer_manager_role,
+            self.max_timers,
+            self.timezone,
+            self.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+}
@@ -1,17 +1,29 @@
+pub mod audit;
 pub mod channel_data;
 pub mod command_macro;
+pub mod guild_data;
+pub mod persona;
 pub mod reminder;
 pub mod timer;
 pub mod user_data;
 
 use chrono_tz::Tz;
-use poise::serenity_prelude::{async_trait, model::id::UserId, ChannelType};
+use poise::serenity_prelude::{
+    async_trait,
+    model::id::{ChannelId, UserId},
+    Channel, ChannelType,
+};
 
 use crate::{
-    models::{channel_data::ChannelData, user_data::UserData},
+    models::{channel_data::ChannelData, guild_data::GuildData, user_data::UserData},
     CommandMacro, Context, Data, Error, GuildId,
 };
 
+/// Whether a channel is a thread of any kind, whose reminders belong to its parent channel
+pub fn is_thread(kind: ChannelType) -> bool {
+    matches!(kind, ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread)
+}
+
 #[async_trait]
 pub trait CtxData {
     async fn user_data<U: Into<UserId> + Send>(&self, user_id: U) -> Result<UserData, Error>;
@@ -20,8 +32,12 @@ pub trait CtxData {
 
     async fn timezone(&self) -> Tz;
 
+    fn reminder_channel(&self) -> ChannelId;
+
     async fn channel_data(&self) -> Result<ChannelData, Error>;
 
+    async fn guild_data(&self) -> Option<Result<GuildData, Error>>;
+
     async fn command_macros(&self) -> Result<Vec<CommandMacro<Data, Error>>, Error>;
 }
 
@@ -38,17 +54,39 @@ impl CtxData for Context<'_> {
         UserData::from_user(&self.author().id, &self.discord(), &self.data().database).await
     }
 
+    /// The timezone to interpret times in: the channel's timezone, then the guild's timezone,
+    /// then the author's timezone. Per-command overrides are applied by the caller
     async fn timezone(&self) -> Tz {
+        if let Some(guild_id) = self.guild_id() {
+            if let Some(timezone) =
+                GuildData::timezone_of(guild_id, self.reminder_channel(), &self.data().database)
+                    .await
+            {
+                return timezone;
+            }
+        }
+
         UserData::timezone_of(self.author().id, &self.data().database).await
     }
 
+    /// The channel that reminders set here belong to. Threads use their parent channel
+    fn reminder_channel(&self) -> ChannelId {
+        match self.channel_id().to_channel_cached(&self.discord()) {
+            Some(Channel::Guild(channel)) if is_thread(channel.kind) => {
+                channel.parent_id.unwrap_or(channel.id)
+            }
+
+            _ => self.channel_id(),
+        }
+    }
+
     async fn channel_data(&self) -> Result<ChannelData, Box<dyn std::error::Error + Sync + Send>> {
         // If we're in a thread, get the parent channel.
         let recv_channel = self.channel_id().to_channel(&self.discord()).await?;
 
         let channel = match recv_channel.guild() {
             Some(guild_channel) => {
-                if guild_channel.kind == ChannelType::PublicThread {
+                if is_thread(guild_channel.kind) {
                     guild_channel.parent_id.unwrap().to_channel_cached(&self.discord()).unwrap()
                 } else {
                     self.channel_id().to_channel_cached(&self.discord()).unwrap()
@@ -61,6 +99,14 @@ impl CtxData for Context<'_> {
         ChannelData::from_channel(&channel, &self.data().database).await
     }
 
+    async fn guild_data(&self) -> Option<Result<GuildData, Error>> {
+        match self.guild_id() {
+            Some(guild_id) => Some(GuildData::from_guild(guild_id, &self.data().database).await),
+
+            None => None,
+        }
+    }
+
     async fn command_macros(&self) -> Result<Vec<CommandMacro<Data, Error>>, Error> {
         self.data().command_macros(self.guild_id().unwrap()).await
     }
@@ -72,7 +118,7 @@ impl Data {
         guild_id: GuildId,
     ) -> Result<Vec<CommandMacro<Data, Error>>, Error> {
         let rows = sqlx::query!(
-            "SELECT name, description, commands FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+            "SELECT name, description, abort_on_failure AS \"abort_on_failure: bool\", commands FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
             guild_id.0
         )
         .fetch_all(&self.database)
@@ -80,6 +126,7 @@ impl Data {
             guild_id,
             name: row.name.clone(),
             description: row.description.clone(),
+            abort_on_failure: row.abort_on_failure,
             commands: serde_json::from_str(&row.commands).unwrap(),
         }).collect();
 
@@ -0,0 +1,116 @@
+use poise::serenity_prelude::model::id::GuildId;
+use sqlx::Executor;
+
+use crate::{
+    consts::{URL_MAX_LENGTH, WEBHOOK_USERNAME_MAX_LENGTH},
+    Database,
+};
+
+/// A display name and avatar saved in a guild, for reminders to be sent as
+pub struct Persona {
+    pub name: String,
+    pub avatar: Option<String>,
+}
+
+/// Why a username or avatar can't be used to send a reminder, if it can't
+pub fn identity_error(username: Option<&str>, avatar: Option<&str>) -> Option<String> {
+    if let Some(username) = username {
+        let lower = username.to_lowercase();
+
+        if username.trim().is_empty() || username.chars().count() > WEBHOOK_USERNAME_MAX_LENGTH {
+            return Some(format!(
+                "Usernames must be between 1 and {} characters",
+                WEBHOOK_USERNAME_MAX_LENGTH
+            ));
+        } else if lower.contains("discord") || lower.contains("clyde") {
+            // Discord refuses webhook messages with these names
+            return Some("Usernames can't contain \"discord\" or \"clyde\"".to_string());
+        }
+    }
+
+    if let Some(avatar) = avatar {
+        if !(avatar.starts_with("http://") || avatar.starts_with("https://")) {
+            return Some("Avatars must be a link to an image".to_string());
+        } else if avatar.len() > URL_MAX_LENGTH {
+            return Some(format!("Avatar links can be at most {} characters", URL_MAX_LENGTH));
+        }
+    }
+
+    None
+}
+
+impl Persona {
+    pub async fn from_name(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Option<Self> {
+        sqlx::query_as!(
+            Self,
+            "
+SELECT name, avatar
+FROM persona
+WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+            guild_id.0,
+            name.trim()
+        )
+        .fetch_one(pool)
+        .await
+        .ok()
+    }
+
+    pub async fn from_guild(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Self,
+            "
+SELECT name, avatar
+FROM persona
+WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+ORDER BY name",
+            guild_id.0
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Save the persona, replacing the avatar of any persona with the same name
+    pub async fn save(
+        &self,
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "
+INSERT INTO persona (guild_id, name, avatar)
+VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?)
+ON DUPLICATE KEY UPDATE avatar = VALUES(avatar)",
+            guild_id.0,
+            self.name,
+            self.avatar
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Delete a persona by name, returning whether there was one to delete
+    pub async fn delete(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            "
+DELETE FROM persona
+WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+            guild_id.0,
+            name.trim()
+        )
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+}
@@ -2,6 +2,7 @@ use std::{collections::HashSet, fmt::Display};
 
 use chrono::{Duration, NaiveDateTime, Utc};
 use chrono_tz::Tz;
+use log::warn;
 use poise::serenity_prelude::{
     http::CacheHttp,
     model::{
@@ -9,16 +10,24 @@ use poise::serenity_prelude::{
         id::{ChannelId, GuildId, UserId},
         webhook::Webhook,
     },
-    ChannelType, Result as SerenityResult,
+    Mentionable, Result as SerenityResult,
 };
-use sqlx::MySqlPool;
+use postman::{
+    guild_log::{self, LogEvent},
+    names,
+};
+use sqlx::{mysql::MySqlDatabaseError, MySqlPool};
 
 use crate::{
     consts::{DAY, DEFAULT_AVATAR, MAX_TIME, MIN_INTERVAL},
     interval_parser::Interval,
     models::{
+        audit::{self, AuditAction, ReminderSnapshot},
         channel_data::ChannelData,
-        reminder::{content::Content, errors::ReminderError, helper::generate_uid, Reminder},
+        is_thread,
+        reminder::{
+            content::Content, embed::Embed, errors::ReminderError, helper::generate_uid, Reminder,
+        },
         user_data::UserData,
     },
     Context,
@@ -50,6 +59,7 @@ impl ReminderScope {
 pub struct ReminderBuilder {
     pool: MySqlPool,
     uid: String,
+    name: String,
     channel: u32,
     thread_id: Option<u64>,
     utc_time: NaiveDateTime,
@@ -62,7 +72,31 @@ pub struct ReminderBuilder {
     tts: bool,
     attachment_name: Option<String>,
     attachment: Option<Vec<u8>>,
+    embed: Embed,
+    username: Option<String>,
+    avatar: Option<String>,
     set_by: Option<u32>,
+    timer_id: Option<u32>,
+    // the default name was used, so is renumbered if another reminder takes it first
+    numbered: bool,
+}
+
+/// Whether an insert failed as the name is already used in the channel, i.e. MySQL error 1062 on
+/// the `unique_name_in_channel` index
+fn is_name_taken(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(e) => e.try_downcast_ref::<MySqlDatabaseError>().map_or(false, |e| {
+            e.number() == 1062 && e.message().contains("unique_name_in_channel")
+        }),
+
+        _ => false,
+    }
+}
+
+fn database_error(error: sqlx::Error) -> ReminderError {
+    warn!("Error building reminder: {:?}", error);
+
+    ReminderError::DatabaseError
 }
 
 impl ReminderBuilder {
@@ -74,17 +108,21 @@ impl ReminderBuilder {
         )
         .fetch_one(&self.pool)
         .await
-        .unwrap();
+        .map_err(database_error)?;
 
         match queried_time.utc_time {
             Some(utc_time) => {
                 if utc_time < (Utc::now() - Duration::seconds(60)).naive_local() {
                     Err(ReminderError::PastTime)
                 } else {
-                    sqlx::query!(
-                        "
+                    let mut name = self.name.clone();
+
+                    loop {
+                        let inserted = sqlx::query!(
+                            "
 INSERT INTO reminders (
     `uid`,
+    `name`,
     `channel_id`,
     `utc_time`,
     `timezone`,
@@ -96,8 +134,35 @@ INSERT INTO reminders (
     `tts`,
     `attachment_name`,
     `attachment`,
-    `set_by`
+    `embed_title`,
+    `embed_description`,
+    `embed_author`,
+    `embed_footer`,
+    `embed_color`,
+    `embed_fields`,
+    `embed_image_url`,
+    `embed_thumbnail_url`,
+    `embed_author_url`,
+    `embed_footer_url`,
+    `username`,
+    `avatar`,
+    `set_by`,
+    `timer_id`
 ) VALUES (
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
     ?,
     ?,
     ?,
@@ -113,25 +178,59 @@ INSERT INTO reminders (
     ?
 )
             ",
-                        self.uid,
-                        self.channel,
-                        utc_time,
-                        self.timezone,
-                        self.interval_seconds,
-                        self.interval_days,
-                        self.interval_months,
-                        self.expires,
-                        self.content,
-                        self.tts,
-                        self.attachment_name,
-                        self.attachment,
-                        self.set_by
-                    )
-                    .execute(&self.pool)
-                    .await
-                    .unwrap();
-
-                    Ok(Reminder::from_uid(&self.pool, &self.uid).await.unwrap())
+                            self.uid,
+                            name,
+                            self.channel,
+                            utc_time,
+                            self.timezone,
+                            self.interval_seconds,
+                            self.interval_days,
+                            self.interval_months,
+                            self.expires,
+                            self.content,
+                            self.tts,
+                            self.attachment_name,
+                            self.attachment,
+                            self.embed.title,
+                            self.embed.description,
+                            self.embed.author,
+                            self.embed.footer,
+                            self.embed.color,
+                            self.embed.fields_json(),
+                            self.embed.image_url,
+                            self.embed.thumbnail_url,
+                            self.embed.author_url,
+                            self.embed.footer_url,
+                            self.username,
+                            self.avatar,
+                            self.set_by,
+                            self.timer_id
+                        )
+                        .execute(&self.pool)
+                        .await;
+
+                        match inserted {
+                            Ok(_) => break,
+
+                            // another reminder took the name since it was checked
+                            Err(e) if is_name_taken(&e) => {
+                                if !self.numbered {
+                                    return Err(ReminderError::NameTaken);
+                                }
+
+                                name =
+                                    names::free_name(&self.pool, self.channel, names::DEFAULT_NAME)
+                                        .await
+                                        .map_err(database_error)?;
+                            }
+
+                            Err(e) => return Err(database_error(e)),
+                        }
+                    }
+
+                    Reminder::from_uid(&self.pool, &self.uid)
+                        .await
+                        .ok_or(ReminderError::DatabaseError)
                 }
             }
 
@@ -147,7 +246,12 @@ pub struct MultiReminderBuilder<'a> {
     interval: Option<Interval>,
     expires: Option<NaiveDateTime>,
     content: Content,
+    embed: Embed,
+    username: Option<String>,
+    avatar: Option<String>,
+    name: Option<String>,
     set_by: Option<u32>,
+    timer_id: Option<u32>,
     ctx: &'a Context<'a>,
     guild_id: Option<GuildId>,
 }
@@ -161,7 +265,12 @@ impl<'a> MultiReminderBuilder<'a> {
             interval: None,
             expires: None,
             content: Content::new(),
+            embed: Embed::default(),
+            username: None,
+            avatar: None,
+            name: None,
             set_by: None,
+            timer_id: None,
             ctx,
             guild_id,
         }
@@ -179,6 +288,29 @@ impl<'a> MultiReminderBuilder<'a> {
         self
     }
 
+    pub fn embed(mut self, embed: Embed) -> Self {
+        self.embed = embed;
+
+        self
+    }
+
+    /// The username and avatar the reminders are sent with, instead of the channel's webhook
+    /// defaults
+    pub fn identity(mut self, username: Option<String>, avatar: Option<String>) -> Self {
+        self.username = username;
+        self.avatar = avatar;
+
+        self
+    }
+
+    /// The name for the reminders, which must be free in each channel. Without a name, reminders are
+    /// given the first free default name
+    pub fn name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+
+        self
+    }
+
     pub fn time<T: Into<i64>>(mut self, time: T) -> Self {
         if let Some(utc_time) = NaiveDateTime::from_timestamp_opt(time.into(), 0) {
             self.utc_time = utc_time;
@@ -206,6 +338,12 @@ impl<'a> MultiReminderBuilder<'a> {
         self
     }
 
+    pub fn timer(mut self, timer_id: Option<u32>) -> Self {
+        self.timer_id = timer_id;
+
+        self
+    }
+
     pub fn set_scopes(&mut self, scopes: Vec<ReminderScope>) {
         self.scopes = scopes;
     }
@@ -264,9 +402,7 @@ impl<'a> MultiReminderBuilder<'a> {
                             if Some(guild_channel.guild_id) != self.guild_id {
                                 Err(ReminderError::InvalidTag)
                             } else {
-                                let mut channel_data = if guild_channel.kind
-                                    == ChannelType::PublicThread
-                                {
+                                let mut channel_data = if is_thread(guild_channel.kind) {
                                     // fixme jesus christ
                                     let parent = guild_channel
                                         .parent_id
@@ -318,11 +454,31 @@ impl<'a> MultiReminderBuilder<'a> {
                     }
                 };
 
+                let db_channel_id = match db_channel_id {
+                    Ok(c) => match &self.name {
+                        Some(name) => {
+                            match names::is_taken(&self.ctx.data().database, c, name, None).await {
+                                Ok(true) => Err(ReminderError::NameTaken),
+                                Ok(false) => Ok((c, name.trim().to_string())),
+                                Err(e) => Err(database_error(e)),
+                            }
+                        }
+
+                        None => names::free_name(&self.ctx.data().database, c, names::DEFAULT_NAME)
+                            .await
+                            .map(|name| (c, name))
+                            .map_err(database_error),
+                    },
+
+                    Err(e) => Err(e),
+                };
+
                 match db_channel_id {
-                    Ok(c) => {
+                    Ok((c, name)) => {
                         let builder = ReminderBuilder {
                             pool: self.ctx.data().database.clone(),
                             uid: generate_uid(),
+                            name,
                             channel: c,
                             thread_id,
                             utc_time: self.utc_time,
@@ -335,11 +491,50 @@ impl<'a> MultiReminderBuilder<'a> {
                             tts: self.content.tts,
                             attachment_name: self.content.attachment_name.clone(),
                             attachment: self.content.attachment.clone(),
+                            embed: self.embed.clone(),
+                            username: self.username.clone(),
+                            avatar: self.avatar.clone(),
                             set_by: self.set_by,
+                            timer_id: self.timer_id,
+                            numbered: self.name.is_none(),
                         };
 
                         match builder.build().await {
                             Ok(r) => {
+                                let snapshot = ReminderSnapshot::of(
+                                    &self.ctx.data().database,
+                                    &r.id.to_string(),
+                                )
+                                .await
+                                .pop()
+                                .map(|s| s.set_in(self.guild_id.map(|g| g.0)));
+                                audit::record(
+                                    &self.ctx.data().database,
+                                    self.ctx.author().id.0,
+                                    audit::source_of(self.ctx),
+                                    AuditAction::Create,
+                                    None,
+                                    snapshot.as_ref(),
+                                )
+                                .await;
+
+                                // countdowns post one message for all of their phases
+                                if let (Some(guild_id), None) = (self.guild_id, self.timer_id) {
+                                    guild_log::post(
+                                        self.ctx.discord(),
+                                        &self.ctx.data().database,
+                                        guild_id.0,
+                                        LogEvent::Created,
+                                        format!(
+                                            "Reminder for {} set by {} for <t:{}:F>",
+                                            scope.mention(),
+                                            self.ctx.author().mention(),
+                                            r.utc_time.timestamp()
+                                        ),
+                                    )
+                                    .await;
+                                }
+
                                 ok_locs.insert((r, scope));
                             }
                             Err(e) => {
@@ -0,0 +1,143 @@
+use poise::serenity_prelude::builder::CreateEmbed;
+use serde::{Deserialize, Serialize};
+
+use crate::consts::{
+    EMBED_AUTHOR_MAX_LENGTH, EMBED_DESCRIPTION_MAX_LENGTH, EMBED_FIELD_TITLE_MAX_LENGTH,
+    EMBED_FIELD_VALUE_MAX_LENGTH, EMBED_FOOTER_MAX_LENGTH, EMBED_MAX_FIELDS, EMBED_MAX_LENGTH,
+    EMBED_TITLE_MAX_LENGTH,
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EmbedField {
+    pub title: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+/// An embed sent with a reminder, stored in the `embed_*` columns of the reminder
+#[derive(Clone, Default)]
+pub struct Embed {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub footer: String,
+    pub color: u32,
+    pub fields: Vec<EmbedField>,
+    pub image_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub author_url: Option<String>,
+    pub footer_url: Option<String>,
+}
+
+/// Parse a colour written in hex, e.g. `#8fb677`
+pub fn parse_color(color: &str) -> Option<u32> {
+    let hex = color.trim().trim_start_matches('#');
+
+    if hex.len() == 6 {
+        u32::from_str_radix(hex, 16).ok()
+    } else {
+        None
+    }
+}
+
+impl Embed {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty()
+            && self.description.is_empty()
+            && self.author.is_empty()
+            && self.footer.is_empty()
+            && self.fields.is_empty()
+            && self.image_url.is_none()
+            && self.thumbnail_url.is_none()
+    }
+
+    /// Why the embed can't be sent, if it can't
+    pub fn error(&self) -> Option<String> {
+        let too_long = |name: &str, text: &str, max: usize| {
+            (text.chars().count() > max)
+                .then(|| format!("The {} can be at most {} characters", name, max))
+        };
+
+        if self.is_empty() {
+            return Some("Please fill in at least one part of the embed".to_string());
+        }
+
+        if self.fields.len() > EMBED_MAX_FIELDS {
+            return Some(format!("Embeds can have at most {} fields", EMBED_MAX_FIELDS));
+        }
+
+        too_long("title", &self.title, EMBED_TITLE_MAX_LENGTH)
+            .or_else(|| too_long("description", &self.description, EMBED_DESCRIPTION_MAX_LENGTH))
+            .or_else(|| too_long("author", &self.author, EMBED_AUTHOR_MAX_LENGTH))
+            .or_else(|| too_long("footer", &self.footer, EMBED_FOOTER_MAX_LENGTH))
+            .or_else(|| {
+                self.fields.iter().find_map(|field| {
+                    too_long("field title", &field.title, EMBED_FIELD_TITLE_MAX_LENGTH).or_else(
+                        || too_long("field value", &field.value, EMBED_FIELD_VALUE_MAX_LENGTH),
+                    )
+                })
+            })
+            .or_else(|| {
+                (self.length() > EMBED_MAX_LENGTH).then(|| {
+                    format!(
+                        "The embed can be at most {} characters in total, but is {}",
+                        EMBED_MAX_LENGTH,
+                        self.length()
+                    )
+                })
+            })
+    }
+
+    /// The number of characters counted towards Discord's limit on the whole embed
+    fn length(&self) -> usize {
+        [&self.title, &self.description, &self.author, &self.footer]
+            .into_iter()
+            .chain(self.fields.iter().flat_map(|field| [&field.title, &field.value]))
+            .map(|text| text.chars().count())
+            .sum()
+    }
+
+    /// The fields as stored in the `embed_fields` column
+    pub fn fields_json(&self) -> String {
+        serde_json::to_string(&self.fields).unwrap()
+    }
+
+    /// The embed as it will look when the reminder is sent
+    pub fn preview(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::default();
+
+        embed.title(&self.title).description(&self.description).color(self.color);
+
+        if !self.author.is_empty() {
+            embed.author(|a| {
+                if let Some(url) = &self.author_url {
+                    a.icon_url(url);
+                }
+                a.name(&self.author)
+            });
+        }
+
+        if !self.footer.is_empty() {
+            embed.footer(|f| {
+                if let Some(url) = &self.footer_url {
+                    f.icon_url(url);
+                }
+                f.text(&self.footer)
+            });
+        }
+
+        if let Some(url) = &self.image_url {
+            embed.image(url);
+        }
+
+        if let Some(url) = &self.thumbnail_url {
+            embed.thumbnail(url);
+        }
+
+        for field in &self.fields {
+            embed.field(&field.title, &field.value, field.inline);
+        }
+
+        embed
+    }
+}
@@ -8,6 +8,8 @@ pub enum ReminderError {
     ShortInterval,
     InvalidTag,
     UserBlockedDm,
+    NameTaken,
+    DatabaseError,
     DiscordError(String),
 }
 
@@ -34,6 +36,12 @@ impl ToString for ReminderError {
             ReminderError::UserBlockedDm => {
                 "User has DM reminders disabled".to_string()
             }
+            ReminderError::NameTaken => {
+                "A reminder with that name already exists in the channel. Please choose another name, or leave `name` blank".to_string()
+            }
+            ReminderError::DatabaseError => {
+                "The reminder couldn't be saved. Please try again".to_string()
+            }
             ReminderError::DiscordError(s) => format!("A Discord error occurred: **{}**", s),
         }
     }
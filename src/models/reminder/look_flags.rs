@@ -1,4 +1,4 @@
-use poise::serenity_prelude::model::id::ChannelId;
+use poise::serenity_prelude::model::id::{ChannelId, UserId};
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 
@@ -9,15 +9,66 @@ pub enum TimeDisplayType {
     Relative = 1,
 }
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+#[derive(Serialize_repr, Deserialize_repr, Copy, Clone, Debug, poise::ChoiceParameter)]
+#[repr(u8)]
+pub enum LookSort {
+    #[name = "Soonest first"]
+    Soonest = 0,
+    #[name = "Latest first"]
+    Latest = 1,
+    #[name = "Most recently created first"]
+    Newest = 2,
+}
+
+impl Default for LookSort {
+    fn default() -> Self {
+        LookSort::Soonest
+    }
+}
+
+/// Filters and display options for `/look`. These are kept in the custom ID of the pager, so are
+/// kept compact, and new fields are added to the end with a default so that older pagers still
+/// work
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LookFlags {
     pub show_disabled: bool,
     pub channel_id: Option<ChannelId>,
     pub time_display: TimeDisplayType,
+    /// Text to find in the content or embed of reminders
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub set_by: Option<UserId>,
+    /// Only show reminders due within this many seconds
+    #[serde(default)]
+    pub within: Option<u32>,
+    /// Only show repeating reminders, or only one-off reminders
+    #[serde(default)]
+    pub repeating: Option<bool>,
+    #[serde(default)]
+    pub sort: LookSort,
 }
 
 impl Default for LookFlags {
     fn default() -> Self {
-        Self { show_disabled: true, channel_id: None, time_display: TimeDisplayType::Relative }
+        Self {
+            show_disabled: true,
+            channel_id: None,
+            time_display: TimeDisplayType::Relative,
+            search: None,
+            set_by: None,
+            within: None,
+            repeating: None,
+            sort: LookSort::Soonest,
+        }
+    }
+}
+
+impl LookFlags {
+    /// The search as a pattern for SQL `LIKE`
+    pub fn search_pattern(&self) -> Option<String> {
+        self.search.as_ref().map(|search| {
+            format!("%{}%", search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+        })
     }
 }
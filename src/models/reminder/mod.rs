@@ -1,8 +1,10 @@
 pub mod builder;
 pub mod content;
+pub mod embed;
 pub mod errors;
 mod helper;
 pub mod look_flags;
+pub mod template;
 
 use std::hash::{Hash, Hasher};
 
@@ -15,7 +17,7 @@ use poise::serenity_prelude::{
 use sqlx::Executor;
 
 use crate::{
-    models::reminder::look_flags::{LookFlags, TimeDisplayType},
+    models::reminder::look_flags::{LookFlags, LookSort, TimeDisplayType},
     Database,
 };
 
@@ -23,6 +25,7 @@ use crate::{
 pub struct Reminder {
     pub id: u32,
     pub uid: String,
+    pub name: String,
     pub channel: u64,
     pub utc_time: DateTime<Utc>,
     pub interval_seconds: Option<u32>,
@@ -57,6 +60,7 @@ impl Reminder {
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
@@ -94,6 +98,7 @@ WHERE
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
@@ -124,6 +129,51 @@ WHERE
         .ok()
     }
 
+    /// Find a reminder by its name, which is unique within a channel
+    pub async fn from_name<C: Into<ChannelId>>(
+        pool: impl Executor<'_, Database = Database>,
+        channel_id: C,
+        name: &str,
+    ) -> Option<Self> {
+        sqlx::query_as_unchecked!(
+            Self,
+            "
+SELECT
+    reminders.id,
+    reminders.uid,
+    reminders.name,
+    channels.channel,
+    reminders.utc_time,
+    reminders.interval_seconds,
+    reminders.interval_days,
+    reminders.interval_months,
+    reminders.expires,
+    reminders.enabled,
+    reminders.content,
+    reminders.embed_description,
+    users.user AS set_by
+FROM
+    reminders
+INNER JOIN
+    channels
+ON
+    reminders.channel_id = channels.id
+LEFT JOIN
+    users
+ON
+    reminders.set_by = users.id
+WHERE
+    channels.channel = ? AND
+    reminders.name = ?
+            ",
+            channel_id.into().as_u64(),
+            name.trim()
+        )
+        .fetch_one(pool)
+        .await
+        .ok()
+    }
+
     pub async fn from_channel<C: Into<ChannelId>>(
         pool: impl Executor<'_, Database = Database>,
         channel_id: C,
@@ -131,13 +181,16 @@ WHERE
     ) -> Vec<Self> {
         let enabled = if flags.show_disabled { "0,1" } else { "1" };
         let channel_id = channel_id.into();
+        let search = flags.search_pattern();
+        let set_by = flags.set_by.map(|u| u.0);
 
-        sqlx::query_as_unchecked!(
+        let mut reminders = sqlx::query_as_unchecked!(
             Self,
             "
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
@@ -160,16 +213,54 @@ ON
     reminders.set_by = users.id
 WHERE
     channels.channel = ? AND
-    FIND_IN_SET(reminders.enabled, ?)
+    FIND_IN_SET(reminders.enabled, ?) AND
+    (? IS NULL OR users.user = ?) AND
+    (? IS NULL OR reminders.utc_time <= DATE_ADD(NOW(), INTERVAL ? SECOND)) AND
+    (? IS NULL OR (
+        reminders.interval_seconds IS NOT NULL OR
+        reminders.interval_days IS NOT NULL OR
+        reminders.interval_months IS NOT NULL
+    ) = ?) AND
+    (
+        ? IS NULL OR
+        CONCAT_WS(
+            ' ',
+            reminders.content,
+            reminders.embed_title,
+            reminders.embed_description,
+            reminders.embed_author,
+            reminders.embed_footer
+        ) LIKE ? OR
+        CAST(reminders.embed_fields AS CHAR) LIKE ?
+    )
 ORDER BY
     reminders.utc_time
             ",
             channel_id.as_u64(),
             enabled,
+            set_by,
+            set_by,
+            flags.within,
+            flags.within,
+            flags.repeating,
+            flags.repeating,
+            search,
+            search,
+            search,
         )
         .fetch_all(pool)
         .await
-        .unwrap()
+        .unwrap();
+
+        match flags.sort {
+            LookSort::Soonest => {}
+
+            LookSort::Latest => reminders.reverse(),
+
+            LookSort::Newest => reminders.sort_by(|a, b| b.id.cmp(&a.id)),
+        }
+
+        reminders
     }
 
     pub async fn from_guild(
@@ -196,6 +287,7 @@ ORDER BY
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
@@ -230,6 +322,7 @@ WHERE
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
@@ -265,6 +358,7 @@ WHERE
 SELECT
     reminders.id,
     reminders.uid,
+    reminders.name,
     channels.channel,
     reminders.utc_time,
     reminders.interval_seconds,
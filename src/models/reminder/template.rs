@@ -0,0 +1,164 @@
+use poise::serenity_prelude::model::id::GuildId;
+use sqlx::Executor;
+
+use crate::{
+    models::reminder::{
+        content::Content,
+        embed::{Embed, EmbedField},
+    },
+    Database,
+};
+
+/// A reminder saved in the `reminder_template` table, for creating similar reminders later
+pub struct ReminderTemplate {
+    pub name: String,
+    pub username: Option<String>,
+    pub avatar: Option<String>,
+    pub content: Content,
+    pub embed: Embed,
+}
+
+impl ReminderTemplate {
+    /// Find a template in a guild by name
+    pub async fn from_name(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Option<Self> {
+        let row = sqlx::query!(
+            "
+SELECT
+    name,
+    username,
+    avatar,
+    content,
+    tts,
+    attachment,
+    attachment_name,
+    embed_title,
+    embed_description,
+    embed_author,
+    embed_author_url,
+    embed_footer,
+    embed_footer_url,
+    embed_image_url,
+    embed_thumbnail_url,
+    embed_color,
+    CAST(embed_fields AS CHAR) AS \"embed_fields: String\"
+FROM reminder_template
+WHERE
+    guild_id = (SELECT id FROM guilds WHERE guild = ?)
+    AND name = ?
+ORDER BY id
+LIMIT 1",
+            guild_id.0,
+            name
+        )
+        .fetch_one(pool)
+        .await
+        .ok()?;
+
+        Some(ReminderTemplate {
+            name: row.name,
+            username: row.username.filter(|u| !u.is_empty()),
+            avatar: row.avatar.filter(|a| !a.is_empty()),
+            content: Content {
+                content: row.content,
+                tts: row.tts,
+                attachment: row.attachment,
+                attachment_name: row.attachment_name,
+            },
+            embed: Embed {
+                title: row.embed_title,
+                description: row.embed_description,
+                author: row.embed_author,
+                footer: row.embed_footer,
+                color: row.embed_color,
+                fields: row
+                    .embed_fields
+                    .and_then(|f| serde_json::from_str::<Vec<EmbedField>>(&f).ok())
+                    .unwrap_or_default(),
+                image_url: row.embed_image_url,
+                thumbnail_url: row.embed_thumbnail_url,
+                author_url: row.embed_author_url,
+                footer_url: row.embed_footer_url,
+            },
+        })
+    }
+
+    /// The names of the templates in a guild
+    pub async fn names(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "
+SELECT name
+FROM reminder_template
+WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+ORDER BY name",
+            guild_id.0
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Save a copy of a reminder's message as a template
+    pub async fn save_reminder(
+        pool: impl Executor<'_, Database = Database>,
+        guild_id: GuildId,
+        reminder_id: u32,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "
+INSERT INTO reminder_template (
+    guild_id,
+    name,
+    username,
+    avatar,
+    content,
+    tts,
+    attachment,
+    attachment_name,
+    embed_title,
+    embed_description,
+    embed_author,
+    embed_author_url,
+    embed_footer,
+    embed_footer_url,
+    embed_image_url,
+    embed_thumbnail_url,
+    embed_color,
+    embed_fields
+)
+SELECT
+    (SELECT id FROM guilds WHERE guild = ?),
+    ?,
+    username,
+    avatar,
+    content,
+    tts,
+    attachment,
+    attachment_name,
+    embed_title,
+    embed_description,
+    embed_author,
+    embed_author_url,
+    embed_footer,
+    embed_footer_url,
+    embed_image_url,
+    embed_thumbnail_url,
+    embed_color,
+    embed_fields
+FROM reminders
+WHERE id = ?",
+            guild_id.0,
+            name,
+            reminder_id
+        )
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+}
@@ -1,10 +1,24 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use sqlx::MySqlPool;
 
 pub struct Timer {
+    pub id: u32,
     pub name: String,
     pub start_time: DateTime<Utc>,
     pub owner: u64,
+    pub end_time: Option<DateTime<Utc>>,
+    pub work_seconds: Option<u32>,
+    pub break_seconds: Option<u32>,
+    pub cycles: u32,
+    pub channel_id: Option<u64>,
+    pub stopped_at: Option<DateTime<Utc>>,
+}
+
+/// The current state of a countdown timer, as displayed by `/timer list`
+pub enum CountdownPhase {
+    Work { cycle: u32, remaining: i64 },
+    Break { cycle: u32, remaining: i64 },
+    Finished,
 }
 
 impl Timer {
@@ -12,7 +26,19 @@ impl Timer {
         sqlx::query_as_unchecked!(
             Timer,
             "
-SELECT name, start_time, owner FROM timers WHERE owner = ?
+SELECT
+    timers.id,
+    timers.name,
+    timers.start_time,
+    timers.owner,
+    timers.end_time,
+    timers.work_seconds,
+    timers.break_seconds,
+    timers.cycles,
+    NULL AS channel_id,
+    timers.stopped_at
+FROM timers
+WHERE owner = ? AND channel_id IS NULL
             ",
             owner
         )
@@ -21,10 +47,39 @@ SELECT name, start_time, owner FROM timers WHERE owner = ?
         .unwrap()
     }
 
+    /// Fetch the timers shared in a channel, visible to all members of the channel
+    pub async fn from_channel(channel: u64, pool: &MySqlPool) -> Vec<Self> {
+        sqlx::query_as_unchecked!(
+            Timer,
+            "
+SELECT
+    timers.id,
+    timers.name,
+    timers.start_time,
+    timers.owner,
+    timers.end_time,
+    timers.work_seconds,
+    timers.break_seconds,
+    timers.cycles,
+    channels.channel AS channel_id,
+    timers.stopped_at
+FROM timers
+INNER JOIN channels ON timers.channel_id = channels.id
+WHERE channels.channel = ?
+            ",
+            channel
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Count the timers towards the owner's limit. Finished countdowns don't count, as they're
+    /// removed once their last reminder is sent
     pub async fn count_from_owner(owner: u64, pool: &MySqlPool) -> u32 {
         sqlx::query!(
             "
-SELECT COUNT(1) as count FROM timers WHERE owner = ?
+SELECT COUNT(1) as count FROM timers WHERE owner = ? AND (end_time IS NULL OR end_time > NOW())
             ",
             owner
         )
@@ -46,4 +101,114 @@ INSERT INTO timers (name, owner) VALUES (?, ?)
         .await
         .unwrap();
     }
+
+    pub async fn create_shared(name: &str, owner: u64, channel_id: u32, pool: &MySqlPool) {
+        sqlx::query!(
+            "
+INSERT INTO timers (name, owner, channel_id) VALUES (?, ?, ?)
+            ",
+            name,
+            owner,
+            channel_id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Create a timer counting down to `end_time`. Returns the ID of the new timer, so that the
+    /// reminders firing on completion can be attached to it.
+    pub async fn create_countdown(
+        name: &str,
+        owner: u64,
+        end_time: NaiveDateTime,
+        work_seconds: u32,
+        break_seconds: Option<u32>,
+        cycles: u32,
+        pool: &MySqlPool,
+    ) -> Result<u32, sqlx::Error> {
+        let row = sqlx::query!(
+            "
+INSERT INTO timers (name, owner, end_time, work_seconds, break_seconds, cycles)
+VALUES (?, ?, ?, ?, ?, ?)
+            ",
+            name,
+            owner,
+            end_time,
+            work_seconds,
+            break_seconds,
+            cycles
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(row.last_insert_id() as u32)
+    }
+
+    /// Stop the timer, freezing the elapsed time until it is started again
+    pub async fn stop(&self, pool: &MySqlPool) {
+        sqlx::query!(
+            "UPDATE timers SET stopped_at = NOW() WHERE id = ? AND stopped_at IS NULL",
+            self.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Start a stopped timer, continuing from the elapsed time when it was stopped
+    pub async fn resume(&self, pool: &MySqlPool) {
+        sqlx::query!(
+            "
+UPDATE timers
+SET start_time = DATE_ADD(start_time, INTERVAL TIMESTAMPDIFF(SECOND, stopped_at, NOW()) SECOND),
+    stopped_at = NULL
+WHERE id = ? AND stopped_at IS NOT NULL
+            ",
+            self.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    pub async fn reset(&self, pool: &MySqlPool) {
+        sqlx::query!(
+            "UPDATE timers SET start_time = NOW(), stopped_at = NULL WHERE id = ?",
+            self.id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// The time counted by the timer, excluding any time it has spent stopped
+    pub fn elapsed_until(&self, now: DateTime<Utc>) -> i64 {
+        (self.stopped_at.unwrap_or(now) - self.start_time).num_seconds().max(0)
+    }
+
+    pub fn is_countdown(&self) -> bool {
+        self.end_time.is_some()
+    }
+
+    pub fn countdown_phase(&self, now: DateTime<Utc>) -> CountdownPhase {
+        match (self.end_time, self.work_seconds) {
+            (Some(end_time), Some(work_seconds)) if now < end_time => {
+                let elapsed = (now - self.start_time).num_seconds().max(0);
+                let work_seconds = work_seconds as i64;
+                let cycle_length = work_seconds + self.break_seconds.unwrap_or(0) as i64;
+
+                let cycle = (elapsed / cycle_length) as u32 + 1;
+                let position = elapsed % cycle_length;
+
+                if position < work_seconds {
+                    CountdownPhase::Work { cycle, remaining: work_seconds - position }
+                } else {
+                    CountdownPhase::Break { cycle, remaining: cycle_length - position }
+                }
+            }
+
+            _ => CountdownPhase::Finished,
+        }
+    }
 }
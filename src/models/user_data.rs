@@ -5,6 +5,7 @@ use sqlx::MySqlPool;
 
 use crate::consts::LOCAL_TIMEZONE;
 
+#[derive(Clone)]
 pub struct UserData {
     pub id: u32,
     pub user: u64,
@@ -0,0 +1,343 @@
+//! Offline dataset used to resolve locations and abbreviations to IANA timezones. Cities that
+//! appear in IANA zone names (e.g. "London", "New York") are matched from the zone names
+//! themselves, so only cities that don't are listed here.
+
+/// Timezone abbreviations. Abbreviations shared between several regions list every region, so
+/// that the ambiguity can be explained to the user.
+pub const ABBREVIATIONS: &[(&str, &[(&str, &str)])] = &[
+    ("UTC", &[("UTC", "Coordinated Universal Time")]),
+    ("GMT", &[("Etc/GMT", "Greenwich Mean Time")]),
+    ("Z", &[("UTC", "Zulu time (UTC)")]),
+    ("WET", &[("Europe/Lisbon", "Western European Time")]),
+    ("WEST", &[("Europe/Lisbon", "Western European Summer Time")]),
+    (
+        "BST",
+        &[("Europe/London", "British Summer Time"), ("Asia/Dhaka", "Bangladesh Standard Time")],
+    ),
+    (
+        "IST",
+        &[
+            ("Asia/Kolkata", "India Standard Time"),
+            ("Asia/Jerusalem", "Israel Standard Time"),
+            ("Europe/Dublin", "Irish Standard Time"),
+        ],
+    ),
+    ("CET", &[("Europe/Berlin", "Central European Time")]),
+    ("CEST", &[("Europe/Berlin", "Central European Summer Time")]),
+    ("EET", &[("Europe/Athens", "Eastern European Time")]),
+    ("EEST", &[("Europe/Athens", "Eastern European Summer Time")]),
+    ("MSK", &[("Europe/Moscow", "Moscow Time")]),
+    ("TRT", &[("Europe/Istanbul", "Turkey Time")]),
+    ("SAST", &[("Africa/Johannesburg", "South Africa Standard Time")]),
+    ("CAT", &[("Africa/Maputo", "Central Africa Time")]),
+    ("EAT", &[("Africa/Nairobi", "East Africa Time")]),
+    ("WAT", &[("Africa/Lagos", "West Africa Time")]),
+    ("IRST", &[("Asia/Tehran", "Iran Standard Time")]),
+    (
+        "GST",
+        &[("Asia/Dubai", "Gulf Standard Time"), ("Atlantic/South_Georgia", "South Georgia Time")],
+    ),
+    ("PKT", &[("Asia/Karachi", "Pakistan Standard Time")]),
+    ("NPT", &[("Asia/Kathmandu", "Nepal Time")]),
+    ("ICT", &[("Asia/Bangkok", "Indochina Time")]),
+    ("WIB", &[("Asia/Jakarta", "Western Indonesia Time")]),
+    ("SGT", &[("Asia/Singapore", "Singapore Time")]),
+    ("HKT", &[("Asia/Hong_Kong", "Hong Kong Time")]),
+    ("PHT", &[("Asia/Manila", "Philippine Time")]),
+    ("PHST", &[("Asia/Manila", "Philippine Standard Time")]),
+    ("KST", &[("Asia/Seoul", "Korea Standard Time")]),
+    ("JST", &[("Asia/Tokyo", "Japan Standard Time")]),
+    ("AWST", &[("Australia/Perth", "Australian Western Standard Time")]),
+    (
+        "ACST",
+        &[
+            ("Australia/Adelaide", "Australian Central Standard Time (South Australia)"),
+            ("Australia/Darwin", "Australian Central Standard Time (Northern Territory)"),
+        ],
+    ),
+    ("ACDT", &[("Australia/Adelaide", "Australian Central Daylight Time")]),
+    (
+        "AEST",
+        &[
+            ("Australia/Sydney", "Australian Eastern Standard Time (New South Wales, Victoria)"),
+            ("Australia/Brisbane", "Australian Eastern Standard Time (Queensland)"),
+        ],
+    ),
+    ("AEDT", &[("Australia/Sydney", "Australian Eastern Daylight Time")]),
+    ("NZST", &[("Pacific/Auckland", "New Zealand Standard Time")]),
+    ("NZDT", &[("Pacific/Auckland", "New Zealand Daylight Time")]),
+    ("ET", &[("America/New_York", "Eastern Time (North America)")]),
+    ("EST", &[("America/New_York", "Eastern Standard Time (North America)")]),
+    ("EDT", &[("America/New_York", "Eastern Daylight Time (North America)")]),
+    ("CT", &[("America/Chicago", "Central Time (North America)")]),
+    (
+        "CST",
+        &[
+            ("America/Chicago", "Central Standard Time (North America)"),
+            ("Asia/Shanghai", "China Standard Time"),
+            ("America/Havana", "Cuba Standard Time"),
+        ],
+    ),
+    ("CDT", &[("America/Chicago", "Central Daylight Time (North America)")]),
+    ("MT", &[("America/Denver", "Mountain Time (North America)")]),
+    (
+        "MST",
+        &[
+            ("America/Denver", "Mountain Standard Time (North America)"),
+            ("America/Phoenix", "Mountain Standard Time (Arizona, no daylight saving)"),
+        ],
+    ),
+    ("MDT", &[("America/Denver", "Mountain Daylight Time (North America)")]),
+    ("PT", &[("America/Los_Angeles", "Pacific Time (North America)")]),
+    ("PST", &[("America/Los_Angeles", "Pacific Standard Time (North America)")]),
+    ("PDT", &[("America/Los_Angeles", "Pacific Daylight Time (North America)")]),
+    ("AKST", &[("America/Anchorage", "Alaska Standard Time")]),
+    ("AKDT", &[("America/Anchorage", "Alaska Daylight Time")]),
+    ("HST", &[("Pacific/Honolulu", "Hawaii Standard Time")]),
+    (
+        "AST",
+        &[("America/Halifax", "Atlantic Standard Time"), ("Asia/Riyadh", "Arabia Standard Time")],
+    ),
+    ("ADT", &[("America/Halifax", "Atlantic Daylight Time")]),
+    ("NST", &[("America/St_Johns", "Newfoundland Standard Time")]),
+    ("BRT", &[("America/Sao_Paulo", "Brasília Time")]),
+    ("ART", &[("America/Argentina/Buenos_Aires", "Argentina Time")]),
+    (
+        "SST",
+        &[
+            ("Pacific/Pago_Pago", "Samoa Standard Time"),
+            ("Asia/Singapore", "Singapore Standard Time"),
+        ],
+    ),
+];
+
+/// Countries and their timezones. Countries spanning several timezones list each, described by
+/// the region they cover.
+pub const COUNTRIES: &[(&[&str], &[(&str, &str)])] = &[
+    (
+        &["United Kingdom", "UK", "GB", "Great Britain", "Britain", "England", "Scotland", "Wales"],
+        &[("Europe/London", "United Kingdom")],
+    ),
+    (&["Ireland"], &[("Europe/Dublin", "Ireland")]),
+    (
+        &["Portugal"],
+        &[("Europe/Lisbon", "Portugal (mainland)"), ("Atlantic/Azores", "Portugal (Azores)")],
+    ),
+    (
+        &["Spain"],
+        &[("Europe/Madrid", "Spain (mainland)"), ("Atlantic/Canary", "Spain (Canary Islands)")],
+    ),
+    (&["France"], &[("Europe/Paris", "France")]),
+    (&["Germany"], &[("Europe/Berlin", "Germany")]),
+    (&["Italy"], &[("Europe/Rome", "Italy")]),
+    (&["Netherlands", "Holland"], &[("Europe/Amsterdam", "Netherlands")]),
+    (&["Belgium"], &[("Europe/Brussels", "Belgium")]),
+    (&["Switzerland"], &[("Europe/Zurich", "Switzerland")]),
+    (&["Austria"], &[("Europe/Vienna", "Austria")]),
+    (&["Poland"], &[("Europe/Warsaw", "Poland")]),
+    (&["Czechia", "Czech Republic"], &[("Europe/Prague", "Czechia")]),
+    (&["Hungary"], &[("Europe/Budapest", "Hungary")]),
+    (&["Romania"], &[("Europe/Bucharest", "Romania")]),
+    (&["Denmark"], &[("Europe/Copenhagen", "Denmark")]),
+    (&["Norway"], &[("Europe/Oslo", "Norway")]),
+    (&["Sweden"], &[("Europe/Stockholm", "Sweden")]),
+    (&["Finland"], &[("Europe/Helsinki", "Finland")]),
+    (&["Iceland"], &[("Atlantic/Reykjavik", "Iceland")]),
+    (&["Greece"], &[("Europe/Athens", "Greece")]),
+    (&["Ukraine"], &[("Europe/Kiev", "Ukraine")]),
+    (&["Turkey", "Türkiye"], &[("Europe/Istanbul", "Turkey")]),
+    (
+        &["Russia"],
+        &[
+            ("Europe/Kaliningrad", "Russia (Kaliningrad)"),
+            ("Europe/Moscow", "Russia (Moscow, St Petersburg)"),
+            ("Europe/Samara", "Russia (Samara)"),
+            ("Asia/Yekaterinburg", "Russia (Yekaterinburg)"),
+            ("Asia/Omsk", "Russia (Omsk)"),
+            ("Asia/Novosibirsk", "Russia (Novosibirsk)"),
+            ("Asia/Krasnoyarsk", "Russia (Krasnoyarsk)"),
+            ("Asia/Irkutsk", "Russia (Irkutsk)"),
+            ("Asia/Yakutsk", "Russia (Yakutsk)"),
+            ("Asia/Vladivostok", "Russia (Vladivostok)"),
+            ("Asia/Magadan", "Russia (Magadan)"),
+            ("Asia/Kamchatka", "Russia (Kamchatka)"),
+        ],
+    ),
+    (&["Israel"], &[("Asia/Jerusalem", "Israel")]),
+    (&["Egypt"], &[("Africa/Cairo", "Egypt")]),
+    (&["Morocco"], &[("Africa/Casablanca", "Morocco")]),
+    (&["Nigeria"], &[("Africa/Lagos", "Nigeria")]),
+    (&["Kenya"], &[("Africa/Nairobi", "Kenya")]),
+    (&["South Africa"], &[("Africa/Johannesburg", "South Africa")]),
+    (&["Saudi Arabia"], &[("Asia/Riyadh", "Saudi Arabia")]),
+    (&["United Arab Emirates", "UAE"], &[("Asia/Dubai", "United Arab Emirates")]),
+    (&["Iran"], &[("Asia/Tehran", "Iran")]),
+    (&["Pakistan"], &[("Asia/Karachi", "Pakistan")]),
+    (&["India"], &[("Asia/Kolkata", "India")]),
+    (&["Nepal"], &[("Asia/Kathmandu", "Nepal")]),
+    (&["Bangladesh"], &[("Asia/Dhaka", "Bangladesh")]),
+    (&["Thailand"], &[("Asia/Bangkok", "Thailand")]),
+    (&["Vietnam", "Viet Nam"], &[("Asia/Ho_Chi_Minh", "Vietnam")]),
+    (&["Malaysia"], &[("Asia/Kuala_Lumpur", "Malaysia")]),
+    (&["Singapore"], &[("Asia/Singapore", "Singapore")]),
+    (
+        &["Indonesia"],
+        &[
+            ("Asia/Jakarta", "Indonesia (Western, Jakarta)"),
+            ("Asia/Makassar", "Indonesia (Central, Bali)"),
+            ("Asia/Jayapura", "Indonesia (Eastern, Papua)"),
+        ],
+    ),
+    (&["Philippines"], &[("Asia/Manila", "Philippines")]),
+    (&["China"], &[("Asia/Shanghai", "China")]),
+    (&["Hong Kong"], &[("Asia/Hong_Kong", "Hong Kong")]),
+    (&["Taiwan"], &[("Asia/Taipei", "Taiwan")]),
+    (&["Japan"], &[("Asia/Tokyo", "Japan")]),
+    (&["South Korea", "Korea"], &[("Asia/Seoul", "South Korea")]),
+    (
+        &["Australia"],
+        &[
+            ("Australia/Perth", "Australia (Western Australia)"),
+            ("Australia/Darwin", "Australia (Northern Territory)"),
+            ("Australia/Adelaide", "Australia (South Australia)"),
+            ("Australia/Brisbane", "Australia (Queensland)"),
+            ("Australia/Sydney", "Australia (New South Wales, Victoria, ACT)"),
+            ("Australia/Hobart", "Australia (Tasmania)"),
+        ],
+    ),
+    (&["New Zealand", "NZ"], &[("Pacific/Auckland", "New Zealand")]),
+    (
+        &["United States", "United States of America", "USA", "US", "America"],
+        &[
+            ("America/New_York", "United States (Eastern)"),
+            ("America/Chicago", "United States (Central)"),
+            ("America/Denver", "United States (Mountain)"),
+            ("America/Phoenix", "United States (Arizona)"),
+            ("America/Los_Angeles", "United States (Pacific)"),
+            ("America/Anchorage", "United States (Alaska)"),
+            ("Pacific/Honolulu", "United States (Hawaii)"),
+        ],
+    ),
+    (
+        &["Canada"],
+        &[
+            ("America/St_Johns", "Canada (Newfoundland)"),
+            ("America/Halifax", "Canada (Atlantic)"),
+            ("America/Toronto", "Canada (Eastern)"),
+            ("America/Winnipeg", "Canada (Central)"),
+            ("America/Regina", "Canada (Saskatchewan)"),
+            ("America/Edmonton", "Canada (Mountain)"),
+            ("America/Vancouver", "Canada (Pacific)"),
+        ],
+    ),
+    (
+        &["Mexico"],
+        &[
+            ("America/Mexico_City", "Mexico (Central)"),
+            ("America/Cancun", "Mexico (Quintana Roo)"),
+            ("America/Mazatlan", "Mexico (Pacific)"),
+            ("America/Tijuana", "Mexico (Baja California)"),
+        ],
+    ),
+    (
+        &["Brazil"],
+        &[
+            ("America/Sao_Paulo", "Brazil (Brasília)"),
+            ("America/Manaus", "Brazil (Amazonas)"),
+            ("America/Rio_Branco", "Brazil (Acre)"),
+            ("America/Noronha", "Brazil (Fernando de Noronha)"),
+        ],
+    ),
+    (&["Argentina"], &[("America/Argentina/Buenos_Aires", "Argentina")]),
+    (
+        &["Chile"],
+        &[("America/Santiago", "Chile (mainland)"), ("Pacific/Easter", "Chile (Easter Island)")],
+    ),
+    (&["Colombia"], &[("America/Bogota", "Colombia")]),
+    (&["Peru"], &[("America/Lima", "Peru")]),
+    (&["Venezuela"], &[("America/Caracas", "Venezuela")]),
+];
+
+/// Cities that don't appear in an IANA zone name, and the zone they are in
+pub const CITIES: &[(&str, &str)] = &[
+    ("NYC", "America/New_York"),
+    ("Washington", "America/New_York"),
+    ("Washington DC", "America/New_York"),
+    ("Boston", "America/New_York"),
+    ("Philadelphia", "America/New_York"),
+    ("Atlanta", "America/New_York"),
+    ("Miami", "America/New_York"),
+    ("Ottawa", "America/Toronto"),
+    ("Dallas", "America/Chicago"),
+    ("Houston", "America/Chicago"),
+    ("Austin", "America/Chicago"),
+    ("Minneapolis", "America/Chicago"),
+    ("Salt Lake City", "America/Denver"),
+    ("Calgary", "America/Edmonton"),
+    ("LA", "America/Los_Angeles"),
+    ("San Francisco", "America/Los_Angeles"),
+    ("San Diego", "America/Los_Angeles"),
+    ("San Jose", "America/Los_Angeles"),
+    ("Seattle", "America/Los_Angeles"),
+    ("Portland", "America/Los_Angeles"),
+    ("Las Vegas", "America/Los_Angeles"),
+    ("Rio de Janeiro", "America/Sao_Paulo"),
+    ("Rio", "America/Sao_Paulo"),
+    ("Brasilia", "America/Sao_Paulo"),
+    ("Edinburgh", "Europe/London"),
+    ("Glasgow", "Europe/London"),
+    ("Manchester", "Europe/London"),
+    ("Birmingham", "Europe/London"),
+    ("Liverpool", "Europe/London"),
+    ("Cardiff", "Europe/London"),
+    ("Munich", "Europe/Berlin"),
+    ("Hamburg", "Europe/Berlin"),
+    ("Frankfurt", "Europe/Berlin"),
+    ("Cologne", "Europe/Berlin"),
+    ("Barcelona", "Europe/Madrid"),
+    ("Milan", "Europe/Rome"),
+    ("Geneva", "Europe/Zurich"),
+    ("Krakow", "Europe/Warsaw"),
+    ("St Petersburg", "Europe/Moscow"),
+    ("Saint Petersburg", "Europe/Moscow"),
+    ("Ankara", "Europe/Istanbul"),
+    ("Cape Town", "Africa/Johannesburg"),
+    ("Abu Dhabi", "Asia/Dubai"),
+    ("Mecca", "Asia/Riyadh"),
+    ("Islamabad", "Asia/Karachi"),
+    ("Lahore", "Asia/Karachi"),
+    ("Mumbai", "Asia/Kolkata"),
+    ("Bombay", "Asia/Kolkata"),
+    ("Delhi", "Asia/Kolkata"),
+    ("New Delhi", "Asia/Kolkata"),
+    ("Bangalore", "Asia/Kolkata"),
+    ("Bengaluru", "Asia/Kolkata"),
+    ("Chennai", "Asia/Kolkata"),
+    ("Hyderabad", "Asia/Kolkata"),
+    ("Hanoi", "Asia/Ho_Chi_Minh"),
+    ("Beijing", "Asia/Shanghai"),
+    ("Shenzhen", "Asia/Shanghai"),
+    ("Guangzhou", "Asia/Shanghai"),
+    ("Osaka", "Asia/Tokyo"),
+    ("Kyoto", "Asia/Tokyo"),
+    ("Busan", "Asia/Seoul"),
+    ("Gold Coast", "Australia/Brisbane"),
+    ("Wellington", "Pacific/Auckland"),
+    ("Christchurch", "Pacific/Auckland"),
+];
+
+/// Zones observing each UTC offset that isn't a whole number of hours, keyed by the offset in
+/// minutes. Whole-hour offsets are resolved to the `Etc/GMT` zones instead.
+pub const PARTIAL_OFFSETS: &[(i32, &str)] = &[
+    (-570, "Pacific/Marquesas"),
+    (-210, "America/St_Johns"),
+    (210, "Asia/Tehran"),
+    (270, "Asia/Kabul"),
+    (330, "Asia/Kolkata"),
+    (345, "Asia/Kathmandu"),
+    (390, "Asia/Yangon"),
+    (525, "Australia/Eucla"),
+    (570, "Australia/Darwin"),
+    (630, "Australia/Lord_Howe"),
+    (765, "Pacific/Chatham"),
+];
@@ -0,0 +1,295 @@
+mod data;
+
+use chrono_tz::{Tz, TZ_VARIANTS};
+use lazy_regex::regex;
+
+use crate::timezone_resolver::data::{ABBREVIATIONS, CITIES, COUNTRIES, PARTIAL_OFFSETS};
+
+/// A timezone that some user input could refer to
+#[derive(Debug)]
+pub struct TimezoneMatch {
+    pub timezone: Tz,
+    /// What the input was understood as, e.g. "India Standard Time"
+    pub description: String,
+}
+
+impl TimezoneMatch {
+    /// Label for displaying the match, e.g. "India Standard Time (Asia/Kolkata)"
+    pub fn label(&self) -> String {
+        if self.description == self.timezone.name() {
+            self.description.clone()
+        } else {
+            format!("{} ({})", self.description, self.timezone)
+        }
+    }
+}
+
+struct Entry {
+    keyword: String,
+    timezone: Tz,
+    description: String,
+}
+
+// IANA areas containing only aliases for zones in other areas, rather than locations
+const ALIAS_AREAS: &[&str] = &["Etc", "US", "Canada", "Brazil", "Mexico", "Chile", "SystemV"];
+
+lazy_static! {
+    static ref ENTRIES: Vec<Entry> = {
+        let mut entries = vec![];
+
+        let mut push = |keyword: &str, zone: &str, description: &str| {
+            if let Ok(timezone) = zone.parse() {
+                entries.push(Entry {
+                    keyword: normalize(keyword),
+                    timezone,
+                    description: description.to_string(),
+                });
+            }
+        };
+
+        for (abbreviation, zones) in ABBREVIATIONS {
+            for (zone, description) in zones.iter() {
+                push(abbreviation, zone, description);
+            }
+        }
+
+        for (names, zones) in COUNTRIES {
+            for name in names.iter() {
+                for (zone, description) in zones.iter() {
+                    push(name, zone, description);
+                }
+            }
+        }
+
+        for (city, zone) in CITIES {
+            push(city, zone, city);
+        }
+
+        // Locations named by zones, e.g. "New York" from America/New_York. Where several zones
+        // name the same location, the most specific is kept, as the others are legacy aliases
+        // (e.g. America/Buenos_Aires for America/Argentina/Buenos_Aires)
+        let mut locations: Vec<(String, Tz)> = vec![];
+        for tz in TZ_VARIANTS.iter() {
+            let name = tz.name();
+
+            if let Some((area, path)) = name.split_once('/') {
+                if ALIAS_AREAS.contains(&area) {
+                    continue;
+                }
+
+                let location = path.rsplit('/').next().unwrap().replace('_', " ");
+                match locations.iter_mut().find(|(l, _)| l == &location) {
+                    Some(existing) => {
+                        if name.matches('/').count() > existing.1.name().matches('/').count() {
+                            existing.1 = *tz;
+                        }
+                    }
+
+                    None => locations.push((location, *tz)),
+                }
+            }
+        }
+
+        for (location, tz) in locations {
+            push(&location, tz.name(), &location);
+        }
+
+        entries
+    };
+}
+
+fn normalize(input: &str) -> String {
+    input.replace('_', " ").split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+/// Parse a fixed UTC offset such as "GMT+2", "UTC-05:00" or "+0530"
+fn parse_offset(input: &str) -> Option<TimezoneMatch> {
+    let caps =
+        regex!(r"^(?i:utc|gmt)?\s*([+-])\s*(\d{1,2})(?::?(\d{2}))?$").captures(input.trim())?;
+
+    let hours = caps[2].parse::<i32>().ok()?;
+    let minutes = caps.get(3).map_or(Some(0), |m| m.as_str().parse::<i32>().ok())?;
+    let sign = if &caps[1] == "-" { -1 } else { 1 };
+
+    if minutes >= 60 || !(-12..=14).contains(&(sign * hours)) {
+        return None;
+    }
+
+    let offset = sign * (hours * 60 + minutes);
+    let display = format!("UTC{}{:02}:{:02}", &caps[1], hours, minutes);
+
+    if minutes == 0 {
+        // Etc/GMT zones use POSIX signs, so are inverted: UTC+2 is Etc/GMT-2
+        let zone = if hours == 0 {
+            "Etc/GMT".to_string()
+        } else {
+            format!("Etc/GMT{}{}", if sign > 0 { '-' } else { '+' }, hours)
+        };
+
+        Some(TimezoneMatch {
+            timezone: zone.parse().ok()?,
+            description: format!("Fixed offset {}", display),
+        })
+    } else {
+        let (_, zone) = PARTIAL_OFFSETS.iter().find(|(o, _)| *o == offset)?;
+
+        Some(TimezoneMatch {
+            timezone: zone.parse().ok()?,
+            description: format!("{}, as observed in {}", display, zone),
+        })
+    }
+}
+
+fn collect_matches<'a>(entries: impl Iterator<Item = &'a Entry>) -> Vec<TimezoneMatch> {
+    let mut matches: Vec<TimezoneMatch> = vec![];
+
+    for entry in entries {
+        if !matches.iter().any(|m| m.timezone == entry.timezone) {
+            matches.push(TimezoneMatch {
+                timezone: entry.timezone,
+                description: entry.description.clone(),
+            });
+        }
+    }
+
+    matches
+}
+
+/// Resolve an IANA name, city, country, abbreviation or UTC offset to the timezones it could
+/// refer to. Returns one match if the input is unambiguous, several if it is ambiguous (e.g.
+/// "IST"), or none if it isn't recognized.
+pub fn resolve(input: &str) -> Vec<TimezoneMatch> {
+    let normalized = normalize(input);
+
+    if normalized.is_empty() {
+        return vec![];
+    }
+
+    // Legacy zones without an area (e.g. "EST", "Japan") are only used if nothing else matches,
+    // since an abbreviation or country is usually meant
+    let (zones, legacy_zones): (Vec<&Tz>, Vec<&Tz>) = TZ_VARIANTS
+        .iter()
+        .filter(|tz| normalize(tz.name()) == normalized)
+        .partition(|tz| tz.name().contains('/'));
+
+    if let Some(tz) = zones.first() {
+        return vec![TimezoneMatch { timezone: **tz, description: tz.name().to_string() }];
+    }
+
+    if let Some(offset) = parse_offset(input) {
+        return vec![offset];
+    }
+
+    let matches = collect_matches(ENTRIES.iter().filter(|e| e.keyword == normalized));
+
+    if matches.is_empty() {
+        legacy_zones
+            .first()
+            .map(|tz| TimezoneMatch { timezone: **tz, description: tz.name().to_string() })
+            .into_iter()
+            .collect()
+    } else {
+        matches
+    }
+}
+
+/// Suggest timezones for partially typed input, for autocompletion
+pub fn suggest(partial: &str) -> Vec<TimezoneMatch> {
+    let normalized = normalize(partial);
+
+    if normalized.is_empty() {
+        return vec![];
+    }
+
+    if let Some(offset) = parse_offset(partial) {
+        return vec![offset];
+    }
+
+    collect_matches(ENTRIES.iter().filter(|e| e.keyword.starts_with(&normalized)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Tz;
+
+    use super::*;
+
+    fn resolved(input: &str) -> Vec<Tz> {
+        resolve(input).into_iter().map(|m| m.timezone).collect()
+    }
+
+    #[test]
+    fn dataset_is_valid() {
+        let zones = ABBREVIATIONS
+            .iter()
+            .flat_map(|(_, zones)| zones.iter().map(|(zone, _)| *zone))
+            .chain(COUNTRIES.iter().flat_map(|(_, zones)| zones.iter().map(|(zone, _)| *zone)))
+            .chain(CITIES.iter().map(|(_, zone)| *zone))
+            .chain(PARTIAL_OFFSETS.iter().map(|(_, zone)| *zone));
+
+        for zone in zones {
+            assert!(zone.parse::<Tz>().is_ok(), "{} is not a valid zone", zone);
+        }
+    }
+
+    #[test]
+    fn cities_are_unambiguous() {
+        for (city, zone) in CITIES {
+            assert_eq!(resolved(city), vec![zone.parse::<Tz>().unwrap()], "{}", city);
+        }
+    }
+
+    #[test]
+    fn resolve_iana() {
+        assert_eq!(resolved("Europe/London"), vec![Tz::Europe__London]);
+        assert_eq!(resolved("america/new_york"), vec![Tz::America__New_York]);
+    }
+
+    #[test]
+    fn resolve_city() {
+        assert_eq!(resolved("London"), vec![Tz::Europe__London]);
+        assert_eq!(resolved("sydney"), vec![Tz::Australia__Sydney]);
+        assert_eq!(resolved("New York"), vec![Tz::America__New_York]);
+        assert_eq!(resolved("Mumbai"), vec![Tz::Asia__Kolkata]);
+        assert_eq!(resolved("Buenos Aires"), vec![Tz::America__Argentina__Buenos_Aires]);
+    }
+
+    #[test]
+    fn resolve_country() {
+        assert_eq!(resolved("Japan"), vec![Tz::Asia__Tokyo]);
+        assert_eq!(resolved("Germany"), vec![Tz::Europe__Berlin]);
+        assert!(resolved("Australia").len() > 1);
+    }
+
+    #[test]
+    fn resolve_abbreviation() {
+        assert_eq!(resolved("EST"), vec![Tz::America__New_York]);
+        assert_eq!(resolved("utc"), vec![Tz::UTC]);
+        assert_eq!(
+            resolved("IST"),
+            vec![Tz::Asia__Kolkata, Tz::Asia__Jerusalem, Tz::Europe__Dublin]
+        );
+    }
+
+    #[test]
+    fn resolve_offset() {
+        assert_eq!(resolved("GMT+2"), vec![Tz::Etc__GMTMinus2]);
+        assert_eq!(resolved("UTC-5"), vec![Tz::Etc__GMTPlus5]);
+        assert_eq!(resolved("+05:30"), vec![Tz::Asia__Kolkata]);
+        assert_eq!(resolved("UTC+0"), vec![Tz::Etc__GMT]);
+        assert!(resolved("UTC+15").is_empty());
+        assert!(resolved("+03:17").is_empty());
+    }
+
+    #[test]
+    fn resolve_unknown() {
+        assert!(resolved("Atlantis").is_empty());
+        assert!(resolved("").is_empty());
+    }
+
+    #[test]
+    fn suggest_partial() {
+        assert!(suggest("Lond").iter().any(|m| m.timezone == Tz::Europe__London));
+        assert!(suggest("ist").iter().any(|m| m.timezone == Tz::Asia__Kolkata));
+    }
+}
@@ -3,15 +3,10 @@ pub const DISCORD_OAUTH_AUTHORIZE: &'static str = "https://discord.com/api/oauth
 pub const DISCORD_API: &'static str = "https://discord.com/api";
 
 pub const MAX_CONTENT_LENGTH: usize = 2000;
-pub const MAX_EMBED_DESCRIPTION_LENGTH: usize = 4096;
-pub const MAX_EMBED_TITLE_LENGTH: usize = 256;
-pub const MAX_EMBED_AUTHOR_LENGTH: usize = 256;
-pub const MAX_EMBED_FOOTER_LENGTH: usize = 2048;
 pub const MAX_URL_LENGTH: usize = 512;
 pub const MAX_USERNAME_LENGTH: usize = 100;
-pub const MAX_EMBED_FIELDS: usize = 25;
-pub const MAX_EMBED_FIELD_TITLE_LENGTH: usize = 256;
-pub const MAX_EMBED_FIELD_VALUE_LENGTH: usize = 1024;
+pub const MAX_MACRO_NAME_LENGTH: usize = 100;
+pub const MAX_MACRO_DESCRIPTION_LENGTH: usize = 100;
 
 pub const MINUTE: usize = 60;
 pub const HOUR: usize = 60 * MINUTE;
@@ -19,9 +14,29 @@ pub const DAY: usize = 24 * HOUR;
 
 pub const CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
 
+pub const INTERVAL_POLICIES: [&str; 2] = ["wall_clock", "elapsed"];
+pub const BULK_ACTIONS: [&str; 4] = ["enable", "disable", "delete", "offset"];
+pub const BUSINESS_DAY_POLICIES: [&str; 4] = ["ignore", "skip", "next", "previous"];
+
+pub const WEEKDAYS: [&str; 7] =
+    ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+pub const DEFAULT_WEEKEND_DAYS: u8 = 0b1100000;
+pub const MAX_HOLIDAYS: usize = 1000;
+pub const MAX_HOLIDAY_NAME_LENGTH: usize = 100;
+pub const MAX_FEED_EVENTS: usize = 5000;
+pub const MAX_AUDIT_ENTRIES: u32 = 50;
+
 use std::{collections::HashSet, env, iter::FromIterator};
 
 use lazy_static::lazy_static;
+pub use postman::{
+    consts::{
+        EMBED_AUTHOR_MAX_LENGTH, EMBED_DESCRIPTION_MAX_LENGTH, EMBED_FIELD_TITLE_MAX_LENGTH,
+        EMBED_FIELD_VALUE_MAX_LENGTH, EMBED_FOOTER_MAX_LENGTH, EMBED_MAX_FIELDS,
+        EMBED_TITLE_MAX_LENGTH, MACRO_MAX_COMMANDS,
+    },
+    names::MAX_NAME_LENGTH,
+};
 use serenity::model::prelude::AttachmentType;
 
 lazy_static! {
@@ -45,4 +60,9 @@ lazy_static! {
         .map(|inner| inner.parse::<u32>().ok())
         .flatten()
         .unwrap_or(600);
+    pub static ref FEED_HORIZON_DAYS: i64 = env::var("FEED_HORIZON_DAYS")
+        .ok()
+        .map(|inner| inner.parse::<i64>().ok())
+        .flatten()
+        .unwrap_or(90);
 }
@@ -0,0 +1,354 @@
+//! Reading and writing of iCalendar (RFC 5545) files, for exchanging reminders and holidays with
+//! calendar apps
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use postman::recurrence::resolve_local;
+
+/// Years of offset changes written into a VTIMEZONE, from the first time that uses it
+const TIMEZONE_YEARS: i64 = 20;
+
+/// A UTC offset in seconds, whether it is daylight saving time, and the offset's abbreviation
+type Observance = (i32, bool, String);
+
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn new(name: &str, value: impl Into<String>) -> Self {
+        Property { name: name.to_string(), params: vec![], value: value.into() }
+    }
+
+    pub fn with_param(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.params.push((name.to_string(), value.into()));
+
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    pub fn new(name: &str) -> Self {
+        Component { name: name.to_string(), properties: vec![], components: vec![] }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Nested components of a type, e.g. the VEVENTs of a VCALENDAR
+    pub fn components<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Component> {
+        self.components.iter().filter(move |c| c.name == name)
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = self.name.clone();
+
+        for (name, value) in &self.params {
+            if value.contains([':', ';', ',']) {
+                line.push_str(&format!(";{}=\"{}\"", name, value.replace('"', "")));
+            } else {
+                line.push_str(&format!(";{}={}", name, value));
+            }
+        }
+
+        line.push(':');
+        line.push_str(&self.value);
+
+        // Lines longer than 75 octets are folded onto continuation lines
+        let mut length = 0;
+        for c in line.chars() {
+            if length + c.len_utf8() > 75 {
+                f.write_str("\r\n ")?;
+                length = 1;
+            }
+
+            length += c.len_utf8();
+            write!(f, "{}", c)?;
+        }
+
+        f.write_str("\r\n")
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Property::new("BEGIN", self.name.as_str()))?;
+
+        for property in &self.properties {
+            write!(f, "{}", property)?;
+        }
+        for component in &self.components {
+            write!(f, "{}", component)?;
+        }
+
+        write!(f, "{}", Property::new("END", self.name.as_str()))
+    }
+}
+
+/// Join lines that have been folded onto several lines
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for line in input.lines() {
+        match line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')) {
+            Some(continuation) if !lines.is_empty() => {
+                lines.last_mut().unwrap().push_str(continuation);
+            }
+
+            _ => {
+                if !line.trim().is_empty() {
+                    lines.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// Parse a content line, e.g. `DTSTART;TZID=Europe/London:20231225T090000`
+fn parse_line(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let mut split = None;
+
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => {
+                split = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let (head, value) = line.split_at(split?);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(name, value)| (name.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property { name, params, value: value[1..].to_string() })
+}
+
+/// Parse an iCalendar file, returning the outermost component (normally a VCALENDAR)
+pub fn parse(input: &str) -> Result<Component, String> {
+    let mut stack: Vec<Component> = vec![];
+
+    for (index, line) in unfold(input).iter().enumerate() {
+        let property = parse_line(line).ok_or(format!("Line {} is not valid", index + 1))?;
+
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.to_uppercase(),
+                properties: vec![],
+                components: vec![],
+            }),
+
+            "END" => {
+                let component =
+                    stack.pop().ok_or(format!("Unexpected END on line {}", index + 1))?;
+
+                if !component.name.eq_ignore_ascii_case(&property.value) {
+                    return Err(format!(
+                        "{} ended by END:{} on line {}",
+                        component.name,
+                        property.value,
+                        index + 1
+                    ));
+                }
+
+                match stack.last_mut() {
+                    Some(parent) => parent.components.push(component),
+                    None => return Ok(component),
+                }
+            }
+
+            _ => match stack.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => return Err(format!("Line {} is outside of a calendar", index + 1)),
+            },
+        }
+    }
+
+    Err("Calendar is incomplete".to_string())
+}
+
+/// Undo the escaping of a TEXT value
+pub fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => text.push('\n'),
+                Some(escaped) => text.push(escaped),
+                None => {}
+            }
+        } else {
+            text.push(c);
+        }
+    }
+
+    text
+}
+
+/// Escape a TEXT value
+pub fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Format a UTC DATE-TIME value, e.g. `20231225T090000Z`
+pub fn format_utc(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Format a local DATE-TIME value, to be given alongside a TZID
+pub fn format_local(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Parse a DATE or DATE-TIME property into an instant. Times without a timezone, and dates, are
+/// taken to be in `default`. `None` if the value or its TZID aren't understood
+pub fn parse_datetime(property: &Property, default: Tz) -> Option<DateTime<Utc>> {
+    let timezone = match property.param("TZID") {
+        Some(tzid) => tzid.trim_start_matches('/').parse::<Tz>().ok()?,
+
+        None => default,
+    };
+
+    let local = if property.param("VALUE").map_or(false, |v| v.eq_ignore_ascii_case("DATE"))
+        || property.value.len() == 8
+    {
+        parse_date(&property.value)?.and_hms_opt(0, 0, 0)?
+    } else if let Some(utc) = property.value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(|time| DateTime::from_utc(time, Utc));
+    } else {
+        NaiveDateTime::parse_from_str(&property.value, "%Y%m%dT%H%M%S").ok()?
+    };
+
+    Some(resolve_local(&timezone, local).with_timezone(&Utc))
+}
+
+/// Parse the date of a DATE or DATE-TIME value
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Format a UTC offset, e.g. `+0100` or `-0330`
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+
+    if seconds % 60 == 0 {
+        format!("{}{:02}{:02}", sign, seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{}{:02}{:02}{:02}", sign, seconds / 3600, seconds % 3600 / 60, seconds % 60)
+    }
+}
+
+/// A VTIMEZONE defining `timezone` from `start` (UTC) onwards, so that times given with its TZID
+/// can be read by calendar apps that don't share the timezone database. Each change of offset is
+/// found from the database and listed under the observance (STANDARD or DAYLIGHT) that it starts
+pub fn vtimezone(timezone: Tz, start: NaiveDateTime) -> Component {
+    let offset_at = |time: NaiveDateTime| -> Observance {
+        let offset = timezone.offset_from_utc_datetime(&time);
+
+        (
+            offset.fix().local_minus_utc(),
+            offset.dst_offset() != Duration::zero(),
+            offset.abbreviation().to_string(),
+        )
+    };
+
+    let end = start + Duration::days(365 * TIMEZONE_YEARS);
+    let mut current = offset_at(start);
+    // the offset in use at `start` is given as an observance that changes nothing
+    let mut changes = vec![(start, current.0, current.clone())];
+
+    let mut time = start;
+    while time < end {
+        let next = time + Duration::days(1);
+        let offset = offset_at(next);
+
+        if offset != current {
+            let (mut before, mut after) = (time, next);
+            while after - before > Duration::seconds(1) {
+                let middle = before + (after - before) / 2;
+
+                if offset_at(middle) == current {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+
+            changes.push((after, current.0, offset.clone()));
+            current = offset;
+        }
+
+        time = next;
+    }
+
+    // changes between the same offsets share one observance, with the later ones as RDATEs
+    let mut observances: Vec<(i32, Observance, Vec<NaiveDateTime>)> = vec![];
+    for (time, from, to) in changes {
+        // onsets are given in the local time that was in use before the change
+        let onset = time + Duration::seconds(from as i64);
+
+        match observances.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+            Some((_, _, onsets)) => onsets.push(onset),
+
+            None => observances.push((from, to, vec![onset])),
+        }
+    }
+
+    let mut component = Component::new("VTIMEZONE");
+    component.properties.push(Property::new("TZID", timezone.name()));
+
+    for (from, (to, dst, name), onsets) in observances {
+        let mut observance = Component::new(if dst { "DAYLIGHT" } else { "STANDARD" });
+
+        observance.properties.push(Property::new("DTSTART", format_local(onsets[0])));
+        if onsets.len() > 1 {
+            observance.properties.push(Property::new(
+                "RDATE",
+                onsets[1..].iter().map(|onset| format_local(*onset)).collect::<Vec<_>>().join(","),
+            ));
+        }
+        observance.properties.push(Property::new("TZOFFSETFROM", format_offset(from)));
+        observance.properties.push(Property::new("TZOFFSETTO", format_offset(to)));
+        observance.properties.push(Property::new("TZNAME", name));
+
+        component.components.push(observance);
+    }
+
+    component
+}
@@ -2,6 +2,7 @@
 extern crate rocket;
 
 mod consts;
+mod ics;
 #[macro_use]
 mod macros;
 mod routes;
@@ -18,7 +19,10 @@ use rocket_dyn_templates::Template;
 use serenity::{
     client::Context,
     http::CacheHttp,
-    model::id::{GuildId, UserId},
+    model::{
+        application::command::CommandOptionType,
+        id::{GuildId, UserId},
+    },
 };
 use sqlx::{MySql, Pool};
 
@@ -32,6 +36,24 @@ enum Error {
     Serenity(serenity::Error),
 }
 
+/// A slash command that can be used as a step in a macro, as registered by the bot
+pub struct MacroCommand {
+    pub qualified_name: String,
+    pub parameters: Vec<MacroCommandParameter>,
+}
+
+pub struct MacroCommandParameter {
+    pub name: String,
+    pub required: bool,
+    /// The type of value the option takes, if known
+    pub kind: Option<CommandOptionType>,
+    /// Names of the choices the option is limited to. Choices are sent as their index
+    pub choices: Vec<String>,
+}
+
+/// Commands available to macros, keyed by their identifying name
+pub type MacroCommands = HashMap<String, MacroCommand>;
+
 #[catch(401)]
 async fn not_authorized() -> Template {
     let map: HashMap<String, String> = HashMap::new();
@@ -70,6 +92,7 @@ pub async fn initialize(
     kill_channel: Sender<()>,
     serenity_context: Context,
     db_pool: Pool<Database>,
+    macro_commands: MacroCommands,
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Checking environment variables...");
     env::var("OAUTH2_CLIENT_ID").expect("`OAUTH2_CLIENT_ID' not supplied");
@@ -105,6 +128,7 @@ pub async fn initialize(
         .manage(reqwest_client)
         .manage(serenity_context)
         .manage(db_pool)
+        .manage(macro_commands)
         .mount("/static", FileServer::from(concat!(env!("CARGO_MANIFEST_DIR"), "/static")))
         .mount(
             "/",
@@ -132,6 +156,7 @@ pub async fn initialize(
             ],
         )
         .mount("/login", routes![routes::login::discord_login, routes::login::discord_callback])
+        .mount("/feed", routes![routes::feed::calendar_feed])
         .mount(
             "/dashboard",
             routes![
@@ -143,6 +168,7 @@ pub async fn initialize(
                 routes::dashboard::guild::get_guild_patreon,
                 routes::dashboard::guild::get_guild_channels,
                 routes::dashboard::guild::get_guild_roles,
+                routes::dashboard::guild::get_guild_timezone,
                 routes::dashboard::guild::get_reminder_templates,
                 routes::dashboard::guild::create_reminder_template,
                 routes::dashboard::guild::delete_reminder_template,
@@ -150,11 +176,33 @@ pub async fn initialize(
                 routes::dashboard::guild::get_reminders,
                 routes::dashboard::guild::edit_reminder,
                 routes::dashboard::guild::delete_reminder,
+                routes::dashboard::guild::bulk_reminders,
+                routes::dashboard::guild::send_reminder,
+                routes::dashboard::command_macro::get_macro_commands,
+                routes::dashboard::command_macro::get_macros,
+                routes::dashboard::command_macro::create_macro,
+                routes::dashboard::command_macro::edit_macro,
+                routes::dashboard::command_macro::delete_macro,
                 routes::dashboard::export::export_reminders,
                 routes::dashboard::export::export_reminder_templates,
                 routes::dashboard::export::export_todos,
                 routes::dashboard::export::import_reminders,
+                routes::dashboard::export::export_reminders_ics,
+                routes::dashboard::export::import_reminders_ics,
                 routes::dashboard::export::import_todos,
+                routes::dashboard::audit::get_audit,
+                routes::dashboard::backup::export_backup,
+                routes::dashboard::backup::restore_backup,
+                routes::dashboard::calendar::get_calendar,
+                routes::dashboard::calendar::edit_calendar,
+                routes::dashboard::calendar::import_holidays,
+                routes::dashboard::calendar::delete_holidays,
+                routes::dashboard::feed::get_guild_feed,
+                routes::dashboard::feed::regenerate_guild_feed,
+                routes::dashboard::feed::revoke_guild_feed,
+                routes::dashboard::feed::get_user_feed,
+                routes::dashboard::feed::regenerate_user_feed,
+                routes::dashboard::feed::revoke_user_feed,
             ],
         )
         .launch()
@@ -0,0 +1,86 @@
+use rocket::{http::CookieJar, serde::json::json, State};
+use serenity::client::Context;
+use sqlx::{MySql, Pool};
+
+use crate::{consts::MAX_AUDIT_ENTRIES, routes::dashboard::JsonResult};
+
+#[derive(FromForm)]
+pub struct AuditFilter {
+    user: Option<u64>,
+    reminder: Option<String>,
+    action: Option<String>,
+    source: Option<String>,
+    before: Option<u32>,
+}
+
+/// Entries are returned newest first. Pass the `next` value of a response as `before` to get the
+/// following page
+#[get("/api/guild/<id>/audit?<filter..>")]
+pub async fn get_audit(
+    id: u64,
+    filter: AuditFilter,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "SELECT id, reminder_uid, reminder_name, actor, source, action, changes, created_at
+        FROM reminder_audit
+        WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+        AND (? IS NULL OR actor = ?)
+        AND (? IS NULL OR reminder_uid = ? OR reminder_name = ?)
+        AND (? IS NULL OR action = ?)
+        AND (? IS NULL OR source = ?)
+        AND (? IS NULL OR id < ?)
+        ORDER BY id DESC
+        LIMIT ?",
+        id,
+        filter.user,
+        filter.user,
+        filter.reminder,
+        filter.reminder,
+        filter.reminder,
+        filter.action,
+        filter.action,
+        filter.source,
+        filter.source,
+        filter.before,
+        filter.before,
+        MAX_AUDIT_ENTRIES
+    )
+    .fetch_all(pool.inner())
+    .await
+    {
+        Ok(rows) => {
+            let next = if rows.len() as u32 == MAX_AUDIT_ENTRIES {
+                rows.last().map(|row| row.id)
+            } else {
+                None
+            };
+
+            Ok(json!({
+                "entries": rows
+                    .into_iter()
+                    .map(|row| json!({
+                        "reminder_uid": row.reminder_uid,
+                        "reminder_name": row.reminder_name,
+                        "actor": row.actor.to_string(),
+                        "source": row.source,
+                        "action": row.action,
+                        "changes": row.changes,
+                        "created_at": row.created_at,
+                    }))
+                    .collect::<Vec<_>>(),
+                "next": next,
+            }))
+        }
+
+        Err(e) => {
+            warn!("Could not fetch audit entries for {}: {:?}", id, e);
+
+            json_err!("Could not load audit log")
+        }
+    }
+}
@@ -0,0 +1,697 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use postman::{
+    audit::AuditSource,
+    guild_log::{self, LogEvent},
+};
+use rocket::{
+    http::CookieJar,
+    serde::json::{json, serde_json, Json, Value as JsonValue},
+    State,
+};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::Context,
+    model::{
+        channel::GuildChannel,
+        id::{ChannelId, GuildId, UserId},
+    },
+};
+use sqlx::{MySql, Pool};
+
+use crate::{
+    routes::dashboard::{
+        command_macro::{validate_steps, MacroStep},
+        create_reminder, JsonResult, Reminder, ReminderTemplate,
+    },
+    MacroCommands,
+};
+
+/// Version of the backup document. Increase when making changes that older restores can't read
+const BACKUP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct GuildBackup {
+    version: u32,
+    guild: String,
+    #[serde(default)]
+    settings: Option<GuildSettings>,
+    #[serde(default)]
+    user: Option<UserSettings>,
+    #[serde(default)]
+    channels: Vec<ChannelSettings>,
+    #[serde(default)]
+    reminders: Vec<Reminder>,
+    #[serde(default)]
+    templates: Vec<ReminderTemplate>,
+    #[serde(default)]
+    todos: Vec<TodoBackup>,
+    #[serde(default)]
+    macros: Vec<MacroBackup>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GuildSettings {
+    timezone: Option<String>,
+    weekend_days: u8,
+    #[serde(default)]
+    holidays: Vec<HolidayBackup>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HolidayBackup {
+    date: NaiveDate,
+    name: String,
+}
+
+/// Settings of the user who made the backup. Restored onto the user restoring it
+#[derive(Serialize, Deserialize)]
+pub struct UserSettings {
+    timezone: Option<String>,
+    allowed_dm: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChannelSettings {
+    id: String,
+    name: String,
+    nudge: i16,
+    blacklisted: bool,
+    paused: bool,
+    paused_until: Option<DateTime<Utc>>,
+    timezone: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TodoBackup {
+    value: String,
+    channel: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MacroBackup {
+    name: String,
+    description: Option<String>,
+    abort_on_failure: bool,
+    commands: JsonValue,
+}
+
+#[get("/api/guild/<id>/backup")]
+pub async fn export_backup(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+    macro_commands: &State<MacroCommands>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+
+    let guild_channels = match GuildId(id).channels(&ctx.inner()).await {
+        Ok(channels) => channels,
+
+        Err(e) => {
+            warn!("Could not fetch channels from {}: {:?}", id, e);
+
+            return json_err!("Failed to get guild channels");
+        }
+    };
+    let channel_list =
+        guild_channels.keys().map(|k| k.as_u64().to_string()).collect::<Vec<String>>().join(",");
+
+    let result: Result<GuildBackup, sqlx::Error> = async {
+        let settings =
+            sqlx::query!("SELECT timezone, weekend_days FROM guilds WHERE guild = ?", id)
+                .fetch_optional(pool.inner())
+                .await?;
+
+        let holidays = sqlx::query_as!(
+            HolidayBackup,
+            "SELECT `date`, name FROM holidays
+            WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+            id
+        )
+        .fetch_all(pool.inner())
+        .await?;
+
+        let user = sqlx::query_as!(
+            UserSettings,
+            "SELECT timezone, allowed_dm AS \"allowed_dm: bool\" FROM users WHERE user = ?",
+            user_id
+        )
+        .fetch_optional(pool.inner())
+        .await?;
+
+        let channels = sqlx::query!(
+            "SELECT
+             channel,
+             name,
+             nudge,
+             blacklisted AS \"blacklisted: bool\",
+             paused AS \"paused: bool\",
+             paused_until,
+             timezone
+            FROM channels
+            WHERE FIND_IN_SET(channel, ?)",
+            channel_list
+        )
+        .fetch_all(pool.inner())
+        .await?
+        .into_iter()
+        .map(|row| ChannelSettings {
+            id: row.channel.to_string(),
+            // Prefer the channel's current name, as names are only stored when a channel is
+            // first used
+            name: guild_channels
+                .get(&ChannelId(row.channel))
+                .map(|c| c.name.clone())
+                .or(row.name)
+                .unwrap_or_default(),
+            nudge: row.nudge,
+            blacklisted: row.blacklisted,
+            paused: row.paused,
+            paused_until: row.paused_until,
+            timezone: row.timezone,
+        })
+        .collect();
+
+        let reminders = sqlx::query_as_unchecked!(
+            Reminder,
+            "SELECT
+             reminders.attachment,
+             reminders.attachment_name,
+             reminders.avatar,
+             reminders.business_day_policy,
+             channels.channel,
+             reminders.content,
+             reminders.embed_author,
+             reminders.embed_author_url,
+             reminders.embed_color,
+             reminders.embed_description,
+             reminders.embed_footer,
+             reminders.embed_footer_url,
+             reminders.embed_image_url,
+             reminders.embed_thumbnail_url,
+             reminders.embed_title,
+             reminders.embed_fields,
+             reminders.enabled,
+             reminders.expires,
+             reminders.interval_seconds,
+             reminders.interval_days,
+             reminders.interval_months,
+             reminders.interval_policy,
+             reminders.name,
+             reminders.restartable,
+             reminders.timezone,
+             reminders.tts,
+             reminders.uid,
+             reminders.username,
+             reminders.utc_time
+            FROM reminders
+            LEFT JOIN channels ON channels.id = reminders.channel_id
+            WHERE FIND_IN_SET(channels.channel, ?)",
+            channel_list
+        )
+        .fetch_all(pool.inner())
+        .await?;
+
+        let templates = sqlx::query_as_unchecked!(
+            ReminderTemplate,
+            "SELECT * FROM reminder_template
+            WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+            id
+        )
+        .fetch_all(pool.inner())
+        .await?;
+
+        let todos = sqlx::query!(
+            "SELECT todos.value, channels.channel FROM todos
+            LEFT JOIN channels ON todos.channel_id = channels.id
+            WHERE todos.guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+            id
+        )
+        .fetch_all(pool.inner())
+        .await?
+        .into_iter()
+        .map(|row| TodoBackup { value: row.value, channel: row.channel.map(|c| c.to_string()) })
+        .collect();
+
+        let macros = sqlx::query!(
+            "SELECT name, description, abort_on_failure AS \"abort_on_failure: bool\", commands
+            FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+            id
+        )
+        .fetch_all(pool.inner())
+        .await?
+        .into_iter()
+        .map(|row| MacroBackup {
+            name: row.name,
+            description: row.description,
+            abort_on_failure: row.abort_on_failure,
+            commands: serde_json::from_str(&row.commands).unwrap_or(JsonValue::Null),
+        })
+        .collect();
+
+        Ok(GuildBackup {
+            version: BACKUP_VERSION,
+            guild: id.to_string(),
+            settings: settings.map(|row| GuildSettings {
+                timezone: row.timezone,
+                weekend_days: row.weekend_days,
+                holidays,
+            }),
+            user,
+            channels,
+            reminders,
+            templates,
+            todos,
+            macros,
+        })
+    }
+    .await;
+
+    match result {
+        Ok(backup) => Ok(json!(backup)),
+
+        Err(e) => {
+            warn!("Could not create backup of {}: {:?}", id, e);
+
+            json_err!("Could not create backup")
+        }
+    }
+}
+
+/// Find the channel of this guild that each channel of a backup corresponds to. Channels are
+/// matched by ID when restoring onto the same guild, and by name otherwise
+fn map_channels(
+    backup: &[ChannelSettings],
+    guild_channels: &HashMap<ChannelId, GuildChannel>,
+    errors: &mut Vec<String>,
+) -> HashMap<String, u64> {
+    let mut mapping = HashMap::new();
+
+    for channel in backup {
+        if let Some(id) =
+            channel.id.parse::<u64>().ok().filter(|id| guild_channels.contains_key(&ChannelId(*id)))
+        {
+            mapping.insert(channel.id.clone(), id);
+            continue;
+        }
+
+        let matches = guild_channels
+            .values()
+            .filter(|c| c.name == channel.name)
+            .map(|c| c.id.as_u64())
+            .collect::<Vec<_>>();
+
+        match matches.as_slice() {
+            [id] => {
+                mapping.insert(channel.id.clone(), *id);
+            }
+
+            [] => errors.push(format!("No channel named #{} was found", channel.name)),
+
+            _ => errors.push(format!(
+                "Several channels are named #{}, so none were restored to",
+                channel.name
+            )),
+        }
+    }
+
+    mapping
+}
+
+/// Restore a backup onto a guild, alongside its existing data. Anything that can't be restored is
+/// reported, and the rest is restored
+#[put("/api/guild/<id>/backup", data = "<backup>")]
+pub async fn restore_backup(
+    id: u64,
+    backup: Json<GuildBackup>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+    let backup = backup.into_inner();
+
+    if backup.version > BACKUP_VERSION {
+        return json_err!("This backup was made by a newer version of Reminder Bot");
+    }
+
+    let guild_channels = match GuildId(id).channels(&ctx.inner()).await {
+        Ok(channels) => channels,
+
+        Err(e) => {
+            warn!("Could not fetch channels from {}: {:?}", id, e);
+
+            return json_err!("Failed to get guild channels");
+        }
+    };
+
+    let mut errors = vec![];
+    let mapping = map_channels(&backup.channels, &guild_channels, &mut errors);
+
+    let mut transaction = match pool.inner().begin().await {
+        Ok(transaction) => transaction,
+
+        Err(e) => {
+            warn!("Could not start transaction: {:?}", e);
+
+            return json_err!("Could not restore backup");
+        }
+    };
+
+    let mut restored: HashMap<&str, usize> = HashMap::new();
+
+    let result: Result<(), sqlx::Error> = async {
+        sqlx::query!(
+            "INSERT INTO guilds (guild) VALUES (?) ON DUPLICATE KEY UPDATE guild = guild",
+            id
+        )
+        .execute(&mut transaction)
+        .await?;
+
+        if let Some(settings) = &backup.settings {
+            let timezone = settings.timezone.as_ref().filter(|tz| {
+                let valid = tz.parse::<Tz>().is_ok();
+                if !valid {
+                    errors.push(format!("Timezone {} isn't recognised", tz));
+                }
+
+                valid
+            });
+
+            sqlx::query!(
+                "UPDATE guilds SET timezone = ?, weekend_days = ? WHERE guild = ?",
+                timezone,
+                settings.weekend_days,
+                id
+            )
+            .execute(&mut transaction)
+            .await?;
+
+            for holiday in &settings.holidays {
+                sqlx::query!(
+                    "INSERT INTO holidays (guild_id, `date`, name)
+                    VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?)
+                    ON DUPLICATE KEY UPDATE name = ?",
+                    id,
+                    holiday.date,
+                    holiday.name,
+                    holiday.name
+                )
+                .execute(&mut transaction)
+                .await?;
+            }
+        }
+
+        for channel in &backup.channels {
+            if let Some(target) = mapping.get(&channel.id) {
+                let name = guild_channels.get(&ChannelId(*target)).map(|c| c.name.clone());
+                let timezone = channel.timezone.as_ref().filter(|tz| {
+                    let valid = tz.parse::<Tz>().is_ok();
+                    if !valid {
+                        errors.push(format!("Timezone {} isn't recognised", tz));
+                    }
+
+                    valid
+                });
+
+                sqlx::query!(
+                    "INSERT INTO channels
+                    (channel, name, guild_id, nudge, blacklisted, paused, paused_until, timezone)
+                    VALUES (?, ?, (SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                    nudge = ?, blacklisted = ?, paused = ?, paused_until = ?, timezone = ?",
+                    target,
+                    name,
+                    id,
+                    channel.nudge,
+                    channel.blacklisted,
+                    channel.paused,
+                    channel.paused_until,
+                    timezone,
+                    channel.nudge,
+                    channel.blacklisted,
+                    channel.paused,
+                    channel.paused_until,
+                    timezone
+                )
+                .execute(&mut transaction)
+                .await?;
+
+                *restored.entry("channels").or_default() += 1;
+            }
+        }
+
+        for mut reminder in backup.reminders {
+            match mapping.get(&reminder.channel.to_string()) {
+                Some(target) => {
+                    reminder.channel = *target;
+                    let name = reminder.name.clone();
+
+                    match create_reminder(
+                        ctx.inner(),
+                        &mut transaction,
+                        GuildId(id),
+                        UserId(user_id),
+                        reminder,
+                        AuditSource::Import,
+                    )
+                    .await
+                    {
+                        Ok(_) => *restored.entry("reminders").or_default() += 1,
+
+                        Err(e) => errors.push(format!(
+                            "Reminder \"{}\": {}",
+                            name,
+                            e["error"].as_str().unwrap_or("could not be restored")
+                        )),
+                    }
+                }
+
+                None => errors.push(format!(
+                    "Reminder \"{}\": its channel could not be found",
+                    reminder.name
+                )),
+            }
+        }
+
+        for template in &backup.templates {
+            let exists = sqlx::query!(
+                "SELECT 1 AS _e FROM reminder_template
+                WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+                id,
+                template.name
+            )
+            .fetch_optional(&mut transaction)
+            .await?
+            .is_some();
+
+            if exists {
+                errors.push(format!("Template \"{}\" already exists", template.name));
+                continue;
+            }
+
+            sqlx::query!(
+                "INSERT INTO reminder_template
+                (guild_id,
+                 name,
+                 attachment,
+                 attachment_name,
+                 avatar,
+                 content,
+                 embed_author,
+                 embed_author_url,
+                 embed_color,
+                 embed_description,
+                 embed_footer,
+                 embed_footer_url,
+                 embed_image_url,
+                 embed_thumbnail_url,
+                 embed_title,
+                 embed_fields,
+                 tts,
+                 username
+                ) VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                id,
+                template.name,
+                template.attachment,
+                template.attachment_name,
+                template.avatar,
+                template.content,
+                template.embed_author,
+                template.embed_author_url,
+                template.embed_color,
+                template.embed_description,
+                template.embed_footer,
+                template.embed_footer_url,
+                template.embed_image_url,
+                template.embed_thumbnail_url,
+                template.embed_title,
+                template.embed_fields,
+                template.tts,
+                template.username,
+            )
+            .execute(&mut transaction)
+            .await?;
+
+            *restored.entry("templates").or_default() += 1;
+        }
+
+        for todo in &backup.todos {
+            let channel = match &todo.channel {
+                Some(channel) => match mapping.get(channel) {
+                    Some(target) => Some(*target),
+
+                    None => {
+                        errors.push(format!(
+                            "Todo \"{}\": its channel could not be found",
+                            todo.value
+                        ));
+                        continue;
+                    }
+                },
+
+                None => None,
+            };
+
+            // restoring the same backup twice shouldn't repeat every todo
+            let exists = sqlx::query!(
+                "SELECT 1 AS _e FROM todos
+                WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+                    AND channel_id <=> (SELECT id FROM channels WHERE channel = ?)
+                    AND value = ?",
+                id,
+                channel,
+                todo.value
+            )
+            .fetch_optional(&mut transaction)
+            .await?
+            .is_some();
+
+            if exists {
+                continue;
+            }
+
+            sqlx::query!(
+                "INSERT INTO todos (value, channel_id, guild_id) VALUES
+                (?, (SELECT id FROM channels WHERE channel = ?), (SELECT id FROM guilds WHERE guild = ?))",
+                todo.value,
+                channel,
+                id
+            )
+            .execute(&mut transaction)
+            .await?;
+
+            *restored.entry("todos").or_default() += 1;
+        }
+
+        for command_macro in &backup.macros {
+            let exists = sqlx::query!(
+                "SELECT 1 AS _e FROM macro
+                WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+                id,
+                command_macro.name
+            )
+            .fetch_optional(&mut transaction)
+            .await?
+            .is_some();
+
+            if exists {
+                errors.push(format!("Macro \"{}\" already exists", command_macro.name));
+                continue;
+            }
+
+            let steps = match serde_json::from_value::<Vec<MacroStep>>(
+                command_macro.commands.clone(),
+            ) {
+                Ok(steps) => steps,
+
+                Err(_) => {
+                    errors.push(format!(
+                        "Macro \"{}\": its commands could not be read",
+                        command_macro.name
+                    ));
+                    continue;
+                }
+            };
+
+            let step_errors = validate_steps(&steps, macro_commands.inner());
+            if !step_errors.is_empty() {
+                errors.extend(
+                    step_errors
+                        .into_iter()
+                        .map(|e| format!("Macro \"{}\": {}", command_macro.name, e)),
+                );
+                continue;
+            }
+
+            sqlx::query!(
+                "INSERT INTO macro (guild_id, name, description, abort_on_failure, commands)
+                VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?)",
+                id,
+                command_macro.name,
+                command_macro.description,
+                command_macro.abort_on_failure,
+                serde_json::to_string(&steps).unwrap()
+            )
+            .execute(&mut transaction)
+            .await?;
+
+            *restored.entry("macros").or_default() += 1;
+        }
+
+        if let Some(user) = &backup.user {
+            sqlx::query!(
+                "UPDATE users SET timezone = IFNULL(?, timezone), allowed_dm = ? WHERE user = ?",
+                user.timezone.as_ref().filter(|tz| tz.parse::<Tz>().is_ok()),
+                user.allowed_dm,
+                user_id
+            )
+            .execute(&mut transaction)
+            .await?;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => match transaction.commit().await {
+            Ok(_) => {
+                if let Some(reminders) = restored.get("reminders") {
+                    guild_log::post(
+                        ctx.inner(),
+                        pool.inner(),
+                        id,
+                        LogEvent::Created,
+                        format!("{} reminders restored from a backup by <@{}>", reminders, user_id),
+                    )
+                    .await;
+                }
+
+                Ok(json!({ "restored": restored, "errors": errors }))
+            }
+
+            Err(e) => {
+                warn!("Could not commit backup for {}: {:?}", id, e);
+
+                json_err!("Could not restore backup")
+            }
+        },
+
+        Err(e) => {
+            warn!("Could not restore backup for {}: {:?}", id, e);
+
+            json_err!("Could not restore backup")
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{Duration, NaiveDate};
+use rocket::{
+    http::CookieJar,
+    serde::json::{json, Json},
+    State,
+};
+use serde::Deserialize;
+use serenity::{client::Context, model::id::GuildId};
+use sqlx::{MySql, Pool};
+
+use crate::{
+    consts::{DEFAULT_WEEKEND_DAYS, MAX_HOLIDAYS, MAX_HOLIDAY_NAME_LENGTH, WEEKDAYS},
+    ics,
+    routes::dashboard::{ImportBody, JsonResult},
+};
+
+#[derive(Deserialize)]
+pub struct PatchCalendar {
+    weekend: Vec<String>,
+}
+
+#[get("/api/guild/<id>/calendar")]
+pub async fn get_calendar(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let weekend_days = match sqlx::query!("SELECT weekend_days FROM guilds WHERE guild = ?", id)
+        .fetch_one(pool.inner())
+        .await
+    {
+        Ok(row) => row.weekend_days,
+
+        Err(sqlx::Error::RowNotFound) => DEFAULT_WEEKEND_DAYS,
+
+        Err(e) => {
+            warn!("Could not fetch weekend for {}: {:?}", id, e);
+
+            return json_err!("Could not load calendar");
+        }
+    };
+
+    match sqlx::query!(
+        "SELECT `date`, name FROM holidays
+        WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+        ORDER BY `date`",
+        id
+    )
+    .fetch_all(pool.inner())
+    .await
+    {
+        Ok(rows) => Ok(json!({
+            "weekend": WEEKDAYS
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| weekend_days & (1 << index) != 0)
+                .map(|(_, day)| day)
+                .collect::<Vec<_>>(),
+            "holidays": rows
+                .iter()
+                .map(|row| json!({"date": row.date.to_string(), "name": row.name}))
+                .collect::<Vec<_>>(),
+        })),
+
+        Err(e) => {
+            warn!("Could not fetch holidays for {}: {:?}", id, e);
+
+            json_err!("Could not load calendar")
+        }
+    }
+}
+
+#[patch("/api/guild/<id>/calendar", data = "<calendar>")]
+pub async fn edit_calendar(
+    id: u64,
+    calendar: Json<PatchCalendar>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let mut weekend_days: u8 = 0;
+    for day in &calendar.weekend {
+        match WEEKDAYS.iter().position(|d| d.eq_ignore_ascii_case(day)) {
+            Some(index) => weekend_days |= 1 << index,
+
+            None => return json_err!(format!("\"{}\" is not a day of the week", day)),
+        }
+    }
+
+    if weekend_days.count_ones() as usize == WEEKDAYS.len() {
+        return json_err!("At least one day of the week must be a working day");
+    }
+
+    match sqlx::query!(
+        "INSERT INTO guilds (guild, weekend_days) VALUES (?, ?)
+        ON DUPLICATE KEY UPDATE weekend_days = ?",
+        id,
+        weekend_days,
+        weekend_days
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not update weekend for {}: {:?}", id, e);
+
+            json_err!("Could not update calendar")
+        }
+    }
+}
+
+/// Import holidays from the all-day events of an iCalendar file. Events that can't be used are
+/// reported rather than failing the import
+#[put("/api/guild/<id>/calendar/holidays", data = "<body>")]
+pub async fn import_holidays(
+    id: u64,
+    body: Json<ImportBody>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let calendar = match base64::decode(&body.body) {
+        Ok(body) => match ics::parse(&String::from_utf8_lossy(&body)) {
+            Ok(calendar) => calendar,
+
+            Err(e) => return json_err!(format!("Could not read calendar: {}", e)),
+        },
+
+        Err(_) => return json_err!("Could not decode file"),
+    };
+
+    let mut holidays: Vec<(NaiveDate, String)> = vec![];
+    let mut errors = vec![];
+
+    for (index, event) in calendar.components("VEVENT").enumerate() {
+        let name: String = event
+            .property("SUMMARY")
+            .map(|p| ics::unescape_text(&p.value))
+            .unwrap_or_default()
+            .chars()
+            .take(MAX_HOLIDAY_NAME_LENGTH)
+            .collect();
+
+        match event.property("DTSTART").and_then(|p| ics::parse_date(&p.value)) {
+            Some(start) => {
+                // The end date of an all-day event is the day after it finishes
+                let end = event
+                    .property("DTEND")
+                    .and_then(|p| ics::parse_date(&p.value))
+                    .filter(|end| *end > start && *end - start <= Duration::days(366))
+                    .unwrap_or(start + Duration::days(1));
+
+                if event.property("RRULE").is_some() {
+                    errors.push(format!(
+                        "Event {} ({}): repeating events aren't supported, so only the first occurrence was imported",
+                        index + 1,
+                        name
+                    ));
+                }
+
+                let mut date = start;
+                while date < end {
+                    holidays.push((date, name.clone()));
+                    date += Duration::days(1);
+                }
+            }
+
+            None => {
+                errors.push(format!("Event {} ({}): no start date", index + 1, name));
+            }
+        }
+    }
+
+    if holidays.len() > MAX_HOLIDAYS {
+        return json_err!(format!("Calendar cannot contain more than {} holidays", MAX_HOLIDAYS));
+    }
+
+    let mut transaction = match pool.inner().begin().await {
+        Ok(transaction) => transaction,
+
+        Err(e) => {
+            warn!("Could not start transaction: {:?}", e);
+
+            return json_err!("Could not import holidays");
+        }
+    };
+
+    if let Err(e) = sqlx::query!(
+        "INSERT INTO guilds (guild) VALUES (?) ON DUPLICATE KEY UPDATE guild = guild",
+        id
+    )
+    .execute(&mut transaction)
+    .await
+    {
+        warn!("Could not create guild {}: {:?}", id, e);
+
+        return json_err!("Could not import holidays");
+    }
+
+    for (date, name) in &holidays {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO holidays (guild_id, `date`, name)
+            VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?)
+            ON DUPLICATE KEY UPDATE name = ?",
+            id,
+            date,
+            name,
+            name
+        )
+        .execute(&mut transaction)
+        .await
+        {
+            warn!("Could not insert holiday for {}: {:?}", id, e);
+
+            return json_err!("Could not import holidays");
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => Ok(json!({"imported": holidays.len(), "errors": errors})),
+
+        Err(e) => {
+            warn!("Could not commit holidays for {}: {:?}", id, e);
+
+            json_err!("Could not import holidays")
+        }
+    }
+}
+
+#[delete("/api/guild/<id>/calendar/holidays")]
+pub async fn delete_holidays(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "DELETE FROM holidays WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+        id
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not delete holidays for {}: {:?}", id, e);
+
+            json_err!("Could not delete holidays")
+        }
+    }
+}
@@ -0,0 +1,455 @@
+use std::collections::HashSet;
+
+use rocket::{
+    http::CookieJar,
+    serde::json::{json, serde_json, Json, Value as JsonValue},
+    State,
+};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::Context,
+    model::{
+        application::{
+            command::CommandOptionType, interaction::application_command::CommandDataOption,
+        },
+        id::GuildId,
+    },
+};
+use sqlx::{MySql, Pool};
+
+use crate::{
+    consts::{MACRO_MAX_COMMANDS, MAX_MACRO_DESCRIPTION_LENGTH, MAX_MACRO_NAME_LENGTH},
+    routes::dashboard::{deserialize_optional_field, JsonResult, Unset},
+    MacroCommandParameter, MacroCommands,
+};
+
+/// A single step of a macro. Stored in the same shape as the bot's `RecordedCommand`
+#[derive(Serialize, Deserialize)]
+pub struct MacroStep {
+    command_name: String,
+    #[serde(default)]
+    options: Vec<CommandDataOption>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CommandMacro {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    abort_on_failure: bool,
+    commands: Vec<MacroStep>,
+}
+
+#[derive(Deserialize)]
+pub struct PatchCommandMacro {
+    name: String,
+    #[serde(default)]
+    rename: Option<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_field")]
+    description: Unset<Option<String>>,
+    #[serde(default)]
+    abort_on_failure: Option<bool>,
+    #[serde(default)]
+    commands: Option<Vec<MacroStep>>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteCommandMacro {
+    name: String,
+}
+
+/// Check an option's value against the type and choices of its parameter. Returns the problem
+/// found, if any. `{placeholder}` values are filled in when the macro is ran, so are only checked
+/// where the option takes text
+fn check_value(parameter: &MacroCommandParameter, value: &JsonValue) -> Option<String> {
+    if !parameter.choices.is_empty() {
+        let valid =
+            value.as_u64().map_or(false, |choice| (choice as usize) < parameter.choices.len());
+
+        return if valid {
+            None
+        } else {
+            Some(format!("must be one of: {}", parameter.choices.join(", ")))
+        };
+    }
+
+    let is_placeholder = |s: &str| s.starts_with('{') && s.ends_with('}');
+
+    let (valid, expected) = match parameter.kind {
+        Some(CommandOptionType::String) => (value.is_string(), "text"),
+        Some(CommandOptionType::Integer) => {
+            (value.is_i64() || value.as_str().map_or(false, is_placeholder), "a whole number")
+        }
+        Some(CommandOptionType::Number) => {
+            (value.is_number() || value.as_str().map_or(false, is_placeholder), "a number")
+        }
+        Some(CommandOptionType::Boolean) => {
+            (value.is_boolean() || value.as_str().map_or(false, is_placeholder), "true or false")
+        }
+        Some(
+            CommandOptionType::User
+            | CommandOptionType::Channel
+            | CommandOptionType::Role
+            | CommandOptionType::Mentionable
+            | CommandOptionType::Attachment,
+        ) => (
+            value.as_str().map_or(false, |s| s.parse::<u64>().is_ok() || is_placeholder(s)),
+            "an ID",
+        ),
+        _ => (true, ""),
+    };
+
+    if valid {
+        None
+    } else {
+        Some(format!("must be {}", expected))
+    }
+}
+
+/// Check each step against the commands registered by the bot. Returns a list of problems
+/// found, or an empty list if the steps are valid.
+pub(crate) fn validate_steps(steps: &[MacroStep], macro_commands: &MacroCommands) -> Vec<String> {
+    let mut errors = vec![];
+
+    if steps.is_empty() {
+        errors.push("Macro must contain at least one command".to_string());
+    }
+    if steps.len() > *MACRO_MAX_COMMANDS {
+        errors.push(format!("Macro cannot contain more than {} commands", *MACRO_MAX_COMMANDS));
+    }
+
+    for (index, step) in steps.iter().enumerate() {
+        match macro_commands.get(&step.command_name) {
+            Some(command) => {
+                let mut seen = HashSet::new();
+
+                for option in &step.options {
+                    let parameter = match command.parameters.iter().find(|p| p.name == option.name)
+                    {
+                        Some(parameter) => parameter,
+
+                        None => {
+                            errors.push(format!(
+                                "Step {}: `/{}` has no option \"{}\"",
+                                index + 1,
+                                command.qualified_name,
+                                option.name
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if !seen.insert(&option.name) {
+                        errors.push(format!(
+                            "Step {}: option \"{}\" given more than once",
+                            index + 1,
+                            option.name
+                        ));
+                    } else if let Some(value) = &option.value {
+                        if let Some(problem) = check_value(parameter, value) {
+                            errors.push(format!(
+                                "Step {}: option \"{}\" {}",
+                                index + 1,
+                                option.name,
+                                problem
+                            ));
+                        }
+                    } else {
+                        errors.push(format!(
+                            "Step {}: option \"{}\" has no value",
+                            index + 1,
+                            option.name
+                        ));
+                    }
+                }
+
+                for parameter in command.parameters.iter().filter(|p| p.required) {
+                    if !step.options.iter().any(|o| o.name == parameter.name) {
+                        errors.push(format!(
+                            "Step {}: `/{}` requires option \"{}\"",
+                            index + 1,
+                            command.qualified_name,
+                            parameter.name
+                        ));
+                    }
+                }
+            }
+
+            None => {
+                errors.push(format!(
+                    "Step {}: command \"{}\" not found",
+                    index + 1,
+                    step.command_name
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+#[get("/api/guild/<id>/macros")]
+pub async fn get_macros(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+    macro_commands: &State<MacroCommands>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "SELECT name, description, abort_on_failure AS \"abort_on_failure: bool\", commands
+        FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+        id
+    )
+    .fetch_all(pool.inner())
+    .await
+    {
+        Ok(rows) => {
+            let macros = rows
+                .into_iter()
+                .map(|row| {
+                    let steps: Vec<MacroStep> =
+                        serde_json::from_str(&row.commands).unwrap_or_default();
+
+                    json!({
+                        "name": row.name,
+                        "description": row.description,
+                        "abort_on_failure": row.abort_on_failure,
+                        "commands": steps.iter().map(|step| json!({
+                            "command_name": step.command_name,
+                            "qualified_name": macro_commands
+                                .get(&step.command_name)
+                                .map(|c| c.qualified_name.clone()),
+                            "options": step.options,
+                        })).collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Ok(json!(macros))
+        }
+
+        Err(e) => {
+            warn!("Could not fetch macros from {}: {:?}", id, e);
+
+            json_err!("Could not get macros")
+        }
+    }
+}
+
+#[get("/api/macros/commands")]
+pub async fn get_macro_commands(macro_commands: &State<MacroCommands>) -> JsonResult {
+    let mut commands = macro_commands
+        .iter()
+        .map(|(identifying_name, command)| {
+            json!({
+                "command_name": identifying_name,
+                "qualified_name": command.qualified_name,
+                "parameters": command.parameters.iter().map(|p| json!({
+                    "name": p.name,
+                    "required": p.required,
+                    "kind": p.kind,
+                    "choices": p.choices,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    commands.sort_by(|a, b| a["qualified_name"].as_str().cmp(&b["qualified_name"].as_str()));
+
+    Ok(json!(commands))
+}
+
+#[post("/api/guild/<id>/macros", data = "<command_macro>")]
+pub async fn create_macro(
+    id: u64,
+    command_macro: Json<CommandMacro>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+    macro_commands: &State<MacroCommands>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    if command_macro.name.is_empty() {
+        return json_err!("Name cannot be empty");
+    }
+    check_length!(MAX_MACRO_NAME_LENGTH, command_macro.name);
+    check_length_opt!(MAX_MACRO_DESCRIPTION_LENGTH, command_macro.description);
+
+    let errors = validate_steps(&command_macro.commands, macro_commands.inner());
+    if !errors.is_empty() {
+        return Err(json!({"error": "Invalid macro", "errors": errors}));
+    }
+
+    let exists = sqlx::query!(
+        "SELECT 1 AS _e FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+        id,
+        command_macro.name
+    )
+    .fetch_one(pool.inner())
+    .await
+    .is_ok();
+
+    if exists {
+        return json_err!("A macro already exists under this name");
+    }
+
+    let json = serde_json::to_string(&command_macro.commands).unwrap();
+
+    match sqlx::query!(
+        "INSERT INTO macro (guild_id, name, description, abort_on_failure, commands)
+        VALUES ((SELECT id FROM guilds WHERE guild = ?), ?, ?, ?, ?)",
+        id,
+        command_macro.name,
+        command_macro.description,
+        command_macro.abort_on_failure,
+        json
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not create macro for {}: {:?}", id, e);
+
+            json_err!("Could not create macro")
+        }
+    }
+}
+
+#[patch("/api/guild/<id>/macros", data = "<command_macro>")]
+pub async fn edit_macro(
+    id: u64,
+    command_macro: Json<PatchCommandMacro>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+    macro_commands: &State<MacroCommands>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let current = match sqlx::query!(
+        "SELECT id, name, description, abort_on_failure AS \"abort_on_failure: bool\", commands
+        FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+        id,
+        command_macro.name
+    )
+    .fetch_one(pool.inner())
+    .await
+    {
+        Ok(row) => row,
+
+        Err(sqlx::Error::RowNotFound) => return json_err!("Macro not found"),
+
+        Err(e) => {
+            warn!("Could not fetch macro from {}: {:?}", id, e);
+
+            return json_err!("Could not edit macro");
+        }
+    };
+
+    let name = match &command_macro.rename {
+        Some(rename) if rename != &current.name => {
+            if rename.is_empty() {
+                return json_err!("Name cannot be empty");
+            }
+            check_length!(MAX_MACRO_NAME_LENGTH, rename);
+
+            let exists = sqlx::query!(
+                "SELECT 1 AS _e FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+                id,
+                rename
+            )
+            .fetch_one(pool.inner())
+            .await
+            .is_ok();
+
+            if exists {
+                return json_err!("A macro already exists under this name");
+            }
+
+            rename.clone()
+        }
+
+        _ => current.name,
+    };
+
+    let description = match &command_macro.description {
+        Some(description) => {
+            check_length_opt!(MAX_MACRO_DESCRIPTION_LENGTH, description);
+
+            description.clone()
+        }
+
+        None => current.description,
+    };
+
+    let abort_on_failure = command_macro.abort_on_failure.unwrap_or(current.abort_on_failure);
+
+    let commands = match &command_macro.commands {
+        Some(steps) => {
+            let errors = validate_steps(steps, macro_commands.inner());
+            if !errors.is_empty() {
+                return Err(json!({"error": "Invalid macro", "errors": errors}));
+            }
+
+            serde_json::to_string(steps).unwrap()
+        }
+
+        None => current.commands,
+    };
+
+    match sqlx::query!(
+        "UPDATE macro SET name = ?, description = ?, abort_on_failure = ?, commands = ? WHERE id = ?",
+        name,
+        description,
+        abort_on_failure,
+        commands,
+        current.id
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not update macro for {}: {:?}", id, e);
+
+            json_err!("Could not edit macro")
+        }
+    }
+}
+
+#[delete("/api/guild/<id>/macros", data = "<command_macro>")]
+pub async fn delete_macro(
+    id: u64,
+    command_macro: Json<DeleteCommandMacro>,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "DELETE FROM macro WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND name = ?",
+        id,
+        command_macro.name
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(res) if res.rows_affected() == 0 => json_err!("Macro not found"),
+
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not delete macro from {}: {:?}", id, e);
+
+            json_err!("Could not delete macro")
+        }
+    }
+}
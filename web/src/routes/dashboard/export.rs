@@ -1,4 +1,13 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
 use csv::{QuoteStyle, WriterBuilder};
+use postman::{
+    audit::AuditSource,
+    guild_log::{self, LogEvent},
+    recurrence::{BusinessDayPolicy, Calendar, IntervalPolicy, Recurrence},
+};
 use rocket::{
     http::CookieJar,
     serde::json::{json, serde_json, Json},
@@ -10,9 +19,13 @@ use serenity::{
 };
 use sqlx::{MySql, Pool};
 
-use crate::routes::dashboard::{
-    create_reminder, generate_uid, ImportBody, JsonResult, Reminder, ReminderCsv,
-    ReminderTemplateCsv, TodoCsv,
+use crate::{
+    consts::{HOUR, MAX_NAME_LENGTH, MINUTE},
+    ics::{self, Component, Property},
+    routes::dashboard::{
+        create_database_channel, create_reminder, generate_uid, validate_reminder, ImportBody,
+        JsonResult, Reminder, ReminderCsv, ReminderTemplateCsv, TodoCsv,
+    },
 };
 
 #[get("/api/guild/<id>/export/reminders")]
@@ -43,6 +56,7 @@ pub async fn export_reminders(
                  reminders.attachment,
                  reminders.attachment_name,
                  reminders.avatar,
+                 reminders.business_day_policy,
                  CONCAT('#', channels.channel) AS channel,
                  reminders.content,
                  reminders.embed_author,
@@ -60,8 +74,10 @@ pub async fn export_reminders(
                  reminders.interval_seconds,
                  reminders.interval_days,
                  reminders.interval_months,
+                 reminders.interval_policy,
                  reminders.name,
                  reminders.restartable,
+                 reminders.timezone,
                  reminders.tts,
                  reminders.username,
                  reminders.utc_time
@@ -114,9 +130,52 @@ pub async fn export_reminders(
     }
 }
 
-#[put("/api/guild/<id>/export/reminders", data = "<body>")]
+fn reminder_from_csv(record: ReminderCsv) -> Result<Reminder, String> {
+    let channel_id = record.channel.strip_prefix('#').unwrap_or(&record.channel);
+
+    let channel =
+        channel_id.parse::<u64>().map_err(|_| format!("Failed to parse channel {}", channel_id))?;
+
+    Ok(Reminder {
+        attachment: record.attachment,
+        attachment_name: record.attachment_name,
+        avatar: record.avatar,
+        business_day_policy: record.business_day_policy,
+        channel,
+        content: record.content,
+        embed_author: record.embed_author,
+        embed_author_url: record.embed_author_url,
+        embed_color: record.embed_color,
+        embed_description: record.embed_description,
+        embed_footer: record.embed_footer,
+        embed_footer_url: record.embed_footer_url,
+        embed_image_url: record.embed_image_url,
+        embed_thumbnail_url: record.embed_thumbnail_url,
+        embed_title: record.embed_title,
+        embed_fields: record.embed_fields.map(|s| serde_json::from_str(&s).ok()).flatten(),
+        enabled: record.enabled,
+        expires: record.expires,
+        interval_seconds: record.interval_seconds,
+        interval_days: record.interval_days,
+        interval_months: record.interval_months,
+        interval_policy: record.interval_policy,
+        name: record.name,
+        restartable: record.restartable,
+        timezone: record.timezone,
+        tts: record.tts,
+        uid: generate_uid(),
+        username: record.username,
+        utc_time: record.utc_time,
+    })
+}
+
+/// Import reminders from a CSV export. Every row is checked before anything is written, and the
+/// reminders are then created in a single transaction, so a bad row means nothing is imported.
+/// The response reports the outcome of each row. With `dry_run`, rows are only checked
+#[put("/api/guild/<id>/export/reminders?<dry_run>", data = "<body>")]
 pub async fn import_reminders(
     id: u64,
+    dry_run: Option<bool>,
     cookies: &CookieJar<'_>,
     body: Json<ImportBody>,
     ctx: &State<Context>,
@@ -126,84 +185,614 @@ pub async fn import_reminders(
 
     let user_id =
         cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+    let dry_run = dry_run.unwrap_or(false);
 
-    match base64::decode(&body.body) {
-        Ok(body) => {
-            let mut reader = csv::Reader::from_reader(body.as_slice());
-
-            for result in reader.deserialize::<ReminderCsv>() {
-                match result {
-                    Ok(record) => {
-                        let channel_id = record.channel.split_at(1).1;
-
-                        match channel_id.parse::<u64>() {
-                            Ok(channel_id) => {
-                                let reminder = Reminder {
-                                    attachment: record.attachment,
-                                    attachment_name: record.attachment_name,
-                                    avatar: record.avatar,
-                                    channel: channel_id,
-                                    content: record.content,
-                                    embed_author: record.embed_author,
-                                    embed_author_url: record.embed_author_url,
-                                    embed_color: record.embed_color,
-                                    embed_description: record.embed_description,
-                                    embed_footer: record.embed_footer,
-                                    embed_footer_url: record.embed_footer_url,
-                                    embed_image_url: record.embed_image_url,
-                                    embed_thumbnail_url: record.embed_thumbnail_url,
-                                    embed_title: record.embed_title,
-                                    embed_fields: record
-                                        .embed_fields
-                                        .map(|s| serde_json::from_str(&s).ok())
-                                        .flatten(),
-                                    enabled: record.enabled,
-                                    expires: record.expires,
-                                    interval_seconds: record.interval_seconds,
-                                    interval_days: record.interval_days,
-                                    interval_months: record.interval_months,
-                                    name: record.name,
-                                    restartable: record.restartable,
-                                    tts: record.tts,
-                                    uid: generate_uid(),
-                                    username: record.username,
-                                    utc_time: record.utc_time,
-                                };
-
-                                create_reminder(
-                                    ctx.inner(),
-                                    pool.inner(),
-                                    GuildId(id),
-                                    UserId(user_id),
-                                    reminder,
-                                )
-                                .await?;
-                            }
+    let body = match base64::decode(&body.body) {
+        Ok(body) => body,
 
-                            Err(_) => {
-                                return json_err!(format!(
-                                    "Failed to parse channel {}",
-                                    channel_id
-                                ));
+        Err(_) => return json_err!("Malformed base64"),
+    };
+
+    let mut reader = csv::Reader::from_reader(body.as_slice());
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+
+        Err(e) => {
+            warn!("Couldn't read CSV headers: {:?}", e);
+
+            return json_err!("Could not read the first (title) row");
+        }
+    };
+
+    let mut rows = vec![];
+    let mut reminders = vec![];
+
+    for result in reader.records() {
+        let (row, record) = match result {
+            Ok(record) => (
+                record.position().map_or(0, |p| p.line()),
+                record.deserialize::<ReminderCsv>(Some(&headers)),
+            ),
+
+            Err(e) => (e.position().map_or(0, |p| p.line()), Err(e)),
+        };
+
+        let (channel, error) = match record {
+            Ok(record) => {
+                let channel = record.channel.clone();
+
+                match reminder_from_csv(record) {
+                    Ok(reminder) => {
+                        match validate_reminder(
+                            ctx.inner(),
+                            GuildId(id),
+                            UserId(user_id),
+                            &reminder,
+                        )
+                        .await
+                        {
+                            Ok(()) => {
+                                reminders.push((rows.len(), reminder));
+
+                                (Some(channel), None)
                             }
+
+                            Err(e) => (Some(channel), e["error"].as_str().map(|e| e.to_string())),
                         }
                     }
 
-                    Err(e) => {
-                        warn!("Couldn't deserialize CSV row: {:?}", e);
+                    Err(e) => (Some(channel), Some(e)),
+                }
+            }
 
-                        return json_err!("Deserialize error. Aborted");
-                    }
+            Err(e) => (None, Some(format!("Could not read row: {}", e))),
+        };
+
+        rows.push(json!({ "row": row, "channel": channel, "error": error }));
+    }
+
+    let failed = rows.iter().filter(|row| !row["error"].is_null()).count();
+    if dry_run || failed > 0 {
+        return Ok(json!({ "dry_run": dry_run, "imported": 0, "failed": failed, "rows": rows }));
+    }
+
+    // Webhooks are created through Discord, so channels are set up before the transaction opens
+    // rather than holding it open across those requests
+    let channels: HashSet<u64> = reminders.iter().map(|(_, reminder)| reminder.channel).collect();
+    for channel in channels {
+        if let Err(e) = create_database_channel(ctx.inner(), ChannelId(channel), pool.inner()).await
+        {
+            warn!("`create_database_channel` returned an error code: {:?}", e);
+
+            for (index, _) in reminders.iter().filter(|(_, reminder)| reminder.channel == channel) {
+                rows[*index]["error"] = json!(
+                    "Failed to configure channel for reminders. Please check the bot permissions"
+                );
+            }
+
+            let failed = rows.iter().filter(|row| !row["error"].is_null()).count();
+            return Ok(json!({ "dry_run": false, "imported": 0, "failed": failed, "rows": rows }));
+        }
+    }
+
+    let mut transaction = match pool.inner().begin().await {
+        Ok(transaction) => transaction,
+
+        Err(e) => {
+            warn!("Could not start transaction: {:?}", e);
+
+            return json_err!("Could not import reminders");
+        }
+    };
+
+    let imported = reminders.len();
+    for (index, reminder) in reminders {
+        if let Err(e) = create_reminder(
+            ctx.inner(),
+            &mut transaction,
+            GuildId(id),
+            UserId(user_id),
+            reminder,
+            AuditSource::Import,
+        )
+        .await
+        {
+            // Dropping the transaction rolls back the rows already created
+            rows[index]["error"] = e["error"].clone();
+
+            return Ok(json!({ "dry_run": false, "imported": 0, "failed": 1, "rows": rows }));
+        }
+    }
+
+    match transaction.commit().await {
+        Ok(_) => {
+            if imported > 0 {
+                guild_log::post(
+                    ctx.inner(),
+                    pool.inner(),
+                    id,
+                    LogEvent::Created,
+                    format!("{} reminders imported from a spreadsheet by <@{}>", imported, user_id),
+                )
+                .await;
+            }
+
+            Ok(json!({ "dry_run": false, "imported": imported, "failed": 0, "rows": rows }))
+        }
+
+        Err(e) => {
+            warn!("Could not commit imported reminders: {:?}", e);
+
+            json_err!("Could not import reminders")
+        }
+    }
+}
+
+/// The iCalendar recurrence rule equivalent to a reminder's interval. `Ok(None)` if the reminder
+/// doesn't repeat, and `Err` if the interval mixes units that a single rule can't express
+fn interval_rrule(
+    seconds: Option<u32>,
+    days: Option<u32>,
+    months: Option<u32>,
+) -> Result<Option<String>, ()> {
+    let rule = match (seconds.unwrap_or(0), days.unwrap_or(0), months.unwrap_or(0)) {
+        (0, 0, 0) => return Ok(None),
+
+        (seconds, 0, 0) if seconds % HOUR as u32 == 0 => {
+            format!("FREQ=HOURLY;INTERVAL={}", seconds / HOUR as u32)
+        }
+        (seconds, 0, 0) if seconds % MINUTE as u32 == 0 => {
+            format!("FREQ=MINUTELY;INTERVAL={}", seconds / MINUTE as u32)
+        }
+        (seconds, 0, 0) => format!("FREQ=SECONDLY;INTERVAL={}", seconds),
+
+        (0, days, 0) if days % 7 == 0 => format!("FREQ=WEEKLY;INTERVAL={}", days / 7),
+        (0, days, 0) => format!("FREQ=DAILY;INTERVAL={}", days),
+
+        (0, 0, months) if months % 12 == 0 => format!("FREQ=YEARLY;INTERVAL={}", months / 12),
+        (0, 0, months) => format!("FREQ=MONTHLY;INTERVAL={}", months),
+
+        _ => return Err(()),
+    };
+
+    Ok(Some(rule))
+}
+
+#[get("/api/guild/<id>/export/reminders.ics")]
+pub async fn export_reminders_ics(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let channels = match GuildId(id).channels(&ctx.inner()).await {
+        Ok(channels) => channels
+            .keys()
+            .into_iter()
+            .map(|k| k.as_u64().to_string())
+            .collect::<Vec<String>>()
+            .join(","),
+
+        Err(e) => {
+            warn!("Could not fetch channels from {}: {:?}", id, e);
+
+            return json_err!("Failed to get guild channels");
+        }
+    };
+
+    let reminders = match sqlx::query!(
+        "SELECT
+         reminders.uid,
+         reminders.name,
+         reminders.content,
+         reminders.embed_description,
+         reminders.utc_time,
+         reminders.timezone,
+         reminders.interval_seconds,
+         reminders.interval_days,
+         reminders.interval_months,
+         reminders.interval_policy,
+         reminders.expires,
+         channels.channel
+        FROM reminders
+        INNER JOIN channels ON channels.id = reminders.channel_id
+        WHERE FIND_IN_SET(channels.channel, ?)",
+        channels
+    )
+    .fetch_all(pool.inner())
+    .await
+    {
+        Ok(reminders) => reminders,
+
+        Err(e) => {
+            warn!("Failed to complete SQL query: {:?}", e);
+
+            return json_err!("Failed to query reminders");
+        }
+    };
+
+    let now = ics::format_utc(Utc::now().naive_utc());
+    let mut calendar = Component::new("VCALENDAR");
+    let mut errors = vec![];
+
+    calendar.properties.push(Property::new("VERSION", "2.0"));
+    calendar.properties.push(Property::new("PRODID", "-//Reminder Bot//Reminders//EN"));
+    calendar.properties.push(Property::new("CALSCALE", "GREGORIAN"));
+
+    // the first time given in each timezone, to define the timezone from
+    let mut timezones: BTreeMap<&str, (Tz, NaiveDateTime)> = BTreeMap::new();
+
+    for reminder in reminders {
+        let mut event = Component::new("VEVENT");
+
+        event.properties.push(Property::new("UID", format!("{}@reminder-bot.com", reminder.uid)));
+        event.properties.push(Property::new("DTSTAMP", now.as_str()));
+
+        // Reminders that keep the same local time are given in their timezone, so that calendar
+        // apps repeat them across daylight saving changes in the same way
+        let timezone = reminder.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        if reminder.interval_policy == "wall_clock" && timezone != Tz::UTC {
+            let local = DateTime::<Utc>::from_utc(reminder.utc_time, Utc).with_timezone(&timezone);
+
+            let first = timezones.entry(timezone.name()).or_insert((timezone, reminder.utc_time));
+            first.1 = first.1.min(reminder.utc_time);
+
+            event.properties.push(
+                Property::new("DTSTART", ics::format_local(local.naive_local()))
+                    .with_param("TZID", timezone.name()),
+            );
+        } else {
+            event.properties.push(Property::new("DTSTART", ics::format_utc(reminder.utc_time)));
+        }
+
+        event.properties.push(Property::new("SUMMARY", ics::escape_text(&reminder.name)));
+        event.properties.push(Property::new(
+            "DESCRIPTION",
+            ics::escape_text(if reminder.content.is_empty() {
+                &reminder.embed_description
+            } else {
+                &reminder.content
+            }),
+        ));
+        event.properties.push(Property::new("X-REMINDER-CHANNEL", reminder.channel.to_string()));
+
+        match interval_rrule(
+            reminder.interval_seconds,
+            reminder.interval_days,
+            reminder.interval_months,
+        ) {
+            Ok(Some(mut rule)) => {
+                if let Some(expires) = reminder.expires {
+                    rule.push_str(&format!(";UNTIL={}", ics::format_utc(expires)));
                 }
+
+                event.properties.push(Property::new("RRULE", rule));
+            }
+
+            Ok(None) => {}
+
+            Err(()) => errors.push(format!(
+                "Reminder \"{}\" repeats on an interval that calendars can't represent, so only its next occurrence was exported",
+                reminder.name
+            )),
+        }
+
+        calendar.components.push(event);
+    }
+
+    for (timezone, start) in timezones.into_values().rev() {
+        calendar.components.insert(0, ics::vtimezone(timezone, start));
+    }
+
+    Ok(json!({ "body": calendar.to_string(), "errors": errors }))
+}
+
+/// A reminder's interval and expiry, read from an iCalendar recurrence rule
+struct RecurrenceRule {
+    seconds: Option<u32>,
+    days: Option<u32>,
+    months: Option<u32>,
+    until: Option<DateTime<Utc>>,
+    count: Option<usize>,
+}
+
+fn parse_rrule(value: &str, start: DateTime<Tz>) -> Result<RecurrenceRule, String> {
+    let parts = value
+        .split(';')
+        .map(|part| {
+            part.split_once('=')
+                .map(|(key, value)| (key.to_uppercase(), value.to_uppercase()))
+                .ok_or_else(|| format!("recurrence rule \"{}\" isn't valid", part))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let freq = parts
+        .iter()
+        .find(|(key, _)| key == "FREQ")
+        .map(|(_, value)| value.as_str())
+        .ok_or("recurrence rule has no frequency")?;
+    let weekday =
+        ["MO", "TU", "WE", "TH", "FR", "SA", "SU"][start.weekday().num_days_from_monday() as usize];
+
+    let mut rule =
+        RecurrenceRule { seconds: None, days: None, months: None, until: None, count: None };
+    let mut interval: u32 = 1;
+
+    for (key, value) in &parts {
+        match (key.as_str(), freq) {
+            ("FREQ", _) | ("WKST", _) => {}
+
+            ("INTERVAL", _) => {
+                interval = value
+                    .parse()
+                    .ok()
+                    .filter(|interval| *interval > 0)
+                    .ok_or("recurrence interval isn't valid")?;
+            }
+
+            ("UNTIL", _) => {
+                rule.until = Some(
+                    ics::parse_datetime(&Property::new("UNTIL", value.as_str()), start.timezone())
+                        .ok_or("recurrence end isn't valid")?,
+                );
+            }
+
+            ("COUNT", _) => {
+                rule.count = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or("recurrence count isn't valid")?,
+                );
+            }
+
+            // Calendar apps often restate the start date in the rule, which changes nothing
+            ("BYDAY", "WEEKLY") if value == weekday => {}
+            ("BYMONTHDAY", "MONTHLY") | ("BYMONTHDAY", "YEARLY")
+                if *value == start.day().to_string() => {}
+            ("BYMONTH", "YEARLY") if *value == start.month().to_string() => {}
+
+            _ => return Err(format!("recurrence rule {}={} isn't supported", key, value)),
+        }
+    }
+
+    let too_long = || "recurrence interval is too long".to_string();
+
+    match freq {
+        "SECONDLY" => rule.seconds = Some(interval),
+        "MINUTELY" => {
+            rule.seconds = Some(interval.checked_mul(MINUTE as u32).ok_or_else(too_long)?)
+        }
+        "HOURLY" => rule.seconds = Some(interval.checked_mul(HOUR as u32).ok_or_else(too_long)?),
+        "DAILY" => rule.days = Some(interval),
+        "WEEKLY" => rule.days = Some(interval.checked_mul(7).ok_or_else(too_long)?),
+        "MONTHLY" => rule.months = Some(interval),
+        "YEARLY" => rule.months = Some(interval.checked_mul(12).ok_or_else(too_long)?),
+        _ => return Err(format!("frequency {} isn't supported", freq)),
+    }
+
+    Ok(rule)
+}
+
+/// Convert an event into a reminder, repeating in the timezone of its start. `Err` explains why
+/// the event can't be imported
+fn event_reminder(
+    event: &Component,
+    channel: Option<u64>,
+    timezone: Tz,
+) -> Result<Reminder, String> {
+    for unsupported in ["RDATE", "EXDATE", "EXRULE"] {
+        if event.property(unsupported).is_some() {
+            return Err(format!("{} isn't supported", unsupported));
+        }
+    }
+    if event.properties.iter().filter(|p| p.name == "RRULE").count() > 1 {
+        return Err("multiple recurrence rules aren't supported".to_string());
+    }
+
+    let start = event.property("DTSTART").ok_or("no start time")?;
+    let timezone = match start.param("TZID") {
+        Some(tzid) => tzid
+            .trim_start_matches('/')
+            .parse::<Tz>()
+            .map_err(|_| format!("timezone {} isn't supported", tzid))?,
+
+        None if start.value.ends_with('Z') => Tz::UTC,
+
+        None => timezone,
+    };
+    let start = ics::parse_datetime(start, timezone).ok_or("start time isn't valid")?;
+
+    let channel = match event.property("X-REMINDER-CHANNEL") {
+        Some(property) => property.value.parse::<u64>().map_err(|_| "channel isn't valid")?,
+
+        None => channel.ok_or("no channel was chosen for the import")?,
+    };
+
+    let rule = match event.property("RRULE") {
+        Some(rrule) => Some(parse_rrule(&rrule.value, start.with_timezone(&timezone))?),
+
+        None => None,
+    };
+
+    let (utc_time, expires) = match &rule {
+        Some(rule) => {
+            let recurrence = Recurrence {
+                timezone,
+                policy: if rule.seconds.is_some() {
+                    IntervalPolicy::Elapsed
+                } else {
+                    IntervalPolicy::WallClock
+                },
+                seconds: rule.seconds,
+                days: rule.days,
+                months: rule.months,
+                business_days: BusinessDayPolicy::Ignore,
+                calendar: Calendar::default(),
+            };
+
+            let expires = match rule.count {
+                Some(count) => recurrence.occurrences(start, None).nth(count - 1).map(|(t, _)| t),
+
+                None => rule.until,
+            };
+
+            let (next, _) = recurrence
+                .next_after(start, None, Utc::now() - Duration::seconds(1))
+                .ok_or("recurrence rule can't be followed")?;
+
+            if expires.map_or(false, |expires| next > expires) {
+                return Err("all occurrences have passed".to_string());
             }
 
-            Ok(json!({}))
+            (next, expires)
         }
 
-        Err(_) => {
-            json_err!("Malformed base64")
+        None if start < Utc::now() => return Err("event has passed".to_string()),
+
+        None => (start, None),
+    };
+
+    let summary =
+        event.property("SUMMARY").map(|p| ics::unescape_text(&p.value)).unwrap_or_default();
+    let content = match event.property("DESCRIPTION") {
+        Some(description) => ics::unescape_text(&description.value),
+
+        None => summary.clone(),
+    };
+    let name = summary.chars().take(MAX_NAME_LENGTH).collect::<String>();
+
+    let reminder = Reminder {
+        attachment: None,
+        attachment_name: None,
+        avatar: None,
+        business_day_policy: "ignore".to_string(),
+        channel,
+        content,
+        embed_author: String::new(),
+        embed_author_url: None,
+        embed_color: 0,
+        embed_description: String::new(),
+        embed_footer: String::new(),
+        embed_footer_url: None,
+        embed_image_url: None,
+        embed_thumbnail_url: None,
+        embed_title: String::new(),
+        embed_fields: None,
+        enabled: true,
+        expires: expires.map(|t| t.naive_utc()),
+        interval_seconds: rule.as_ref().and_then(|r| r.seconds),
+        interval_days: rule.as_ref().and_then(|r| r.days),
+        interval_months: rule.as_ref().and_then(|r| r.months),
+        interval_policy: if rule.as_ref().map_or(false, |r| r.seconds.is_some()) {
+            "elapsed".to_string()
+        } else {
+            "wall_clock".to_string()
+        },
+        name: if name.is_empty() { "Reminder".to_string() } else { name },
+        restartable: false,
+        timezone: timezone.name().to_string(),
+        tts: false,
+        uid: generate_uid(),
+        username: None,
+        utc_time: utc_time.naive_utc(),
+    };
+
+    Ok(reminder)
+}
+
+/// Import the events of an iCalendar file as reminders. Events without an `X-REMINDER-CHANNEL`
+/// (i.e. not exported from here) are created in `channel`. Events that can't be imported are
+/// reported individually, and don't stop the rest of the import
+#[put("/api/guild/<id>/export/reminders.ics?<channel>", data = "<body>")]
+pub async fn import_reminders_ics(
+    id: u64,
+    channel: Option<u64>,
+    cookies: &CookieJar<'_>,
+    body: Json<ImportBody>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+
+    let calendar = match base64::decode(&body.body) {
+        Ok(body) => match ics::parse(&String::from_utf8_lossy(&body)) {
+            Ok(calendar) => calendar,
+
+            Err(e) => return json_err!(format!("Could not read calendar: {}", e)),
+        },
+
+        Err(_) => return json_err!("Malformed base64"),
+    };
+
+    // Times without a timezone are taken to be in the user's timezone
+    let timezone = sqlx::query!(
+        "SELECT IFNULL(timezone, 'UTC') AS timezone FROM users WHERE user = ?",
+        user_id
+    )
+    .fetch_one(pool.inner())
+    .await
+    .ok()
+    .and_then(|row| row.timezone.parse::<Tz>().ok())
+    .unwrap_or(Tz::UTC);
+
+    let mut imported = 0;
+    let mut errors = vec![];
+
+    for (index, event) in calendar.components("VEVENT").enumerate() {
+        let summary =
+            event.property("SUMMARY").map(|p| ics::unescape_text(&p.value)).unwrap_or_default();
+
+        let reminder = match event_reminder(event, channel, timezone) {
+            Ok(reminder) => reminder,
+
+            Err(e) => {
+                errors.push(format!("Event {} ({}): {}", index + 1, summary, e));
+                continue;
+            }
+        };
+
+        match create_reminder(
+            ctx.inner(),
+            pool.inner(),
+            GuildId(id),
+            UserId(user_id),
+            reminder,
+            AuditSource::Import,
+        )
+        .await
+        {
+            Ok(_) => imported += 1,
+
+            Err(e) => {
+                errors.push(format!(
+                    "Event {} ({}): {}",
+                    index + 1,
+                    summary,
+                    e["error"].as_str().unwrap_or("could not be created")
+                ));
+            }
         }
     }
+
+    if imported > 0 {
+        guild_log::post(
+            ctx.inner(),
+            pool.inner(),
+            id,
+            LogEvent::Created,
+            format!("{} reminders imported from a calendar by <@{}>", imported, user_id),
+        )
+        .await;
+    }
+
+    Ok(json!({ "imported": imported, "errors": errors }))
 }
 
 #[get("/api/guild/<id>/export/todos")]
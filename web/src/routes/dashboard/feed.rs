@@ -0,0 +1,213 @@
+use rocket::{http::CookieJar, serde::json::json, State};
+use serenity::{client::Context, model::id::GuildId};
+use sqlx::{MySql, Pool};
+
+use crate::routes::dashboard::{generate_uid, JsonResult};
+
+fn feed_path(token: &str) -> String {
+    format!("/feed/{}", token)
+}
+
+#[get("/api/guild/<id>/feed")]
+pub async fn get_guild_feed(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "SELECT token FROM calendar_feeds
+        WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+        id
+    )
+    .fetch_one(pool.inner())
+    .await
+    {
+        Ok(row) => Ok(json!({ "path": feed_path(&row.token) })),
+
+        Err(sqlx::Error::RowNotFound) => Ok(json!({ "path": null })),
+
+        Err(e) => {
+            warn!("Could not fetch feed for {}: {:?}", id, e);
+
+            json_err!("Could not load calendar feed")
+        }
+    }
+}
+
+/// Create the guild's feed, or replace its link so that the previous link stops working
+#[post("/api/guild/<id>/feed")]
+pub async fn regenerate_guild_feed(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    if let Err(e) = sqlx::query!(
+        "INSERT INTO guilds (guild) VALUES (?) ON DUPLICATE KEY UPDATE guild = guild",
+        id
+    )
+    .execute(pool.inner())
+    .await
+    {
+        warn!("Could not create guild {}: {:?}", id, e);
+
+        return json_err!("Could not create calendar feed");
+    }
+
+    let token = generate_uid();
+
+    match sqlx::query!(
+        "INSERT INTO calendar_feeds (token, guild_id)
+        VALUES (?, (SELECT id FROM guilds WHERE guild = ?))
+        ON DUPLICATE KEY UPDATE token = ?, created_at = CURRENT_TIMESTAMP",
+        token,
+        id,
+        token
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({ "path": feed_path(&token) })),
+
+        Err(e) => {
+            warn!("Could not create feed for {}: {:?}", id, e);
+
+            json_err!("Could not create calendar feed")
+        }
+    }
+}
+
+#[delete("/api/guild/<id>/feed")]
+pub async fn revoke_guild_feed(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    match sqlx::query!(
+        "DELETE FROM calendar_feeds WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+        id
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not delete feed for {}: {:?}", id, e);
+
+            json_err!("Could not revoke calendar feed")
+        }
+    }
+}
+
+#[get("/api/user/feed")]
+pub async fn get_user_feed(cookies: &CookieJar<'_>, pool: &State<Pool<MySql>>) -> JsonResult {
+    let user_id = match cookies.get_private("userid").map(|u| u.value().parse::<u64>().ok()) {
+        Some(Some(user_id)) => user_id,
+
+        _ => return json_err!("User not authorized"),
+    };
+
+    match sqlx::query!(
+        "SELECT token FROM calendar_feeds
+        WHERE user_id = (SELECT id FROM users WHERE user = ?)",
+        user_id
+    )
+    .fetch_one(pool.inner())
+    .await
+    {
+        Ok(row) => Ok(json!({ "path": feed_path(&row.token) })),
+
+        Err(sqlx::Error::RowNotFound) => Ok(json!({ "path": null })),
+
+        Err(e) => {
+            warn!("Could not fetch feed for user {}: {:?}", user_id, e);
+
+            json_err!("Could not load calendar feed")
+        }
+    }
+}
+
+#[post("/api/user/feed")]
+pub async fn regenerate_user_feed(
+    cookies: &CookieJar<'_>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    let user_id = match cookies.get_private("userid").map(|u| u.value().parse::<u64>().ok()) {
+        Some(Some(user_id)) => user_id,
+
+        _ => return json_err!("User not authorized"),
+    };
+
+    // Users are only stored once they've used the bot
+    let id = match sqlx::query!("SELECT id FROM users WHERE user = ?", user_id)
+        .fetch_one(pool.inner())
+        .await
+    {
+        Ok(row) => row.id,
+
+        Err(sqlx::Error::RowNotFound) => {
+            return json_err!("Use a command with the bot before creating a calendar feed");
+        }
+
+        Err(e) => {
+            warn!("Could not fetch user {}: {:?}", user_id, e);
+
+            return json_err!("Could not create calendar feed");
+        }
+    };
+
+    let token = generate_uid();
+
+    match sqlx::query!(
+        "INSERT INTO calendar_feeds (token, user_id) VALUES (?, ?)
+        ON DUPLICATE KEY UPDATE token = ?, created_at = CURRENT_TIMESTAMP",
+        token,
+        id,
+        token
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({ "path": feed_path(&token) })),
+
+        Err(e) => {
+            warn!("Could not create feed for user {}: {:?}", user_id, e);
+
+            json_err!("Could not create calendar feed")
+        }
+    }
+}
+
+#[delete("/api/user/feed")]
+pub async fn revoke_user_feed(cookies: &CookieJar<'_>, pool: &State<Pool<MySql>>) -> JsonResult {
+    let user_id = match cookies.get_private("userid").map(|u| u.value().parse::<u64>().ok()) {
+        Some(Some(user_id)) => user_id,
+
+        _ => return json_err!("User not authorized"),
+    };
+
+    match sqlx::query!(
+        "DELETE FROM calendar_feeds WHERE user_id = (SELECT id FROM users WHERE user = ?)",
+        user_id
+    )
+    .execute(pool.inner())
+    .await
+    {
+        Ok(_) => Ok(json!({})),
+
+        Err(e) => {
+            warn!("Could not delete feed for user {}: {:?}", user_id, e);
+
+            json_err!("Could not revoke calendar feed")
+        }
+    }
+}
@@ -1,5 +1,11 @@
-use std::env;
+use std::{collections::HashMap, env};
 
+use postman::{
+    audit::{self, AuditAction, AuditSource, ReminderSnapshot},
+    guild_log::{self, LogEvent},
+    names,
+    selector::Selector,
+};
 use rocket::{
     http::CookieJar,
     serde::json::{json, Json},
@@ -18,14 +24,15 @@ use sqlx::{MySql, Pool};
 use crate::{
     check_guild_subscription, check_subscription,
     consts::{
-        MAX_CONTENT_LENGTH, MAX_EMBED_AUTHOR_LENGTH, MAX_EMBED_DESCRIPTION_LENGTH,
-        MAX_EMBED_FIELDS, MAX_EMBED_FIELD_TITLE_LENGTH, MAX_EMBED_FIELD_VALUE_LENGTH,
-        MAX_EMBED_FOOTER_LENGTH, MAX_EMBED_TITLE_LENGTH, MAX_URL_LENGTH, MAX_USERNAME_LENGTH,
-        MIN_INTERVAL,
+        BULK_ACTIONS, BUSINESS_DAY_POLICIES, EMBED_AUTHOR_MAX_LENGTH, EMBED_DESCRIPTION_MAX_LENGTH,
+        EMBED_FIELD_TITLE_MAX_LENGTH, EMBED_FIELD_VALUE_MAX_LENGTH, EMBED_FOOTER_MAX_LENGTH,
+        EMBED_MAX_FIELDS, EMBED_TITLE_MAX_LENGTH, INTERVAL_POLICIES, MAX_CONTENT_LENGTH,
+        MAX_URL_LENGTH, MAX_USERNAME_LENGTH, MIN_INTERVAL,
     },
     routes::dashboard::{
-        create_database_channel, create_reminder, template_name_default, DeleteReminder,
-        DeleteReminderTemplate, JsonResult, PatchReminder, Reminder, ReminderTemplate,
+        create_database_channel, create_reminder, fetch_reminder, fetch_reminders,
+        template_name_default, BulkReminders, DeleteReminder, DeleteReminderTemplate, JsonResult,
+        PatchReminder, Reminder, ReminderTemplate, SendReminder,
     },
 };
 
@@ -35,6 +42,8 @@ struct ChannelInfo {
     name: String,
     webhook_avatar: Option<String>,
     webhook_name: Option<String>,
+    /// Why the channel's webhook is missing and couldn't be recreated
+    webhook_error: Option<String>,
 }
 
 #[get("/api/guild/<id>/patreon")]
@@ -69,11 +78,33 @@ pub async fn get_guild_channels(
     id: u64,
     cookies: &CookieJar<'_>,
     ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
 ) -> JsonResult {
     check_authorization!(cookies, ctx.inner(), id);
 
     match GuildId(id).to_guild_cached(ctx.inner()) {
         Some(guild) => {
+            // errors are only current while the channel has no webhook
+            let webhook_errors = sqlx::query!(
+                "SELECT channel, webhook_error FROM channels
+                WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?)
+                    AND webhook_id IS NULL
+                    AND webhook_error IS NOT NULL",
+                id
+            )
+            .fetch_all(pool.inner())
+            .await
+            .map(|rows| {
+                rows.into_iter()
+                    .filter_map(|row| row.webhook_error.map(|error| (row.channel, error)))
+                    .collect::<HashMap<u64, String>>()
+            })
+            .unwrap_or_else(|e| {
+                warn!("Could not fetch webhook errors for {}: {:?}", id, e);
+
+                HashMap::new()
+            });
+
             let mut channels = guild
                 .channels
                 .iter()
@@ -90,6 +121,7 @@ pub async fn get_guild_channels(
                     id: channel_id.to_string(),
                     webhook_avatar: None,
                     webhook_name: None,
+                    webhook_error: webhook_errors.get(&channel_id.0).cloned(),
                 })
                 .collect::<Vec<ChannelInfo>>();
 
@@ -129,6 +161,63 @@ pub async fn get_guild_roles(id: u64, cookies: &CookieJar<'_>, ctx: &State<Conte
     }
 }
 
+/// Timezones configured for the guild and its channels. `timezone` is the timezone that times in
+/// the guild are read in by default: the guild's timezone, or the user's timezone if not set
+#[get("/api/guild/<id>/timezone")]
+pub async fn get_guild_timezone(
+    id: u64,
+    cookies: &CookieJar<'_>,
+    ctx: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, ctx.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+
+    let guild_timezone = sqlx::query!("SELECT timezone FROM guilds WHERE guild = ?", id)
+        .fetch_one(pool.inner())
+        .await
+        .ok()
+        .and_then(|r| r.timezone);
+
+    let user_timezone = sqlx::query!(
+        "SELECT IFNULL(timezone, 'UTC') AS timezone FROM users WHERE user = ?",
+        user_id
+    )
+    .fetch_one(pool.inner())
+    .await
+    .map_or("UTC".to_string(), |r| r.timezone);
+
+    match sqlx::query!(
+        "SELECT channel, timezone FROM channels
+        WHERE guild_id = (SELECT id FROM guilds WHERE guild = ?) AND timezone IS NOT NULL",
+        id
+    )
+    .fetch_all(pool.inner())
+    .await
+    {
+        Ok(rows) => {
+            let channels = rows
+                .into_iter()
+                .filter_map(|r| r.timezone.map(|t| (r.channel.to_string(), t)))
+                .collect::<HashMap<String, String>>();
+
+            Ok(json!({
+                "timezone": guild_timezone.clone().unwrap_or(user_timezone),
+                "guild": guild_timezone,
+                "channels": channels,
+            }))
+        }
+
+        Err(e) => {
+            warn!("Could not fetch channel timezones from {}: {:?}", id, e);
+
+            json_err!("Could not get timezones")
+        }
+    }
+}
+
 #[get("/api/guild/<id>/templates")]
 pub async fn get_reminder_templates(
     id: u64,
@@ -167,15 +256,15 @@ pub async fn create_reminder_template(
 
     // validate lengths
     check_length!(MAX_CONTENT_LENGTH, reminder_template.content);
-    check_length!(MAX_EMBED_DESCRIPTION_LENGTH, reminder_template.embed_description);
-    check_length!(MAX_EMBED_TITLE_LENGTH, reminder_template.embed_title);
-    check_length!(MAX_EMBED_AUTHOR_LENGTH, reminder_template.embed_author);
-    check_length!(MAX_EMBED_FOOTER_LENGTH, reminder_template.embed_footer);
-    check_length_opt!(MAX_EMBED_FIELDS, reminder_template.embed_fields);
+    check_length!(EMBED_DESCRIPTION_MAX_LENGTH, reminder_template.embed_description);
+    check_length!(EMBED_TITLE_MAX_LENGTH, reminder_template.embed_title);
+    check_length!(EMBED_AUTHOR_MAX_LENGTH, reminder_template.embed_author);
+    check_length!(EMBED_FOOTER_MAX_LENGTH, reminder_template.embed_footer);
+    check_length_opt!(EMBED_MAX_FIELDS, reminder_template.embed_fields);
     if let Some(fields) = &reminder_template.embed_fields {
         for field in &fields.0 {
-            check_length!(MAX_EMBED_FIELD_VALUE_LENGTH, field.value);
-            check_length!(MAX_EMBED_FIELD_TITLE_LENGTH, field.title);
+            check_length!(EMBED_FIELD_VALUE_MAX_LENGTH, field.value);
+            check_length!(EMBED_FIELD_TITLE_MAX_LENGTH, field.title);
         }
     }
     check_length_opt!(MAX_USERNAME_LENGTH, reminder_template.username);
@@ -303,6 +392,7 @@ pub async fn create_guild_reminder(
         GuildId(id),
         UserId(user_id),
         reminder.into_inner(),
+        AuditSource::Dashboard,
     )
     .await
 }
@@ -326,6 +416,7 @@ pub async fn get_reminders(id: u64, ctx: &State<Context>, pool: &State<Pool<MySq
                  reminders.attachment,
                  reminders.attachment_name,
                  reminders.avatar,
+                 reminders.business_day_policy,
                  channels.channel,
                  reminders.content,
                  reminders.embed_author,
@@ -343,8 +434,10 @@ pub async fn get_reminders(id: u64, ctx: &State<Context>, pool: &State<Pool<MySq
                  reminders.interval_seconds,
                  reminders.interval_days,
                  reminders.interval_months,
+                 reminders.interval_policy,
                  reminders.name,
                  reminders.restartable,
+                 reminders.timezone,
                  reminders.tts,
                  reminders.uid,
                  reminders.username,
@@ -386,6 +479,18 @@ pub async fn edit_reminder(
     let user_id =
         cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
 
+    let previous = match fetch_reminder(pool.inner(), &reminder.uid).await {
+        Ok(previous) => previous,
+
+        Err(e) => {
+            warn!("Error in `edit_reminder`: {:?}", e);
+
+            return Err(json!({"error": "Reminder not found"}));
+        }
+    };
+
+    let old = ReminderSnapshot::of_uid(pool.inner(), &reminder.uid).await;
+
     if reminder.message_ok() {
         update_field!(pool.inner(), error, reminder.[
             content,
@@ -411,12 +516,58 @@ pub async fn edit_reminder(
         embed_thumbnail_url,
         enabled,
         expires,
-        name,
         restartable,
         tts,
         utc_time
     ]);
 
+    if let Some(name) = &reminder.name {
+        let taken =
+            match sqlx::query!("SELECT channel_id FROM reminders WHERE uid = ?", reminder.uid)
+                .fetch_one(pool.inner())
+                .await
+            {
+                Ok(row) => {
+                    names::is_taken(pool.inner(), row.channel_id, name, Some(&reminder.uid)).await
+                }
+
+                Err(e) => Err(e),
+            };
+
+        match taken {
+            Ok(false) => update_field!(pool.inner(), error, reminder.[name]),
+
+            Ok(true) => {
+                error.push("Another reminder in the channel already has that name".to_string())
+            }
+
+            Err(e) => {
+                warn!("Error checking reminder name: {:?}", e);
+
+                error.push("Couldn't set name".to_string())
+            }
+        }
+    }
+
+    if let Some(interval_policy) = &reminder.interval_policy {
+        if INTERVAL_POLICIES.contains(&interval_policy.as_str()) {
+            update_field!(pool.inner(), error, reminder.[interval_policy]);
+        } else {
+            error.push("Interval policy must be one of \"wall_clock\" or \"elapsed\"".to_string());
+        }
+    }
+
+    if let Some(business_day_policy) = &reminder.business_day_policy {
+        if BUSINESS_DAY_POLICIES.contains(&business_day_policy.as_str()) {
+            update_field!(pool.inner(), error, reminder.[business_day_policy]);
+        } else {
+            error.push(
+                "Business day policy must be one of \"ignore\", \"skip\", \"next\" or \"previous\""
+                    .to_string(),
+            );
+        }
+    }
+
     if reminder.interval_days.flatten().is_some()
         || reminder.interval_months.flatten().is_some()
         || reminder.interval_seconds.flatten().is_some()
@@ -511,6 +662,23 @@ pub async fn edit_reminder(
                 }
 
                 let channel = channel.unwrap();
+                let name = reminder.name.as_ref().unwrap_or(&previous.name);
+
+                match names::is_taken(pool.inner(), channel, name, Some(&reminder.uid)).await {
+                    Ok(false) => {}
+
+                    Ok(true) => {
+                        return Err(json!({
+                            "error": "Another reminder in that channel already has this name"
+                        }));
+                    }
+
+                    Err(e) => {
+                        warn!("Error checking reminder name: {:?}", e);
+
+                        return Err(json!({"error": "Couldn't set channel"}));
+                    }
+                }
 
                 match sqlx::query!(
                     "UPDATE reminders SET channel_id = ? WHERE uid = ?",
@@ -540,43 +708,43 @@ pub async fn edit_reminder(
         }
     }
 
-    match sqlx::query_as_unchecked!(
-        Reminder,
-        "SELECT reminders.attachment,
-         reminders.attachment_name,
-         reminders.avatar,
-         channels.channel,
-         reminders.content,
-         reminders.embed_author,
-         reminders.embed_author_url,
-         reminders.embed_color,
-         reminders.embed_description,
-         reminders.embed_footer,
-         reminders.embed_footer_url,
-         reminders.embed_image_url,
-         reminders.embed_thumbnail_url,
-         reminders.embed_title,
-         reminders.embed_fields,
-         reminders.enabled,
-         reminders.expires,
-         reminders.interval_seconds,
-         reminders.interval_days,
-         reminders.interval_months,
-         reminders.name,
-         reminders.restartable,
-         reminders.tts,
-         reminders.uid,
-         reminders.username,
-         reminders.utc_time
-        FROM reminders
-        LEFT JOIN channels ON channels.id = reminders.channel_id
-        WHERE uid = ?",
-        reminder.uid
-    )
-    .fetch_one(pool.inner())
-    .await
-    {
-        Ok(reminder) => Ok(json!({"reminder": reminder, "errors": error})),
+    match fetch_reminder(pool.inner(), &reminder.uid).await {
+        Ok(reminder) => {
+            let new = ReminderSnapshot::of_uid(pool.inner(), &reminder.uid).await;
+            let changed = audit::diff(old.as_ref(), new.as_ref())
+                .as_object()
+                .map(|changes| changes.keys().cloned().collect::<Vec<String>>())
+                .unwrap_or_default();
+
+            audit::record(
+                pool.inner(),
+                user_id,
+                AuditSource::Dashboard,
+                AuditAction::Edit,
+                old.as_ref(),
+                new.as_ref(),
+            )
+            .await;
+
+            if !changed.is_empty() {
+                guild_log::post(
+                    serenity_context.inner(),
+                    pool.inner(),
+                    id,
+                    LogEvent::Edited,
+                    format!(
+                        "\"{}\" for <#{}> edited by <@{}> on the dashboard: {}",
+                        reminder.name,
+                        reminder.channel,
+                        user_id,
+                        changed.join(", ")
+                    ),
+                )
+                .await;
+            }
+
+            Ok(json!({"reminder": reminder, "errors": error}))
+        }
 
         Err(e) => {
             warn!("Error exiting `edit_reminder': {:?}", e);
@@ -586,16 +754,60 @@ pub async fn edit_reminder(
     }
 }
 
-#[delete("/api/guild/<_>/reminders", data = "<reminder>")]
+#[delete("/api/guild/<id>/reminders", data = "<reminder>")]
 pub async fn delete_reminder(
+    id: u64,
     reminder: Json<DeleteReminder>,
+    cookies: &CookieJar<'_>,
+    serenity_context: &State<Context>,
     pool: &State<Pool<MySql>>,
 ) -> JsonResult {
+    check_authorization!(cookies, serenity_context.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+
+    let previous = match fetch_reminder(pool.inner(), &reminder.uid).await {
+        Ok(previous) => previous,
+
+        Err(e) => {
+            warn!("Error in `delete_reminder`: {:?}", e);
+
+            return Err(json!({"error": "Could not delete reminder"}));
+        }
+    };
+
+    let old = ReminderSnapshot::of_uid(pool.inner(), &reminder.uid).await;
+
     match sqlx::query!("DELETE FROM reminders WHERE uid = ?", reminder.uid)
         .execute(pool.inner())
         .await
     {
-        Ok(_) => Ok(json!({})),
+        Ok(_) => {
+            audit::record(
+                pool.inner(),
+                user_id,
+                AuditSource::Dashboard,
+                AuditAction::Delete,
+                old.as_ref(),
+                None,
+            )
+            .await;
+
+            guild_log::post(
+                serenity_context.inner(),
+                pool.inner(),
+                id,
+                LogEvent::Deleted,
+                format!(
+                    "\"{}\" for <#{}> deleted by <@{}> on the dashboard",
+                    previous.name, previous.channel, user_id
+                ),
+            )
+            .await;
+
+            Ok(json!({}))
+        }
 
         Err(e) => {
             warn!("Error in `delete_reminder`: {:?}", e);
@@ -604,3 +816,203 @@ pub async fn delete_reminder(
         }
     }
 }
+
+/// Send a reminder now, to its own channel or to `channel` if one is given
+#[post("/api/guild/<id>/reminders/<uid>/send", data = "<send>")]
+pub async fn send_reminder(
+    id: u64,
+    uid: String,
+    send: Json<SendReminder>,
+    cookies: &CookieJar<'_>,
+    serenity_context: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, serenity_context.inner(), id);
+
+    let reminder_id = match sqlx::query!(
+        "SELECT reminders.id FROM reminders
+        INNER JOIN channels ON channels.id = reminders.channel_id
+        WHERE reminders.uid = ? AND channels.guild_id = (SELECT id FROM guilds WHERE guild = ?)",
+        uid,
+        id
+    )
+    .fetch_one(pool.inner())
+    .await
+    {
+        Ok(row) => row.id,
+
+        Err(e) => {
+            warn!("Error in `send_reminder`: {:?}", e);
+
+            return json_err!("Reminder not found");
+        }
+    };
+
+    let channel = if send.channel > 0 {
+        let channel_matches_guild = ChannelId(send.channel)
+            .to_channel_cached(&serenity_context.inner())
+            .and_then(|c| c.guild())
+            .map_or(false, |c| c.guild_id.0 == id);
+
+        if !channel_matches_guild {
+            return json_err!("Channel not found");
+        }
+
+        Some(send.channel)
+    } else {
+        None
+    };
+
+    match postman::send_now(pool.inner(), serenity_context.inner(), reminder_id, channel).await {
+        Ok(()) => Ok(json!({})),
+
+        Err(e) => json_err!(format!("Reminder could not be sent: {}", e)),
+    }
+}
+
+/// Enable, disable, delete or move the reminders matched by a selector. With `preview` set, the
+/// matched reminders are listed without being changed
+#[post("/api/guild/<id>/reminders/bulk", data = "<bulk>")]
+pub async fn bulk_reminders(
+    id: u64,
+    bulk: Json<BulkReminders>,
+    cookies: &CookieJar<'_>,
+    serenity_context: &State<Context>,
+    pool: &State<Pool<MySql>>,
+) -> JsonResult {
+    check_authorization!(cookies, serenity_context.inner(), id);
+
+    let user_id =
+        cookies.get_private("userid").map(|c| c.value().parse::<u64>().ok()).flatten().unwrap();
+
+    if !BULK_ACTIONS.contains(&bulk.action.as_str()) {
+        return json_err!(format!("Action must be one of: {}", BULK_ACTIONS.join(", ")));
+    }
+
+    if bulk.action == "offset" && bulk.seconds == 0 {
+        return json_err!("Offset requires a number of seconds");
+    }
+
+    let timezone = match &bulk.timezone {
+        Some(timezone) => timezone.clone(),
+
+        None => sqlx::query!("SELECT timezone FROM guilds WHERE guild = ?", id)
+            .fetch_optional(pool.inner())
+            .await
+            .ok()
+            .flatten()
+            .and_then(|row| row.timezone)
+            .unwrap_or_else(|| "UTC".to_string()),
+    };
+
+    let selector = match Selector::parse(&bulk.selector, &timezone) {
+        Ok(selector) => selector,
+
+        Err(e) => return json_err!(e),
+    };
+
+    let ids = match selector.matching(pool.inner(), id).await {
+        Ok(ids) => ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(","),
+
+        Err(e) => {
+            warn!("Could not select reminders for {}: {:?}", id, e);
+
+            return json_err!("Could not select reminders");
+        }
+    };
+
+    let previous = match fetch_reminders(pool.inner(), &ids).await {
+        Ok(reminders) => reminders,
+
+        Err(e) => {
+            warn!("Could not select reminders for {}: {:?}", id, e);
+
+            return json_err!("Could not select reminders");
+        }
+    };
+
+    if bulk.preview {
+        return Ok(json!({
+            "count": previous.len(),
+            "reminders": previous
+                .iter()
+                .map(|reminder| json!({
+                    "uid": reminder.uid,
+                    "name": reminder.name,
+                    "channel": reminder.channel.to_string(),
+                    "utc_time": reminder.utc_time,
+                }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    let old = ReminderSnapshot::of(pool.inner(), &ids).await;
+
+    let result = match bulk.action.as_str() {
+        "enable" | "disable" => {
+            sqlx::query!(
+                "UPDATE reminders SET enabled = ? WHERE FIND_IN_SET(id, ?)",
+                bulk.action == "enable",
+                ids
+            )
+            .execute(pool.inner())
+            .await
+        }
+
+        "delete" => {
+            sqlx::query!("DELETE FROM reminders WHERE FIND_IN_SET(id, ?)", ids)
+                .execute(pool.inner())
+                .await
+        }
+
+        _ => {
+            sqlx::query!(
+                "UPDATE reminders SET utc_time = DATE_ADD(utc_time, INTERVAL ? SECOND)
+                WHERE FIND_IN_SET(id, ?)",
+                bulk.seconds,
+                ids
+            )
+            .execute(pool.inner())
+            .await
+        }
+    };
+
+    if let Err(e) = result {
+        warn!("Could not apply bulk change for {}: {:?}", id, e);
+
+        return json_err!("Could not change reminders");
+    }
+
+    let new = ReminderSnapshot::of(pool.inner(), &ids).await;
+    let action = if bulk.action == "delete" { AuditAction::Delete } else { AuditAction::Edit };
+
+    audit::record_all(pool.inner(), user_id, AuditSource::Dashboard, action, &old, &new).await;
+
+    let (event, done) = match bulk.action.as_str() {
+        "enable" => (LogEvent::Edited, "enabled".to_string()),
+        "disable" => (LogEvent::Edited, "disabled".to_string()),
+        "delete" => (LogEvent::Deleted, "deleted".to_string()),
+        _ => (LogEvent::Offset, format!("moved by {} seconds", bulk.seconds)),
+    };
+
+    guild_log::post(
+        serenity_context.inner(),
+        pool.inner(),
+        id,
+        event,
+        format!(
+            "{} reminders {} by <@{}> on the dashboard, selecting {}",
+            previous.len(),
+            done,
+            user_id,
+            if bulk.selector.trim().is_empty() {
+                "all reminders".to_string()
+            } else {
+                format!("`{}`", bulk.selector.trim())
+            }
+        ),
+    )
+    .await;
+
+    Ok(json!({"count": previous.len()}))
+}
@@ -1,6 +1,12 @@
 use std::collections::HashMap;
 
 use chrono::{naive::NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use postman::{
+    audit::{AuditAction, AuditSource, ReminderSnapshot},
+    guild_log::{self, LogEvent},
+    names,
+};
 use rand::{rngs::OsRng, seq::IteratorRandom};
 use rocket::{
     http::CookieJar,
@@ -14,20 +20,25 @@ use serenity::{
     http::Http,
     model::id::{ChannelId, GuildId, UserId},
 };
-use sqlx::{types::Json, Executor};
+use sqlx::{types::Json, Acquire, Executor};
 
 use crate::{
     check_guild_subscription, check_subscription,
     consts::{
-        CHARACTERS, DAY, DEFAULT_AVATAR, MAX_CONTENT_LENGTH, MAX_EMBED_AUTHOR_LENGTH,
-        MAX_EMBED_DESCRIPTION_LENGTH, MAX_EMBED_FIELDS, MAX_EMBED_FIELD_TITLE_LENGTH,
-        MAX_EMBED_FIELD_VALUE_LENGTH, MAX_EMBED_FOOTER_LENGTH, MAX_EMBED_TITLE_LENGTH,
-        MAX_URL_LENGTH, MAX_USERNAME_LENGTH, MIN_INTERVAL,
+        BUSINESS_DAY_POLICIES, CHARACTERS, DAY, DEFAULT_AVATAR, EMBED_AUTHOR_MAX_LENGTH,
+        EMBED_DESCRIPTION_MAX_LENGTH, EMBED_FIELD_TITLE_MAX_LENGTH, EMBED_FIELD_VALUE_MAX_LENGTH,
+        EMBED_FOOTER_MAX_LENGTH, EMBED_MAX_FIELDS, EMBED_TITLE_MAX_LENGTH, INTERVAL_POLICIES,
+        MAX_CONTENT_LENGTH, MAX_URL_LENGTH, MAX_USERNAME_LENGTH, MIN_INTERVAL,
     },
     Database, Error,
 };
 
+pub mod audit;
+pub mod backup;
+pub mod calendar;
+pub mod command_macro;
 pub mod export;
+pub mod feed;
 pub mod guild;
 pub mod user;
 
@@ -54,6 +65,18 @@ fn interval_default() -> Unset<Option<u32>> {
     None
 }
 
+fn interval_policy_default() -> String {
+    "wall_clock".to_string()
+}
+
+fn business_day_policy_default() -> String {
+    "ignore".to_string()
+}
+
+fn timezone_default() -> String {
+    "UTC".to_string()
+}
+
 fn deserialize_optional_field<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
 where
     D: Deserializer<'de>,
@@ -128,6 +151,8 @@ pub struct Reminder {
     attachment: Option<Vec<u8>>,
     attachment_name: Option<String>,
     avatar: Option<String>,
+    #[serde(default = "business_day_policy_default")]
+    business_day_policy: String,
     #[serde(with = "string")]
     channel: u64,
     content: String,
@@ -146,9 +171,13 @@ pub struct Reminder {
     interval_seconds: Option<u32>,
     interval_days: Option<u32>,
     interval_months: Option<u32>,
+    #[serde(default = "interval_policy_default")]
+    interval_policy: String,
     #[serde(default = "name_default")]
     name: String,
     restartable: bool,
+    #[serde(default = "timezone_default")]
+    timezone: String,
     tts: bool,
     #[serde(default)]
     uid: String,
@@ -162,6 +191,8 @@ pub struct ReminderCsv {
     attachment: Option<Vec<u8>>,
     attachment_name: Option<String>,
     avatar: Option<String>,
+    #[serde(default = "business_day_policy_default")]
+    business_day_policy: String,
     channel: String,
     content: String,
     embed_author: String,
@@ -179,9 +210,13 @@ pub struct ReminderCsv {
     interval_seconds: Option<u32>,
     interval_days: Option<u32>,
     interval_months: Option<u32>,
+    #[serde(default = "interval_policy_default")]
+    interval_policy: String,
     #[serde(default = "name_default")]
     name: String,
     restartable: bool,
+    #[serde(default = "timezone_default")]
+    timezone: String,
     tts: bool,
     username: Option<String>,
     utc_time: NaiveDateTime,
@@ -199,6 +234,8 @@ pub struct PatchReminder {
     #[serde(default)]
     #[serde(deserialize_with = "deserialize_optional_field")]
     avatar: Unset<Option<String>>,
+    #[serde(default)]
+    business_day_policy: Unset<String>,
     #[serde(default = "channel_default")]
     #[serde(with = "string")]
     channel: u64,
@@ -243,6 +280,8 @@ pub struct PatchReminder {
     #[serde(deserialize_with = "deserialize_optional_field")]
     interval_months: Unset<Option<u32>>,
     #[serde(default)]
+    interval_policy: Unset<String>,
+    #[serde(default)]
     name: Unset<String>,
     #[serde(default)]
     restartable: Unset<bool>,
@@ -258,18 +297,18 @@ pub struct PatchReminder {
 impl PatchReminder {
     fn message_ok(&self) -> bool {
         self.content.as_ref().map_or(true, |c| c.len() <= MAX_CONTENT_LENGTH)
-            && self.embed_author.as_ref().map_or(true, |c| c.len() <= MAX_EMBED_AUTHOR_LENGTH)
+            && self.embed_author.as_ref().map_or(true, |c| c.len() <= EMBED_AUTHOR_MAX_LENGTH)
             && self
                 .embed_description
                 .as_ref()
-                .map_or(true, |c| c.len() <= MAX_EMBED_DESCRIPTION_LENGTH)
-            && self.embed_footer.as_ref().map_or(true, |c| c.len() <= MAX_EMBED_FOOTER_LENGTH)
-            && self.embed_title.as_ref().map_or(true, |c| c.len() <= MAX_EMBED_TITLE_LENGTH)
+                .map_or(true, |c| c.len() <= EMBED_DESCRIPTION_MAX_LENGTH)
+            && self.embed_footer.as_ref().map_or(true, |c| c.len() <= EMBED_FOOTER_MAX_LENGTH)
+            && self.embed_title.as_ref().map_or(true, |c| c.len() <= EMBED_TITLE_MAX_LENGTH)
             && self.embed_fields.as_ref().map_or(true, |c| {
-                c.0.len() <= MAX_EMBED_FIELDS
+                c.0.len() <= EMBED_MAX_FIELDS
                     && c.0.iter().all(|f| {
-                        f.title.len() <= MAX_EMBED_FIELD_TITLE_LENGTH
-                            && f.value.len() <= MAX_EMBED_FIELD_VALUE_LENGTH
+                        f.title.len() <= EMBED_FIELD_TITLE_MAX_LENGTH
+                            && f.value.len() <= EMBED_FIELD_VALUE_MAX_LENGTH
                     })
             })
             && self
@@ -340,6 +379,29 @@ pub struct DeleteReminder {
     uid: String,
 }
 
+#[derive(Deserialize)]
+pub struct BulkReminders {
+    /// Selector for the reminders to change, e.g. `channel:<#123> name:standup* enabled:yes`
+    selector: String,
+    action: String,
+    /// Seconds to move reminders by, for the offset action
+    #[serde(default)]
+    seconds: i64,
+    /// Timezone of dates in the selector. Defaults to the guild's timezone, then UTC
+    timezone: Option<String>,
+    /// List the reminders that would be changed without changing them
+    #[serde(default)]
+    preview: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SendReminder {
+    /// Channel to send the reminder to instead of its own, e.g. to test it. 0 for its own channel
+    #[serde(default = "channel_default")]
+    #[serde(with = "string")]
+    channel: u64,
+}
+
 #[derive(Deserialize)]
 pub struct ImportBody {
     body: String,
@@ -351,30 +413,13 @@ pub struct TodoCsv {
     channel_id: Option<String>,
 }
 
-pub async fn create_reminder(
+/// Check that a reminder could be created, without writing anything
+pub async fn validate_reminder(
     ctx: &Context,
-    pool: impl sqlx::Executor<'_, Database = Database> + Copy,
     guild_id: GuildId,
     user_id: UserId,
-    reminder: Reminder,
-) -> JsonResult {
-    // check guild in db
-    match sqlx::query!("SELECT 1 as A FROM guilds WHERE guild = ?", guild_id.0)
-        .fetch_one(pool)
-        .await
-    {
-        Err(sqlx::Error::RowNotFound) => {
-            if sqlx::query!("INSERT INTO guilds (guild) VALUES (?)", guild_id.0)
-                .execute(pool)
-                .await
-                .is_err()
-            {
-                return Err(json!({"error": "Guild could not be created"}));
-            }
-        }
-        _ => {}
-    }
-
+    reminder: &Reminder,
+) -> Result<(), JsonValue> {
     // validate channel
     let channel = ChannelId(reminder.channel).to_channel_cached(&ctx);
     let channel_exists = channel.is_some();
@@ -384,36 +429,24 @@ pub async fn create_reminder(
 
     if !channel_matches_guild || !channel_exists {
         warn!(
-            "Error in `create_reminder`: channel {} not found for guild {} (channel exists: {})",
+            "Error in `validate_reminder`: channel {} not found for guild {} (channel exists: {})",
             reminder.channel, guild_id, channel_exists
         );
 
         return Err(json!({"error": "Channel not found"}));
     }
 
-    let channel = create_database_channel(&ctx, ChannelId(reminder.channel), pool).await;
-
-    if let Err(e) = channel {
-        warn!("`create_database_channel` returned an error code: {:?}", e);
-
-        return Err(
-            json!({"error": "Failed to configure channel for reminders. Please check the bot permissions"}),
-        );
-    }
-
-    let channel = channel.unwrap();
-
     // validate lengths
     check_length!(MAX_CONTENT_LENGTH, reminder.content);
-    check_length!(MAX_EMBED_DESCRIPTION_LENGTH, reminder.embed_description);
-    check_length!(MAX_EMBED_TITLE_LENGTH, reminder.embed_title);
-    check_length!(MAX_EMBED_AUTHOR_LENGTH, reminder.embed_author);
-    check_length!(MAX_EMBED_FOOTER_LENGTH, reminder.embed_footer);
-    check_length_opt!(MAX_EMBED_FIELDS, reminder.embed_fields);
+    check_length!(EMBED_DESCRIPTION_MAX_LENGTH, reminder.embed_description);
+    check_length!(EMBED_TITLE_MAX_LENGTH, reminder.embed_title);
+    check_length!(EMBED_AUTHOR_MAX_LENGTH, reminder.embed_author);
+    check_length!(EMBED_FOOTER_MAX_LENGTH, reminder.embed_footer);
+    check_length_opt!(EMBED_MAX_FIELDS, reminder.embed_fields);
     if let Some(fields) = &reminder.embed_fields {
         for field in &fields.0 {
-            check_length!(MAX_EMBED_FIELD_VALUE_LENGTH, field.value);
-            check_length!(MAX_EMBED_FIELD_TITLE_LENGTH, field.title);
+            check_length!(EMBED_FIELD_VALUE_MAX_LENGTH, field.value);
+            check_length!(EMBED_FIELD_TITLE_MAX_LENGTH, field.title);
         }
     }
     check_length_opt!(MAX_USERNAME_LENGTH, reminder.username);
@@ -451,6 +484,19 @@ pub async fn create_reminder(
             return Err(json!({"error": "Interval too short"}));
         }
     }
+    if !INTERVAL_POLICIES.contains(&reminder.interval_policy.as_str()) {
+        return Err(
+            json!({"error": "Interval policy must be one of \"wall_clock\" or \"elapsed\""}),
+        );
+    }
+    if reminder.timezone.parse::<Tz>().is_err() {
+        return Err(json!({"error": "Timezone not recognised"}));
+    }
+    if !BUSINESS_DAY_POLICIES.contains(&reminder.business_day_policy.as_str()) {
+        return Err(json!({
+            "error": "Business day policy must be one of \"ignore\", \"skip\", \"next\" or \"previous\""
+        }));
+    }
 
     // check patreon if necessary
     if reminder.interval_seconds.is_some()
@@ -464,9 +510,67 @@ pub async fn create_reminder(
         }
     }
 
+    Ok(())
+}
+
+pub async fn create_reminder(
+    ctx: &Context,
+    db: impl Acquire<'_, Database = Database>,
+    guild_id: GuildId,
+    user_id: UserId,
+    reminder: Reminder,
+    source: AuditSource,
+) -> JsonResult {
+    validate_reminder(ctx, guild_id, user_id, &reminder).await?;
+
+    let mut conn = db.acquire().await.map_err(|e| {
+        warn!("Error in `create_reminder`: Could not acquire connection: {:?}", e);
+
+        json!({"error": "Unknown error"})
+    })?;
+
+    // check guild in db
+    match sqlx::query!("SELECT 1 as A FROM guilds WHERE guild = ?", guild_id.0)
+        .fetch_one(&mut *conn)
+        .await
+    {
+        Err(sqlx::Error::RowNotFound) => {
+            if sqlx::query!("INSERT INTO guilds (guild) VALUES (?)", guild_id.0)
+                .execute(&mut *conn)
+                .await
+                .is_err()
+            {
+                return Err(json!({"error": "Guild could not be created"}));
+            }
+        }
+        _ => {}
+    }
+
+    let channel = create_database_channel(&ctx, ChannelId(reminder.channel), &mut *conn).await;
+
+    if let Err(e) = channel {
+        warn!("`create_database_channel` returned an error code: {:?}", e);
+
+        return Err(
+            json!({"error": "Failed to configure channel for reminders. Please check the bot permissions"}),
+        );
+    }
+
+    let channel = channel.unwrap();
+
+    // names are unique in a channel, so a name that is taken is numbered
+    let name = match names::free_name(&mut *conn, channel, &reminder.name).await {
+        Ok(name) => name,
+
+        Err(e) => {
+            warn!("Error in `create_reminder`: Could not name reminder: {:?}", e);
+
+            return Err(json!({"error": "Could not name reminder"}));
+        }
+    };
+
     // base64 decode error dropped here
     let attachment_data = reminder.attachment.as_ref().map(|s| base64::decode(s).ok()).flatten();
-    let name = if reminder.name.is_empty() { name_default() } else { reminder.name.clone() };
     let username = if reminder.username.as_ref().map(|s| s.is_empty()).unwrap_or(true) {
         None
     } else {
@@ -483,6 +587,7 @@ pub async fn create_reminder(
          attachment_name,
          channel_id,
          avatar,
+         business_day_policy,
          content,
          embed_author,
          embed_author_url,
@@ -499,17 +604,20 @@ pub async fn create_reminder(
          interval_seconds,
          interval_days,
          interval_months,
+         interval_policy,
          name,
          restartable,
+         timezone,
          tts,
          username,
          `utc_time`
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         new_uid,
         attachment_data,
         reminder.attachment_name,
         channel,
         reminder.avatar,
+        reminder.business_day_policy,
         reminder.content,
         reminder.embed_author,
         reminder.embed_author_url,
@@ -526,57 +634,54 @@ pub async fn create_reminder(
         reminder.interval_seconds,
         reminder.interval_days,
         reminder.interval_months,
+        reminder.interval_policy,
         name,
         reminder.restartable,
+        reminder.timezone,
         reminder.tts,
         username,
         reminder.utc_time,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     {
-        Ok(_) => sqlx::query_as_unchecked!(
-            Reminder,
-            "SELECT
-             reminders.attachment,
-             reminders.attachment_name,
-             reminders.avatar,
-             channels.channel,
-             reminders.content,
-             reminders.embed_author,
-             reminders.embed_author_url,
-             reminders.embed_color,
-             reminders.embed_description,
-             reminders.embed_footer,
-             reminders.embed_footer_url,
-             reminders.embed_image_url,
-             reminders.embed_thumbnail_url,
-             reminders.embed_title,
-             reminders.embed_fields,
-             reminders.enabled,
-             reminders.expires,
-             reminders.interval_seconds,
-             reminders.interval_days,
-             reminders.interval_months,
-             reminders.name,
-             reminders.restartable,
-             reminders.tts,
-             reminders.uid,
-             reminders.username,
-             reminders.utc_time
-            FROM reminders
-            LEFT JOIN channels ON channels.id = reminders.channel_id
-            WHERE uid = ?",
-            new_uid
-        )
-        .fetch_one(pool)
-        .await
-        .map(|r| Ok(json!(r)))
-        .unwrap_or_else(|e| {
-            warn!("Failed to complete SQL query: {:?}", e);
+        Ok(_) => match fetch_reminder(&mut *conn, &new_uid).await {
+            Ok(reminder) => {
+                let snapshot = ReminderSnapshot::of_uid(&mut *conn, &new_uid).await;
+                postman::audit::record(
+                    &mut *conn,
+                    user_id.0,
+                    source,
+                    AuditAction::Create,
+                    None,
+                    snapshot.as_ref(),
+                )
+                .await;
+
+                // Imports post a summary once finished, rather than a message for each reminder
+                if let AuditSource::Dashboard = source {
+                    guild_log::post(
+                        ctx,
+                        &mut *conn,
+                        guild_id.0,
+                        LogEvent::Created,
+                        format!(
+                            "\"{}\" for <#{}> created by <@{}> on the dashboard",
+                            reminder.name, reminder.channel, user_id
+                        ),
+                    )
+                    .await;
+                }
+
+                Ok(json!(reminder))
+            }
 
-            Err(json!({"error": "Could not load reminder"}))
-        }),
+            Err(e) => {
+                warn!("Failed to complete SQL query: {:?}", e);
+
+                Err(json!({"error": "Could not load reminder"}))
+            }
+        },
 
         Err(e) => {
             warn!("Error in `create_reminder`: Could not execute query: {:?}", e);
@@ -586,14 +691,108 @@ pub async fn create_reminder(
     }
 }
 
-async fn create_database_channel(
+/// Fetch a reminder in the form used by the API
+pub async fn fetch_reminder(
+    db: impl Executor<'_, Database = Database>,
+    uid: &str,
+) -> Result<Reminder, sqlx::Error> {
+    sqlx::query_as_unchecked!(
+        Reminder,
+        "SELECT
+         reminders.attachment,
+         reminders.attachment_name,
+         reminders.avatar,
+         reminders.business_day_policy,
+         channels.channel,
+         reminders.content,
+         reminders.embed_author,
+         reminders.embed_author_url,
+         reminders.embed_color,
+         reminders.embed_description,
+         reminders.embed_footer,
+         reminders.embed_footer_url,
+         reminders.embed_image_url,
+         reminders.embed_thumbnail_url,
+         reminders.embed_title,
+         reminders.embed_fields,
+         reminders.enabled,
+         reminders.expires,
+         reminders.interval_seconds,
+         reminders.interval_days,
+         reminders.interval_months,
+         reminders.interval_policy,
+         reminders.name,
+         reminders.restartable,
+         reminders.timezone,
+         reminders.tts,
+         reminders.uid,
+         reminders.username,
+         reminders.utc_time
+        FROM reminders
+        LEFT JOIN channels ON channels.id = reminders.channel_id
+        WHERE uid = ?",
+        uid
+    )
+    .fetch_one(db)
+    .await
+}
+
+/// Fetch reminders by a comma separated list of IDs, in the form used by the API
+pub async fn fetch_reminders(
+    db: impl Executor<'_, Database = Database>,
+    ids: &str,
+) -> Result<Vec<Reminder>, sqlx::Error> {
+    sqlx::query_as_unchecked!(
+        Reminder,
+        "SELECT
+         reminders.attachment,
+         reminders.attachment_name,
+         reminders.avatar,
+         reminders.business_day_policy,
+         channels.channel,
+         reminders.content,
+         reminders.embed_author,
+         reminders.embed_author_url,
+         reminders.embed_color,
+         reminders.embed_description,
+         reminders.embed_footer,
+         reminders.embed_footer_url,
+         reminders.embed_image_url,
+         reminders.embed_thumbnail_url,
+         reminders.embed_title,
+         reminders.embed_fields,
+         reminders.enabled,
+         reminders.expires,
+         reminders.interval_seconds,
+         reminders.interval_days,
+         reminders.interval_months,
+         reminders.interval_policy,
+         reminders.name,
+         reminders.restartable,
+         reminders.timezone,
+         reminders.tts,
+         reminders.uid,
+         reminders.username,
+         reminders.utc_time
+        FROM reminders
+        LEFT JOIN channels ON channels.id = reminders.channel_id
+        WHERE FIND_IN_SET(reminders.id, ?)",
+        ids
+    )
+    .fetch_all(db)
+    .await
+}
+
+pub(crate) async fn create_database_channel(
     ctx: impl AsRef<Http>,
     channel: ChannelId,
-    pool: impl Executor<'_, Database = Database> + Copy,
+    db: impl Acquire<'_, Database = Database>,
 ) -> Result<u32, crate::Error> {
+    let mut conn = db.acquire().await.map_err(|e| Error::SQLx(e))?;
+
     let row =
         sqlx::query!("SELECT webhook_token, webhook_id FROM channels WHERE channel = ?", channel.0)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await;
 
     match row {
@@ -610,7 +809,7 @@ async fn create_database_channel(
                     webhook.token,
                     channel.0
                 )
-                .execute(pool)
+                .execute(&mut *conn)
                 .await
                 .map_err(|e| Error::SQLx(e))?;
             }
@@ -636,7 +835,7 @@ async fn create_database_channel(
                 webhook.token,
                 channel.0
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| Error::SQLx(e))?;
 
@@ -647,7 +846,7 @@ async fn create_database_channel(
     }?;
 
     let row = sqlx::query!("SELECT id FROM channels WHERE channel = ?", channel.0)
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await
         .map_err(|e| Error::SQLx(e))?;
 
@@ -0,0 +1,185 @@
+//! Read-only calendar feeds of upcoming reminders, for calendar apps to subscribe to. Feeds are
+//! found by a secret token rather than a login, as calendar apps can't sign in with Discord
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use postman::recurrence::{BusinessDayPolicy, Calendar, IntervalPolicy, Recurrence};
+use rocket::{
+    http::{ContentType, Status},
+    State,
+};
+use sqlx::{MySql, Pool};
+
+use crate::{
+    consts::{FEED_HORIZON_DAYS, MAX_FEED_EVENTS},
+    ics::{self, Component, Property},
+};
+
+struct FeedReminder {
+    uid: String,
+    name: String,
+    content: String,
+    embed_description: String,
+    utc_time: NaiveDateTime,
+    timezone: String,
+    interval_seconds: Option<u32>,
+    interval_days: Option<u32>,
+    interval_months: Option<u32>,
+    interval_policy: String,
+    business_day_policy: String,
+    shifted_from: Option<NaiveDateTime>,
+    expires: Option<NaiveDateTime>,
+    guild_id: Option<u32>,
+    weekend_days: Option<u8>,
+}
+
+#[get("/<token>")]
+pub async fn calendar_feed(
+    token: &str,
+    pool: &State<Pool<MySql>>,
+) -> Result<(ContentType, String), Status> {
+    let feed = sqlx::query!(
+        "SELECT calendar_feeds.guild_id, calendar_feeds.user_id, guilds.name AS guild_name
+        FROM calendar_feeds
+        LEFT JOIN guilds ON guilds.id = calendar_feeds.guild_id
+        WHERE token = ?",
+        token
+    )
+    .fetch_one(pool.inner())
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => Status::NotFound,
+
+        e => {
+            warn!("Could not fetch calendar feed: {:?}", e);
+
+            Status::InternalServerError
+        }
+    })?;
+
+    // A guild feed lists the guild's reminders. A user feed lists the reminders the user set, and
+    // those sent to their DMs
+    let reminders = sqlx::query_as_unchecked!(
+        FeedReminder,
+        "SELECT
+         reminders.uid,
+         reminders.name,
+         reminders.content,
+         reminders.embed_description,
+         reminders.utc_time,
+         reminders.timezone,
+         reminders.interval_seconds,
+         reminders.interval_days,
+         reminders.interval_months,
+         reminders.interval_policy,
+         reminders.business_day_policy,
+         reminders.shifted_from,
+         reminders.expires,
+         channels.guild_id,
+         guilds.weekend_days
+        FROM reminders
+        INNER JOIN channels ON channels.id = reminders.channel_id
+        LEFT JOIN guilds ON guilds.id = channels.guild_id
+        WHERE reminders.enabled AND (
+            channels.guild_id = ? OR
+            reminders.set_by = ? OR
+            reminders.channel_id = (SELECT dm_channel FROM users WHERE id = ?)
+        )
+        ORDER BY reminders.utc_time",
+        feed.guild_id,
+        feed.user_id,
+        feed.user_id
+    )
+    .fetch_all(pool.inner())
+    .await
+    .map_err(|e| {
+        warn!("Could not fetch reminders for calendar feed: {:?}", e);
+
+        Status::InternalServerError
+    })?;
+
+    let now = Utc::now();
+    let horizon = now + Duration::days(*FEED_HORIZON_DAYS);
+    let mut holidays: HashMap<u32, HashSet<NaiveDate>> = HashMap::new();
+
+    let mut calendar = Component::new("VCALENDAR");
+    calendar.properties.push(Property::new("VERSION", "2.0"));
+    calendar.properties.push(Property::new("PRODID", "-//Reminder Bot//Reminders//EN"));
+    calendar.properties.push(Property::new("CALSCALE", "GREGORIAN"));
+    calendar.properties.push(Property::new(
+        "X-WR-CALNAME",
+        ics::escape_text(feed.guild_name.as_deref().unwrap_or("Reminders")),
+    ));
+    calendar.properties.push(Property::new("X-PUBLISHED-TTL", "PT1H"));
+
+    for reminder in reminders {
+        let business_days = BusinessDayPolicy::from(reminder.business_day_policy.as_str());
+        let mut working_days = Calendar::default();
+
+        if let Some(weekend_days) = reminder.weekend_days {
+            working_days.weekend_days = weekend_days;
+        }
+
+        match reminder.guild_id {
+            Some(guild_id) if business_days != BusinessDayPolicy::Ignore => {
+                if !holidays.contains_key(&guild_id) {
+                    let dates =
+                        sqlx::query!("SELECT `date` FROM holidays WHERE guild_id = ?", guild_id)
+                            .fetch_all(pool.inner())
+                            .await
+                            .map(|rows| rows.into_iter().map(|row| row.date).collect())
+                            .unwrap_or_default();
+
+                    holidays.insert(guild_id, dates);
+                }
+
+                working_days.holidays = holidays[&guild_id].clone();
+            }
+
+            _ => {}
+        }
+
+        let recurrence = Recurrence {
+            timezone: reminder.timezone.parse::<Tz>().unwrap_or(Tz::UTC),
+            policy: IntervalPolicy::from(reminder.interval_policy.as_str()),
+            seconds: reminder.interval_seconds,
+            days: reminder.interval_days,
+            months: reminder.interval_months,
+            business_days,
+            calendar: working_days,
+        };
+
+        let expires = reminder.expires.map(|expires| DateTime::<Utc>::from_utc(expires, Utc));
+        let description = ics::escape_text(if reminder.content.is_empty() {
+            &reminder.embed_description
+        } else {
+            &reminder.content
+        });
+
+        for (time, _) in recurrence
+            .occurrences(DateTime::from_utc(reminder.utc_time, Utc), reminder.shifted_from)
+            .take_while(|(time, _)| *time <= horizon && expires.map_or(true, |e| *time <= e))
+            .filter(|(time, _)| *time >= now)
+        {
+            if calendar.components.len() >= MAX_FEED_EVENTS {
+                break;
+            }
+
+            let mut event = Component::new("VEVENT");
+            event.properties.push(Property::new(
+                "UID",
+                format!("{}-{}@reminder-bot.com", reminder.uid, time.timestamp()),
+            ));
+            event.properties.push(Property::new("DTSTAMP", ics::format_utc(now.naive_utc())));
+            event.properties.push(Property::new("DTSTART", ics::format_utc(time.naive_utc())));
+            event.properties.push(Property::new("SUMMARY", ics::escape_text(&reminder.name)));
+            event.properties.push(Property::new("DESCRIPTION", description.as_str()));
+
+            calendar.components.push(event);
+        }
+    }
+
+    Ok((ContentType::Calendar, calendar.to_string()))
+}
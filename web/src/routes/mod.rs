@@ -1,4 +1,5 @@
 pub mod dashboard;
+pub mod feed;
 pub mod login;
 
 use std::collections::HashMap;
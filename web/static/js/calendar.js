@@ -0,0 +1,142 @@
+const $weekendDays = document.getElementById("weekendDays");
+const $holidayList = document.getElementById("holidayList");
+const $holidayUploader = document.getElementById("holidayUploader");
+
+function load_calendar(guild) {
+    fetch(`/dashboard/api/guild/${guild}/calendar`)
+        .then((response) => response.json())
+        .then((data) => {
+            if (data.error) {
+                show_error(data.error);
+                return;
+            }
+
+            $weekendDays.querySelectorAll("input").forEach((el) => {
+                el.checked = data.weekend.includes(el.value);
+            });
+
+            $holidayList.replaceChildren();
+            for (let holiday of data.holidays) {
+                let $item = document.createElement("li");
+                $item.textContent = holiday.name
+                    ? `${holiday.date}: ${holiday.name}`
+                    : holiday.date;
+                $holidayList.appendChild($item);
+            }
+        });
+}
+
+document.addEventListener("guildSwitched", (e) => {
+    load_calendar(e.detail.guild_id);
+});
+
+document.getElementById("save-weekend").addEventListener("click", () => {
+    let weekend = [...$weekendDays.querySelectorAll("input:checked")].map(
+        (el) => el.value
+    );
+
+    fetch(`/dashboard/api/guild/${guildId()}/calendar`, {
+        method: "PATCH",
+        headers: {
+            "Content-Type": "application/json",
+        },
+        body: JSON.stringify({ weekend: weekend }),
+    })
+        .then((response) => response.json())
+        .then((data) => {
+            if (data.error) {
+                show_error(data.error);
+            }
+        });
+});
+
+document.getElementById("import-holidays").addEventListener("click", () => {
+    $holidayUploader.click();
+});
+
+$holidayUploader.addEventListener("change", () => {
+    let guild = guildId();
+
+    new Promise((resolve) => {
+        let fileReader = new FileReader();
+        fileReader.onload = (e) => resolve(fileReader.result);
+        fileReader.readAsDataURL($holidayUploader.files[0]);
+    }).then((dataUrl) => {
+        fetch(`/dashboard/api/guild/${guild}/calendar/holidays`, {
+            method: "PUT",
+            headers: {
+                "Content-Type": "application/json",
+            },
+            body: JSON.stringify({ body: dataUrl.split(",")[1] }),
+        })
+            .then((response) => response.json())
+            .then((data) => {
+                $holidayUploader.value = "";
+
+                if (data.error) {
+                    show_error(data.error);
+                } else {
+                    for (let error of data.errors) show_error(error);
+                    load_calendar(guild);
+                }
+            });
+    });
+});
+
+document.getElementById("clear-holidays").addEventListener("click", () => {
+    let guild = guildId();
+
+    fetch(`/dashboard/api/guild/${guild}/calendar/holidays`, {
+        method: "DELETE",
+    })
+        .then((response) => response.json())
+        .then((data) => {
+            if (data.error) {
+                show_error(data.error);
+            } else {
+                load_calendar(guild);
+            }
+        });
+});
+
+function feed_endpoint(feed) {
+    return feed === "guild"
+        ? `/dashboard/api/guild/${guildId()}/feed`
+        : "/dashboard/api/user/feed";
+}
+
+function show_feed($feed, data) {
+    if (data.error) {
+        show_error(data.error);
+    } else {
+        $feed.querySelector("input.feed-url").value = data.path
+            ? window.location.origin + data.path
+            : "";
+    }
+}
+
+function load_feed($feed) {
+    fetch(feed_endpoint($feed.dataset["feed"]))
+        .then((response) => response.json())
+        .then((data) => show_feed($feed, data));
+}
+
+document.querySelectorAll("div.feed").forEach(($feed) => {
+    $feed.querySelector("button.regenerate-feed").addEventListener("click", () => {
+        fetch(feed_endpoint($feed.dataset["feed"]), { method: "POST" })
+            .then((response) => response.json())
+            .then((data) => show_feed($feed, data));
+    });
+
+    $feed.querySelector("button.revoke-feed").addEventListener("click", () => {
+        fetch(feed_endpoint($feed.dataset["feed"]), { method: "DELETE" })
+            .then((response) => response.json())
+            .then((data) => show_feed($feed, data));
+    });
+});
+
+document.addEventListener("guildSwitched", () => {
+    load_feed(document.querySelector('div.feed[data-feed="guild"]'));
+});
+
+load_feed(document.querySelector('div.feed[data-feed="user"]'));
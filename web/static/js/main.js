@@ -30,6 +30,8 @@ let mentions = new Tribute({
 });
 
 let globalPatreon = false;
+let guildTimezone = "UTC";
+let channelTimezones = {};
 let guildPatreon = false;
 
 function guildId() {
@@ -90,6 +92,28 @@ async function fetch_patreon(guild_id) {
         });
 }
 
+async function fetch_timezone(guild_id) {
+    await fetch(`/dashboard/api/guild/${guild_id}/timezone`)
+        .then((response) => response.json())
+        .then((data) => {
+            if (data.error) {
+                show_error(data.error);
+            } else {
+                guildTimezone = data.timezone;
+                channelTimezones = data.channels;
+                update_bot_timezone();
+            }
+        });
+}
+
+// The bot reads times in the timezone of the channel that the reminder is for, if it has one
+function update_bot_timezone() {
+    const channel = $createReminder.querySelector("select.channel-selector").value;
+
+    botTimezone = channelTimezones[channel] || guildTimezone;
+    update_times();
+}
+
 function fetch_roles(guild_id) {
     fetch(`/dashboard/api/guild/${guild_id}/roles`)
         .then((response) => response.json())
@@ -150,6 +174,17 @@ async function fetch_channels(guild_id) {
                 }
             } else {
                 channels = data;
+
+                const webhookErrors = channels
+                    .filter((channel) => channel.webhook_error)
+                    .map(
+                        (channel) =>
+                            `Reminders in #${channel.name} will be sent without their username and avatar, because ${channel.webhook_error}.`
+                    );
+
+                if (webhookErrors.length > 0) {
+                    show_error(webhookErrors.join(" "));
+                }
             }
         })
         .then(() => {
@@ -306,6 +341,8 @@ async function serialize_reminder(node, mode) {
         attachment: attachment,
         attachment_name: attachment_name,
         avatar: has_source(node.querySelector("img.discord-avatar").src),
+        business_day_policy: node.querySelector('select[name="business_day_policy"]')
+            .value,
         channel: node.querySelector("select.channel-selector").value,
         content: content,
         embed_author_url: embed_author_url,
@@ -322,6 +359,7 @@ async function serialize_reminder(node, mode) {
         interval_seconds: mode !== "template" ? interval.seconds : null,
         interval_days: mode !== "template" ? interval.days : null,
         interval_months: mode !== "template" ? interval.months : null,
+        interval_policy: node.querySelector('select[name="interval_policy"]').value,
         name: node.querySelector('input[name="name"]').value,
         tts: node.querySelector('input[name="tts"]').checked,
         username: node.querySelector('input[name="username"]').value,
@@ -416,6 +454,7 @@ document.addEventListener("guildSwitched", async (e) => {
             .forEach((el) => el.classList.remove("is-locked"));
     }
 
+    await fetch_timezone(e.detail.guild_id);
     fetch_roles(e.detail.guild_id);
     fetch_templates(e.detail.guild_id);
     await fetch_channels(e.detail.guild_id);
@@ -435,8 +474,13 @@ document.addEventListener("guildSwitched", async (e) => {
 
 document.addEventListener("channelsLoaded", () => {
     document.querySelectorAll("select.channel-selector").forEach(set_channels);
+    update_bot_timezone();
 });
 
+$createReminder
+    .querySelector("select.channel-selector")
+    .addEventListener("change", update_bot_timezone);
+
 document.addEventListener("remindersLoaded", (event) => {
     const guild = guildId();
 
@@ -592,7 +636,13 @@ document.addEventListener("DOMContentLoaded", () => {
             if (data.error) {
                 show_error(data.error);
             } else {
-                if (data.timezone !== null) botTimezone = data.timezone;
+                // a server's timezone is taken from `fetch_timezone` instead
+                if (
+                    data.timezone !== null &&
+                    document.querySelector(".guildList a.is-active") === null
+                ) {
+                    botTimezone = data.timezone;
+                }
 
                 globalPatreon = data.patreon;
 
@@ -683,8 +733,49 @@ function has_source(string) {
     }
 }
 
+function restore_backup(file) {
+    file.text().then((text) => {
+        fetch(`/dashboard/api/guild/${guildId()}/backup`, {
+            method: "PUT",
+            headers: {
+                "Content-Type": "application/json",
+            },
+            body: text,
+        })
+            .then((response) => response.json())
+            .then((data) => {
+                if (data.error) {
+                    show_error(data.error);
+                    return;
+                }
+
+                for (let error of data.errors) show_error(error);
+
+                const restored = Object.entries(data.restored)
+                    .map(([kind, count]) => `${count} ${kind}`)
+                    .join(", ");
+                show_error(`Restored ${restored || "nothing"}`);
+            });
+    });
+}
+
 $uploader.addEventListener("change", (ev) => {
-    const urlTail = document.querySelector('input[name="exportSelect"]:checked').value;
+    let urlTail = document.querySelector('input[name="exportSelect"]:checked').value;
+
+    if (urlTail === "backup") {
+        restore_backup($uploader.files[0]);
+        return;
+    }
+
+    // events from other calendars go to the channel chosen for new reminders
+    if (urlTail === "reminders.ics") {
+        urlTail += `?channel=${
+            $createReminder.querySelector("select.channel-selector").value
+        }`;
+    }
+    if (urlTail === "reminders" && document.querySelector("input#import-dry-run").checked) {
+        urlTail += "?dry_run=true";
+    }
 
     new Promise((resolve) => {
         let fileReader = new FileReader();
@@ -694,9 +785,24 @@ $uploader.addEventListener("change", (ev) => {
         fetch(`/dashboard/api/guild/${guildId()}/export/${urlTail}`, {
             method: "PUT",
             body: JSON.stringify({ body: dataUrl.split(",")[1] }),
-        }).then(() => {
-            delete $uploader.files[0];
-        });
+        })
+            .then((response) => response.json())
+            .then((data) => {
+                delete $uploader.files[0];
+
+                if (data.error) {
+                    show_error(data.error);
+                } else if (data.errors) {
+                    for (let error of data.errors) show_error(error);
+                } else if (data.rows) {
+                    for (let row of data.rows.filter((row) => row.error)) {
+                        show_error(`Row ${row.row} (${row.channel || "no channel"}): ${row.error}`);
+                    }
+                    if (data.dry_run && data.failed === 0) {
+                        show_error(`All ${data.rows.length} rows can be imported`);
+                    }
+                }
+            });
     });
 });
 
@@ -707,12 +813,40 @@ $importBtn.addEventListener("click", () => {
 $exportBtn.addEventListener("click", () => {
     const urlTail = document.querySelector('input[name="exportSelect"]:checked').value;
 
+    if (urlTail === "backup") {
+        fetch(`/dashboard/api/guild/${guildId()}/backup`)
+            .then((response) => response.json())
+            .then((data) => {
+                if (data.error) {
+                    show_error(data.error);
+                    return;
+                }
+
+                $downloader.download = `backup-${guildId()}.json`;
+                $downloader.href =
+                    "data:application/json;charset=utf-8," +
+                    encodeURIComponent(JSON.stringify(data, null, 2));
+                $downloader.click();
+            });
+        return;
+    }
+
     fetch(`/dashboard/api/guild/${guildId()}/export/${urlTail}`)
         .then((response) => response.json())
         .then((data) => {
+            if (data.error) {
+                show_error(data.error);
+                return;
+            }
+
+            $downloader.download = urlTail.endsWith(".ics") ? "export.ics" : "export.csv";
             $downloader.href =
                 "data:text/plain;charset=utf-8," + encodeURIComponent(data.body);
             $downloader.click();
+
+            if (data.errors) {
+                for (let error of data.errors) show_error(error);
+            }
         });
 });
 